@@ -0,0 +1,83 @@
+/// Something the player can do from the HUD, which has to recharge afterwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ability {
+    /// Kills every enemy in a clicked area
+    Airstrike,
+    /// Stops every enemy moving for a few seconds
+    Freeze,
+    /// Gives the player some money straight away
+    EmergencyCash,
+}
+
+impl Ability {
+    pub const ALL: [Ability; 3] = [Ability::Airstrike, Ability::Freeze, Ability::EmergencyCash];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ability::Airstrike => "Airstrike",
+            Ability::Freeze => "Freeze",
+            Ability::EmergencyCash => "Emergency cash",
+        }
+    }
+
+    /// How many steps the ability takes to recharge after being used
+    pub fn cooldown(&self) -> usize {
+        match self {
+            Ability::Airstrike => 60 * 20,
+            Ability::Freeze => 60 * 30,
+            Ability::EmergencyCash => 60 * 45,
+        }
+    }
+
+    /// The index of this ability in `ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// The radius of the area an airstrike kills enemies in
+pub const AIRSTRIKE_RADIUS: f32 = 150.0;
+/// How many steps a freeze lasts for
+pub const FREEZE_STEPS: usize = 60 * 3;
+/// The money given by emergency cash
+pub const EMERGENCY_CASH: u64 = 30;
+
+/// How long each ability has left to recharge, and how long enemies are frozen for
+#[derive(Debug, Clone, Default)]
+pub struct Abilities {
+    /// Steps until each ability can be used again, in the same order as `Ability::ALL`
+    cooldowns: [usize; Ability::ALL.len()],
+    /// Steps until frozen enemies can move again
+    frozen: usize,
+}
+
+impl Abilities {
+    /// Moves every cooldown and the freeze on by one step
+    pub fn update(&mut self) {
+        for cooldown in self.cooldowns.iter_mut() {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+        self.frozen = self.frozen.saturating_sub(1);
+    }
+
+    pub fn is_ready(&self, ability: Ability) -> bool {
+        self.cooldowns[ability.index()] == 0
+    }
+
+    /// How much of its cooldown an ability has left, from 0 (ready) to 1 (just used)
+    pub fn cooldown_left(&self, ability: Ability) -> f32 {
+        self.cooldowns[ability.index()] as f32 / ability.cooldown() as f32
+    }
+
+    /// Starts an ability's cooldown. A freeze also starts freezing the enemies.
+    pub fn use_ability(&mut self, ability: Ability) {
+        self.cooldowns[ability.index()] = ability.cooldown();
+        if ability == Ability::Freeze {
+            self.frozen = FREEZE_STEPS;
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen > 0
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    f32::consts::PI,
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
+
+use crate::vector::Vector;
+
+/// A direction in radians, always kept between -PI and PI.
+/// Spans like a field of view can be wider than that, so they stay as raw f32's.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Angle(f32);
+
+impl Angle {
+    pub fn from_radians(radians: f32) -> Self {
+        Self(Self::normalise(radians))
+    }
+
+    /// The direction a vector points in
+    pub fn of(vector: Vector) -> Self {
+        Self(vector.angle())
+    }
+
+    pub fn radians(&self) -> f32 {
+        self.0
+    }
+
+    /// Wraps any angle into -PI to PI
+    fn normalise(radians: f32) -> f32 {
+        let wrapped = (radians + PI).rem_euclid(2.0 * PI) - PI;
+        // rem_euclid can round up to exactly 2 PI
+        if wrapped >= PI {
+            wrapped - 2.0 * PI
+        } else {
+            wrapped
+        }
+    }
+
+    /// The signed shortest turn from self to `other`, which is positive if it's anticlockwise
+    pub fn distance_to(&self, other: Angle) -> f32 {
+        Self::normalise(other.0 - self.0)
+    }
+
+    /// Turns `t` of the way from self to `other`, the short way round
+    pub fn lerp(&self, other: Angle, t: f32) -> Angle {
+        *self + self.distance_to(other) * t
+    }
+
+    /// A vector of length 1 pointing in this direction
+    pub fn direction(&self) -> Vector {
+        Vector::from_polar(self.0, 1.0)
+    }
+}
+
+impl Add<f32> for Angle {
+    type Output = Self;
+    fn add(self, rhs: f32) -> Self {
+        Self::from_radians(self.0 + rhs)
+    }
+}
+
+impl AddAssign<f32> for Angle {
+    fn add_assign(&mut self, rhs: f32) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<f32> for Angle {
+    type Output = Self;
+    fn sub(self, rhs: f32) -> Self {
+        Self::from_radians(self.0 - rhs)
+    }
+}
+
+impl SubAssign<f32> for Angle {
+    fn sub_assign(&mut self, rhs: f32) {
+        *self = *self - rhs;
+    }
+}
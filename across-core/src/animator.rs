@@ -0,0 +1,41 @@
+use crate::vector::Vector;
+
+/// Where something is in its animations, and which way it's facing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Animator {
+    /// How many steps the current animation has played for
+    steps: usize,
+    dying: bool,
+    /// Sprites face right, so are flipped when moving left
+    facing_left: bool,
+}
+
+impl Animator {
+    /// Moves the animation on by a step, turning to face the way it's moving
+    pub fn update(&mut self, direction: Option<Vector>) {
+        self.steps += 1;
+        match direction {
+            Some(direction) if direction.x.abs() > 0.01 => self.facing_left = direction.x < 0.0,
+            _ => (),
+        }
+    }
+
+    /// Switches to the death animation, from its first frame
+    pub fn die(&mut self) {
+        self.steps = 0;
+        self.dying = true;
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Whether the death animation is playing, rather than the walk
+    pub fn dying(&self) -> bool {
+        self.dying
+    }
+
+    pub fn facing_left(&self) -> bool {
+        self.facing_left
+    }
+}
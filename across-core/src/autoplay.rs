@@ -0,0 +1,200 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cli::Difficulty,
+    kill_reward,
+    map::Map,
+    path::Web,
+    registry::{TowerKind, TowerRegistry},
+    vec2d,
+    vector::Vector,
+    wave::{EnemyMix, SpawnEvent, SpawnScheduler},
+    world::World,
+    SCREEN_HEIGHT, SCREEN_WIDTH, STARTING_MONEY,
+};
+
+/// Games are stopped after this many steps, in case a wave never finishes
+const MAX_STEPS: usize = 60 * 60 * 60;
+/// How much of an edge a tower has to cover to be worth buying, normalised [0-1]
+const MIN_COVERAGE: f32 = 0.3;
+/// How far to the side of an edge towers are tried, as a multiple of their radius
+const PLACEMENT_OFFSETS: [f32; 4] = [2.0, -2.0, 4.0, -4.0];
+/// Where along an edge towers are tried, normalised [0-1]
+const PLACEMENT_FRACTIONS: [f32; 3] = [0.5, 0.25, 0.75];
+
+/// How a game played by the autoplayer went
+#[derive(Debug, Clone, Copy)]
+pub struct AutoplayReport {
+    pub seed: u64,
+    /// The last round which was started
+    pub round: usize,
+    /// Whether every wave was cleared
+    pub won: bool,
+    pub towers: usize,
+    pub lives: u32,
+}
+
+impl std::fmt::Display for AutoplayReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "seed {}: {} on round {} with {} towers and {} lives",
+            self.seed,
+            if self.won { "won" } else { "lost" },
+            self.round,
+            self.towers,
+            self.lives
+        )
+    }
+}
+
+/// Plays a whole game without drawing anything, to check the game can still be beaten.
+/// Between rounds it keeps buying the cheapest tower which covers the edge that most
+/// needs covering, until it runs out of money or places to put towers.
+pub struct Autoplayer {
+    registry: TowerRegistry,
+    web: Web,
+    world: World,
+    scheduler: SpawnScheduler,
+    mix: EnemyMix,
+    rng: StdRng,
+    money: u64,
+    lives: u32,
+}
+
+impl Autoplayer {
+    /// Returns None if the map doesn't build a valid web
+    pub fn new(map: &Map, difficulty: Difficulty, seed: u64) -> Option<Self> {
+        Some(Self {
+            registry: TowerRegistry::default(),
+            web: map.build_web().ok()?,
+            world: World::new(),
+            scheduler: SpawnScheduler::new(map.waves()),
+            mix: EnemyMix::default(),
+            rng: StdRng::seed_from_u64(seed),
+            money: STARTING_MONEY,
+            lives: difficulty.lives(),
+        })
+    }
+
+    /// Plays until every wave is cleared or every life is lost
+    pub fn play(mut self, seed: u64) -> AutoplayReport {
+        self.buy_towers();
+        let mut won = true;
+        for _ in 0..MAX_STEPS {
+            let round = self.scheduler.round();
+            let event = self.scheduler.update(self.world.enemies.len());
+            for kind in event.spawns() {
+                let kind = kind.unwrap_or_else(|| self.mix.pick(round, &mut self.rng));
+                let enemy = kind.spawn(&self.web);
+                self.world
+                    .enemies
+                    .push(enemy.with_modifier(self.scheduler.modifier()));
+            }
+            if event == SpawnEvent::WaveCleared {
+                self.scheduler.roll_modifier(&mut self.rng);
+                self.buy_towers();
+                self.web.reset_traversals();
+            }
+            let escaped = self.world.movement_system(&mut self.web, false);
+            self.lives = self.lives.saturating_sub(escaped as u32);
+            if self.lives == 0 {
+                won = false;
+                break;
+            }
+            if !self.world.siege_system().is_empty() {
+                self.web.recalculate_weights(&self.world.towers);
+            }
+            let kills = self.world.combat_system(
+                vec2d![SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
+                None,
+                self.web.obstacles(),
+                &mut self.rng,
+            );
+            self.money += kill_reward(self.scheduler.modifier()) * kills as u64;
+            if self.scheduler.is_finished() {
+                break;
+            }
+        }
+        AutoplayReport {
+            seed,
+            round: self.scheduler.round(),
+            won: won && self.scheduler.is_finished(),
+            towers: self.world.towers.len(),
+            lives: self.lives,
+        }
+    }
+
+    /// How much each edge on the route needs covering: how many enemies walked along it
+    /// last round (plus one, so the first round has something to go on), times how much of
+    /// its length isn't covered yet.
+    /// Returns the edges on the route, most in need first.
+    fn edges_by_need(&self) -> Vec<usize> {
+        let pathfinder = self.web.pathfinder();
+        let need = |connection: usize| {
+            (self.web.traversals()[connection] + 1) as f32 * pathfinder.length(connection)
+                / pathfinder.weights()[connection].multiplier()
+        };
+        let mut edges = self.web.route().connections().clone();
+        edges.sort_unstable();
+        edges.dedup();
+        edges.sort_by(|&a, &b| need(b).total_cmp(&need(a)));
+        edges
+    }
+
+    /// Keeps buying towers until none can be afforded or placed
+    fn buy_towers(&mut self) {
+        while self.buy_tower() {}
+    }
+
+    /// Buys the cheapest tower which covers the edge most in need of covering.
+    /// Returns whether a tower was bought.
+    fn buy_tower(&mut self) -> bool {
+        let mut kinds: Vec<&TowerKind> = self.registry.kinds().iter().collect();
+        kinds.sort_by_key(|kind| kind.price);
+        let money = self.money;
+        for edge in self.edges_by_need() {
+            let points = self.web.connection_points(edge);
+            let (a, b) = (points[0], points[points.len() - 1]);
+            if (b - a).sqr_length() == 0.0 {
+                continue;
+            }
+            let along = (b - a).normalised();
+            let side = vec2d![-along.y, along.x];
+            for kind in kinds.iter().filter(|kind| kind.price <= money) {
+                for fraction in PLACEMENT_FRACTIONS {
+                    for offset in PLACEMENT_OFFSETS {
+                        let radius = (kind.spawn)(a).radius();
+                        let position = a + (b - a) * fraction + side * offset * radius;
+                        let tower = (kind.spawn)(position);
+                        let effective = !tower.is_wall()
+                            && tower.danger() > 0.0
+                            && points
+                                .windows(2)
+                                .map(|x| tower.range().edge_coverage(x[0], x[1]))
+                                .fold(0.0, f32::max)
+                                >= MIN_COVERAGE;
+                        let overlaps = self.world.towers.iter().any(|other| {
+                            other.position().distance(position) < other.radius() + tower.radius()
+                        });
+                        if !effective || overlaps {
+                            continue;
+                        }
+                        self.world.towers.push(tower);
+                        if self.web.recalculate_weights(&self.world.towers) {
+                            self.money -= kind.price;
+                            return true;
+                        }
+                        self.world.towers.pop();
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Plays a game on a map from a seed, or returns None if the map is invalid
+pub fn autoplay(map: &Map, difficulty: Difficulty, seed: u64) -> Option<AutoplayReport> {
+    Some(Autoplayer::new(map, difficulty, seed)?.play(seed))
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{polygon::Polygon, tower::tower::Tower};
+
+/// Where towers can be built on a map, and how many of them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildRules {
+    /// If there are any, towers can only be built inside one of these
+    pub zones: Vec<Polygon>,
+    /// Towers can never be built inside these
+    pub forbidden: Vec<Polygon>,
+    /// The most towers which can be built
+    pub limit: Option<usize>,
+    /// The most towers of a kind which can be built, by the kind's name
+    pub kind_limits: Vec<(String, usize)>,
+}
+
+impl BuildRules {
+    /// Why a tower can't be built alongside the towers already placed, as a message for
+    /// the player, or None if it can be
+    pub fn refusal(&self, tower: &dyn Tower, towers: &[Box<dyn Tower>]) -> Option<&'static str> {
+        let position = tower.position();
+        if self.forbidden.iter().any(|zone| zone.contains(position)) {
+            return Some("Towers can't be built there");
+        }
+        if !self.zones.is_empty() && !self.zones.iter().any(|zone| zone.contains(position)) {
+            return Some("Towers can only be built in the build zones");
+        }
+        if self.limit.map_or(false, |limit| towers.len() >= limit) {
+            return Some("No more towers can be built on this map");
+        }
+        let same_kind = towers.iter().filter(|x| x.name() == tower.name()).count();
+        if self
+            .kind_limits
+            .iter()
+            .any(|(name, limit)| name == tower.name() && same_kind >= *limit)
+        {
+            return Some("No more of that tower can be built on this map");
+        }
+        None
+    }
+}
@@ -0,0 +1,567 @@
+pub mod bullet {
+    use std::f32::consts::PI;
+
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        enemy::enemy::{Enemy, EnemyId},
+        spatial::SpatialHash,
+        tower::tower::{Tower, TowerId},
+        vec2d,
+        vector::Vector,
+        Alive, Dead, Updated,
+    };
+
+    pub trait BulletTrait: std::fmt::Debug {
+        /// create a new bullet
+        fn spawn(tower: &impl Tower, target: Vector) -> Box<dyn BulletTrait>
+        where
+            Self: Sized;
+        /// The tower which shot this bullet, if any
+        fn tower(&self) -> Option<TowerId>;
+        /// Killed enemies are removed from the grid.
+        /// `rng` decides which hits are critical.
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> BulletUpdate;
+        /// What the bullet looks like, for drawing it
+        fn look(&self) -> BulletLook;
+    }
+
+    /// What a bullet looks like at the moment, which is everything a front end needs to
+    /// know to draw it
+    #[derive(Debug, Clone, Copy)]
+    pub enum BulletLook {
+        Ball {
+            position: Vector,
+            radius: f32,
+        },
+        /// A missile, which also shows where it's going to land
+        Missile {
+            position: Vector,
+            target: Vector,
+        },
+        /// A streak from `tail` to `head`, pointing the way the bullet is going
+        Streak {
+            tail: Vector,
+            head: Vector,
+            width: f32,
+        },
+    }
+
+    /// What happened to a bullet in one step
+    #[derive(Debug, Default)]
+    pub struct BulletUpdate {
+        /// Whether the bullet is still flying
+        pub alive: bool,
+        /// Every enemy the bullet hit
+        pub hits: Vec<Hit>,
+        /// New bullets the bullet has split into, which start moving next step
+        pub spawned: Vec<Box<dyn BulletTrait>>,
+    }
+
+    /// How much damage a normal bullet does, which is enough to kill most enemies
+    const BULLET_DAMAGE: f32 = 1.0;
+
+    /// How likely a tower's hits are to be critical, and how many times as much damage
+    /// critical hits do
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct Crit {
+        /// Normalised [0-1]
+        pub chance: f32,
+        pub multiplier: f32,
+    }
+
+    impl Crit {
+        /// Never critical
+        pub const NONE: Crit = Crit {
+            chance: 0.0,
+            multiplier: 1.0,
+        };
+
+        /// What a tower starts with
+        pub const BASE: Crit = Crit {
+            chance: 0.05,
+            multiplier: 2.0,
+        };
+
+        /// Whether a hit is critical
+        fn roll(&self, rng: &mut StdRng) -> bool {
+            self.chance > 0.0 && rng.gen::<f32>() < self.chance
+        }
+    }
+
+    /// One enemy being hit by a tower
+    #[derive(Debug, Clone, Copy)]
+    pub struct Hit {
+        /// Where the enemy was when it was hit
+        pub position: Vector,
+        /// The health the enemy lost, normalised [0-1]
+        pub damage: f32,
+        /// The damage left over once the enemy was killed, which piercing bullets carry on
+        /// to the next enemy
+        pub overkill: f32,
+        pub critical: bool,
+        pub killed: bool,
+    }
+
+    /// Damages an enemy, which might be a critical hit, and removes it from the grid if it
+    /// dies. The hit is also recorded in the grid, so it can be shown to the player.
+    pub fn hit(
+        enemies: &mut [Enemy<Alive>],
+        grid: &mut SpatialHash,
+        index: usize,
+        damage: f32,
+        crit: Crit,
+        rng: &mut StdRng,
+    ) -> Hit {
+        let enemy = &mut enemies[index];
+        let critical = crit.roll(rng);
+        let damage = if critical {
+            damage * crit.multiplier
+        } else {
+            damage
+        };
+        let position = enemy.position();
+        let done = enemy.damage(damage);
+        let hit = Hit {
+            position,
+            damage: done.lost,
+            overkill: done.overkill,
+            critical,
+            killed: enemy.health() <= 0.0,
+        };
+        if hit.killed {
+            grid.remove(index);
+        }
+        grid.record_hit(hit);
+        hit
+    }
+
+    /// Hits every enemy touching a circle, returning the hits
+    fn hit_all_touching(
+        enemies: &mut [Enemy<Alive>],
+        grid: &mut SpatialHash,
+        position: Vector,
+        radius: f32,
+        crit: Crit,
+        rng: &mut StdRng,
+    ) -> Vec<Hit> {
+        let touching: Vec<usize> = grid
+            .query(position, radius)
+            .into_iter()
+            .filter(|&i| enemies[i].collides(position, radius))
+            .collect();
+        touching
+            .into_iter()
+            .map(|i| hit(enemies, grid, i, BULLET_DAMAGE, crit, rng))
+            .collect()
+    }
+
+    /// A lot of this is copied from enemy.rs. Is there a way to reduce repetition?
+    #[derive(Debug)]
+    pub struct Bullet<State> {
+        bullet: Box<dyn BulletTrait>,
+        state: std::marker::PhantomData<State>,
+    }
+
+    impl Bullet<Alive> {
+        pub fn new(bullet: Box<dyn BulletTrait>) -> Bullet<Alive> {
+            Bullet {
+                bullet,
+                state: std::marker::PhantomData::<Alive>,
+            }
+        }
+
+        /// Also returns the enemies the bullet hit, and the bullets it split into
+        pub fn update(
+            mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> (
+            Updated<Bullet<Alive>, Bullet<Dead>>,
+            Vec<Hit>,
+            Vec<Bullet<Alive>>,
+        ) {
+            let BulletUpdate {
+                alive,
+                hits,
+                spawned,
+            } = self.bullet.update(enemies, grid, bounds, rng);
+            let spawned = spawned.into_iter().map(Bullet::new).collect();
+            if alive {
+                (Updated::Alive(Bullet::new(self.bullet)), hits, spawned)
+            } else {
+                (
+                    Updated::Dead(Bullet {
+                        bullet: self.bullet,
+                        state: std::marker::PhantomData::<Dead>,
+                    }),
+                    hits,
+                    spawned,
+                )
+            }
+        }
+
+        /// Returns the bullets which are still alive, including any new ones bullets split
+        /// into, and every hit
+        pub fn update_all(
+            mut bullets: Vec<Bullet<Alive>>,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> (Vec<Bullet<Alive>>, Vec<Hit>) {
+            let mut new_bullets = Vec::with_capacity(bullets.len());
+            let mut hits = vec![];
+            while let Some(bullet) = bullets.pop() {
+                let (updated, new_hits, spawned) = bullet.update(enemies, grid, bounds, rng);
+                if let Updated::Alive(bullet) = updated {
+                    new_bullets.push(bullet);
+                }
+                new_bullets.extend(spawned);
+                hits.extend(new_hits);
+            }
+            (new_bullets, hits)
+        }
+
+        /// Just for testing
+        pub fn debug_spawn(position: Vector, target: Vector) -> Bullet<Alive> {
+            Bullet {
+                bullet: Box::new(Projectile {
+                    tower: None,
+                    position,
+                    radius: 10.0f32,
+                    velocity: (target - position).normalised() * 3.0,
+                    steps_left: None,
+                    crit: Crit::NONE,
+                }),
+                state: std::marker::PhantomData::<Alive>,
+            }
+        }
+
+        pub fn look(&self) -> BulletLook {
+            self.bullet.look()
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Projectile {
+        tower: Option<TowerId>,
+        position: Vector,
+        velocity: Vector,
+        radius: f32,
+        /// How many more steps the projectile flies for, if it doesn't fly until it
+        /// leaves the screen
+        steps_left: Option<usize>,
+        crit: Crit,
+    }
+
+    impl BulletTrait for Projectile {
+        fn spawn(tower: &impl Tower, target: Vector) -> Box<dyn BulletTrait>
+        where
+            Self: Sized,
+        {
+            Box::new(Self {
+                tower: Some(tower.id()),
+                position: tower.position(),
+                velocity: (target - tower.position()).normalised() * 3.0,
+                radius: 5.0,
+                steps_left: None,
+                crit: tower.crit().unwrap_or(Crit::NONE),
+            }) as Box<dyn BulletTrait>
+        }
+
+        fn tower(&self) -> Option<TowerId> {
+            self.tower
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> BulletUpdate {
+            self.position += self.velocity;
+            self.steps_left = self.steps_left.map(|x| x.saturating_sub(1));
+
+            let hits = hit_all_touching(enemies, grid, self.position, self.radius, self.crit, rng);
+
+            let alive = hits.is_empty()
+                && self.steps_left != Some(0)
+                && self.position.x + self.radius >= 0.0
+                && self.position.y + self.radius >= 0.0
+                && self.position.x - self.radius <= bounds.x
+                && self.position.y - self.radius <= bounds.y;
+            BulletUpdate {
+                alive,
+                hits,
+                spawned: vec![],
+            }
+        }
+
+        fn look(&self) -> BulletLook {
+            BulletLook::Ball {
+                position: self.position,
+                radius: self.radius,
+            }
+        }
+    }
+
+    /// How many bomblets a missile splits into
+    const BOMBLETS: usize = 6;
+    /// How many steps bomblets fly for before they fizzle out
+    const BOMBLET_STEPS: usize = 20;
+
+    /// Flies to where its target was when it was shot, without hitting anything on the
+    /// way, and then splits into bomblets which spread out in every direction
+    #[derive(Debug, Clone)]
+    pub struct Missile {
+        tower: Option<TowerId>,
+        position: Vector,
+        target: Vector,
+        speed: f32,
+        /// Passed on to the bomblets
+        crit: Crit,
+    }
+
+    impl BulletTrait for Missile {
+        fn spawn(tower: &impl Tower, target: Vector) -> Box<dyn BulletTrait>
+        where
+            Self: Sized,
+        {
+            Box::new(Self {
+                tower: Some(tower.id()),
+                position: tower.position(),
+                target,
+                speed: 2.0,
+                crit: tower.crit().unwrap_or(Crit::NONE),
+            }) as Box<dyn BulletTrait>
+        }
+
+        fn tower(&self) -> Option<TowerId> {
+            self.tower
+        }
+
+        fn update(
+            &mut self,
+            _enemies: &mut [Enemy<Alive>],
+            _grid: &mut SpatialHash,
+            _bounds: Vector,
+            _rng: &mut StdRng,
+        ) -> BulletUpdate {
+            let offset = self.target - self.position;
+            if offset.length() > self.speed {
+                self.position += offset.normalised() * self.speed;
+                return BulletUpdate {
+                    alive: true,
+                    ..Default::default()
+                };
+            }
+            // arrived, so split
+            let spawned = (0..BOMBLETS)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as f32 / BOMBLETS as f32;
+                    Box::new(Projectile {
+                        tower: self.tower,
+                        position: self.target,
+                        velocity: vec2d![angle.cos(), angle.sin()] * 3.0,
+                        radius: 4.0,
+                        steps_left: Some(BOMBLET_STEPS),
+                        crit: self.crit,
+                    }) as Box<dyn BulletTrait>
+                })
+                .collect();
+            BulletUpdate {
+                alive: false,
+                hits: vec![],
+                spawned,
+            }
+        }
+
+        fn look(&self) -> BulletLook {
+            BulletLook::Missile {
+                position: self.position,
+                target: self.target,
+            }
+        }
+    }
+
+    /// How many times a bouncer can bounce off the edges of the screen
+    const MAX_BOUNCES: usize = 3;
+
+    /// Like a projectile, but bounces off the edges of the screen a few times before it
+    /// can leave
+    #[derive(Debug, Clone)]
+    pub struct Bouncer {
+        tower: Option<TowerId>,
+        position: Vector,
+        velocity: Vector,
+        radius: f32,
+        bounces_left: usize,
+        crit: Crit,
+    }
+
+    impl BulletTrait for Bouncer {
+        fn spawn(tower: &impl Tower, target: Vector) -> Box<dyn BulletTrait>
+        where
+            Self: Sized,
+        {
+            Box::new(Self {
+                tower: Some(tower.id()),
+                position: tower.position(),
+                velocity: (target - tower.position()).normalised() * 4.0,
+                radius: 4.0,
+                bounces_left: MAX_BOUNCES,
+                crit: tower.crit().unwrap_or(Crit::NONE),
+            }) as Box<dyn BulletTrait>
+        }
+
+        fn tower(&self) -> Option<TowerId> {
+            self.tower
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> BulletUpdate {
+            self.position += self.velocity;
+
+            // only bounce while heading out, so it can't get stuck on an edge
+            let edges = [
+                (self.position.x - self.radius < 0.0, vec2d![1.0, 0.0]),
+                (self.position.y - self.radius < 0.0, vec2d![0.0, 1.0]),
+                (self.position.x + self.radius > bounds.x, vec2d![-1.0, 0.0]),
+                (self.position.y + self.radius > bounds.y, vec2d![0.0, -1.0]),
+            ];
+            for (past, normal) in edges {
+                if past && self.bounces_left > 0 && self.velocity.dot(normal) < 0.0 {
+                    self.velocity = self.velocity.reflect(normal);
+                    self.bounces_left -= 1;
+                }
+            }
+
+            let hits = hit_all_touching(enemies, grid, self.position, self.radius, self.crit, rng);
+
+            let alive = hits.is_empty()
+                && self.position.x + self.radius >= 0.0
+                && self.position.y + self.radius >= 0.0
+                && self.position.x - self.radius <= bounds.x
+                && self.position.y - self.radius <= bounds.y;
+            BulletUpdate {
+                alive,
+                hits,
+                spawned: vec![],
+            }
+        }
+
+        fn look(&self) -> BulletLook {
+            BulletLook::Ball {
+                position: self.position,
+                radius: self.radius,
+            }
+        }
+    }
+
+    /// How much damage a piercing bullet starts with
+    const PIERCER_DAMAGE: f32 = 3.0;
+
+    /// Goes through every enemy it kills, carrying on with whatever damage is left over,
+    /// until an enemy survives it or it runs out
+    #[derive(Debug, Clone)]
+    pub struct Piercer {
+        tower: Option<TowerId>,
+        position: Vector,
+        velocity: Vector,
+        radius: f32,
+        /// The damage it has left to do
+        damage: f32,
+        /// The enemies it's already gone through, so it doesn't hit them twice
+        pierced: Vec<EnemyId>,
+        crit: Crit,
+    }
+
+    impl BulletTrait for Piercer {
+        fn spawn(tower: &impl Tower, target: Vector) -> Box<dyn BulletTrait>
+        where
+            Self: Sized,
+        {
+            Box::new(Self {
+                tower: Some(tower.id()),
+                position: tower.position(),
+                velocity: (target - tower.position()).normalised() * 5.0,
+                radius: 4.0,
+                damage: PIERCER_DAMAGE,
+                pierced: vec![],
+                crit: tower.crit().unwrap_or(Crit::NONE),
+            }) as Box<dyn BulletTrait>
+        }
+
+        fn tower(&self) -> Option<TowerId> {
+            self.tower
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            rng: &mut StdRng,
+        ) -> BulletUpdate {
+            self.position += self.velocity;
+
+            let touching: Vec<usize> = grid
+                .query(self.position, self.radius)
+                .into_iter()
+                .filter(|&i| enemies[i].collides(self.position, self.radius))
+                .filter(|&i| !self.pierced.contains(&enemies[i].id()))
+                .collect();
+            let mut hits = vec![];
+            for i in touching {
+                if self.damage <= 0.0 {
+                    break;
+                }
+                self.pierced.push(enemies[i].id());
+                let hit = hit(enemies, grid, i, self.damage, self.crit, rng);
+                // the next enemy rolls for its own crit, so the multiplier is taken back
+                // off what carries on rather than being applied again on top
+                self.damage = match (hit.killed, hit.critical) {
+                    (false, _) => 0.0,
+                    (true, false) => hit.overkill,
+                    (true, true) => hit.overkill / self.crit.multiplier,
+                };
+                hits.push(hit);
+            }
+
+            let alive = self.damage > 0.0
+                && self.position.x + self.radius >= 0.0
+                && self.position.y + self.radius >= 0.0
+                && self.position.x - self.radius <= bounds.x
+                && self.position.y - self.radius <= bounds.y;
+            BulletUpdate {
+                alive,
+                hits,
+                spawned: vec![],
+            }
+        }
+
+        fn look(&self) -> BulletLook {
+            BulletLook::Streak {
+                tail: self.position - self.velocity.normalised() * self.radius * 3.0,
+                head: self.position,
+                width: self.radius,
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::map::Map;
+
+pub const USAGE: &str = "Usage: across [options]
+    --map <file>           play a map loaded from a file
+    --import <file>        install a shared map into the maps folder, and play it
+    --difficulty <level>   easy, normal or hard
+    --seed <number>        seed the run's random numbers
+    --fullscreen           start in fullscreen (Alt+Enter switches while playing)
+    --play                 skip the main menu and start playing
+    --autoplay <games>     play games without a window, printing the round each reached
+    --developer            enable cheat and debugging keys, listed on the help screen";
+
+/// How many lives the player starts with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn lives(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 30,
+            Difficulty::Normal => 20,
+            Difficulty::Hard => 10,
+        }
+    }
+}
+
+/// The options the game was started with, from the command line
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub map: Option<Map>,
+    /// Whether to save the map into the maps folder, as it was imported
+    pub install: bool,
+    pub difficulty: Difficulty,
+    pub seed: Option<u64>,
+    pub fullscreen: bool,
+    /// Go straight into the game, rather than starting on the main menu
+    pub play: bool,
+    /// How many games to play without a window, to check the game can still be beaten
+    pub autoplay: Option<usize>,
+    /// Enables cheats and debugging tools, for testing towers and enemies
+    pub developer: bool,
+}
+
+impl LaunchOptions {
+    /// Reads the options from the command line arguments, not including the program name.
+    /// The error is a message for the player.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--map" => options.map = Some(read_map(&value()?)?),
+                "--import" => {
+                    options.map = Some(read_map(&value()?)?);
+                    options.install = true;
+                }
+                "--difficulty" => {
+                    options.difficulty = match value()?.as_str() {
+                        "easy" => Difficulty::Easy,
+                        "normal" => Difficulty::Normal,
+                        "hard" => Difficulty::Hard,
+                        other => return Err(format!("Unknown difficulty '{}'", other)),
+                    }
+                }
+                "--seed" => {
+                    let seed = value()?;
+                    options.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("'{}' isn't a valid seed", seed))?,
+                    );
+                }
+                "--fullscreen" => options.fullscreen = true,
+                "--play" => options.play = true,
+                "--developer" => options.developer = true,
+                "--autoplay" => {
+                    let games = value()?;
+                    options.autoplay = Some(
+                        games
+                            .parse()
+                            .map_err(|_| format!("'{}' isn't a number of games", games))?,
+                    );
+                }
+                other => return Err(format!("Unknown option '{}'", other)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Loads a map file, checking that it builds a valid web
+fn read_map(path: &str) -> Result<Map, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+    let map = Map::parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+    map.build_web()
+        .map_err(|e| format!("{} isn't a valid map: {}", path, e))?;
+    if map
+        .waves
+        .iter()
+        .any(|wave| wave.count == 0 || (!wave.is_scheduled() && wave.interval == 0))
+    {
+        return Err(format!(
+            "{} has a wave with no enemies or no time between them",
+            path
+        ));
+    }
+    Ok(map)
+}
@@ -0,0 +1,950 @@
+pub mod enemy {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        animator::Animator,
+        path::{Route, Web},
+        pathfind::RouteProfile,
+        tower::tower::Tower,
+        vector::Vector,
+        wave::Modifier,
+        Alive, Dead, Updated,
+    };
+
+    /// The name and a short description of every kind of enemy, for the help screen
+    pub const ENEMY_KINDS: [(&str, &str); 5] = [
+        ("Test Enemy", "Follows the safest route to the end"),
+        (
+            "Brave Enemy",
+            "Takes the shortest route, whatever the towers can see",
+        ),
+        (
+            "Carrier",
+            "Slow and tough, and releases a swarm of fast enemies halfway or when it's killed",
+        ),
+        (
+            "Teleporter",
+            "Every few seconds, jumps a long way along its route",
+        ),
+        (
+            "Juggernaut",
+            "Very slow and tough, and stops to smash any tower it can reach",
+        ),
+    ];
+
+    /// Every kind of enemy which can be spawned, in the same order as `ENEMY_KINDS`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum EnemyKind {
+        Test,
+        Brave,
+        Carrier,
+        Teleporter,
+        Juggernaut,
+    }
+
+    impl EnemyKind {
+        pub const ALL: [EnemyKind; 5] = [
+            EnemyKind::Test,
+            EnemyKind::Brave,
+            EnemyKind::Carrier,
+            EnemyKind::Teleporter,
+            EnemyKind::Juggernaut,
+        ];
+
+        /// The name used in map files
+        pub fn name(&self) -> &'static str {
+            match self {
+                EnemyKind::Test => "test",
+                EnemyKind::Brave => "brave",
+                EnemyKind::Carrier => "carrier",
+                EnemyKind::Teleporter => "teleporter",
+                EnemyKind::Juggernaut => "juggernaut",
+            }
+        }
+
+        /// What several enemies of this kind are called in messages to the player,
+        /// before it's translated
+        pub fn plural_name(&self) -> &'static str {
+            match self {
+                EnemyKind::Test => "test enemies",
+                EnemyKind::Brave => "brave enemies",
+                EnemyKind::Carrier => "carriers",
+                EnemyKind::Teleporter => "teleporters",
+                EnemyKind::Juggernaut => "juggernauts",
+            }
+        }
+
+        pub fn from_name(name: &str) -> Option<Self> {
+            Self::ALL.into_iter().find(|x| x.name() == name)
+        }
+
+        /// Spawns an enemy of this kind, on the route it prefers
+        pub fn spawn(&self, web: &Web) -> Enemy<Alive> {
+            match self {
+                EnemyKind::Test => TestEnemy::spawn(web.route_for(RouteProfile::Cautious).clone()),
+                EnemyKind::Brave => BraveEnemy::spawn(web.route_for(RouteProfile::Brave).clone()),
+                EnemyKind::Carrier => CarrierEnemy::spawn(web.route().clone()),
+                EnemyKind::Teleporter => TeleporterEnemy::spawn(web.route().clone()),
+                EnemyKind::Juggernaut => JuggernautEnemy::spawn(web.route().clone()),
+            }
+        }
+    }
+    /// How many frames enemies take to fade in, during which they can't be hit
+    const SPAWN_FRAMES: usize = 20;
+    /// How many frames killed enemies take to fade out
+    pub const DEATH_FRAMES: usize = 20;
+    /// The furthest enemies can be pushed to the side of their route
+    const MAX_SPREAD: f32 = 20.0;
+    /// The fraction of the way back to their route enemies move each frame
+    const SPREAD_RETURN: f32 = 0.02;
+
+    /// Identifies an enemy for as long as the game runs, so towers can keep track of one
+    /// across steps even as the list of enemies is rebuilt.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EnemyId(u64);
+
+    impl EnemyId {
+        /// A new ID, different from every other one given out
+        fn next() -> Self {
+            static NEXT: AtomicU64 = AtomicU64::new(0);
+            Self(NEXT.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+
+    /// What damaging an enemy did
+    #[derive(Debug, Clone, Copy)]
+    pub struct Damage {
+        /// The health the enemy lost, normalised [0-1]
+        pub lost: f32,
+        /// The damage left over once the enemy's health ran out
+        pub overkill: f32,
+    }
+
+    #[derive(Debug)]
+    pub struct Enemy<State> {
+        id: EnemyId,
+        enemy: Box<dyn EnemyTrait>,
+        state: std::marker::PhantomData<State>,
+        /// How many frames the enemy has been in this state, for the spawn and death animations
+        frames: usize,
+        animator: Animator,
+        /// How fast the enemy moves this step compared to normal, from the auras it's in.
+        /// This goes back to 1 after every step.
+        speed: f32,
+        /// The modifier on the wave this enemy spawned in, if any
+        modifier: Option<Modifier>,
+    }
+
+    impl Enemy<Alive> {
+        pub fn new(enemy: Box<dyn EnemyTrait>) -> Enemy<Alive> {
+            Enemy {
+                id: EnemyId::next(),
+                enemy,
+                state: std::marker::PhantomData::<Alive>,
+                frames: 0,
+                animator: Animator::default(),
+                speed: 1.0,
+                modifier: None,
+            }
+        }
+
+        /// Makes the enemy part of a wave with a modifier, which changes how fast it moves,
+        /// how tough it is and whether it heals
+        pub fn with_modifier(mut self, modifier: Option<Modifier>) -> Self {
+            self.modifier = modifier;
+            self
+        }
+
+        /// Moves every enemy, and records on the web whenever one finishes a connection.
+        /// Also returns how many enemies reached the end of their route.
+        pub fn update_all(
+            mut enemies: Vec<Enemy<Alive>>,
+            web: &mut Web,
+        ) -> (Vec<Enemy<Alive>>, usize) {
+            let mut new_enemies = Vec::with_capacity(enemies.len());
+            let mut escaped = 0;
+            while let Some(enemy) = enemies.pop() {
+                let connection = enemy.connection();
+                let updated = enemy.update();
+                let new_connection = match &updated {
+                    Updated::Alive(enemy) => enemy.connection(),
+                    Updated::Dead(_) => None,
+                };
+                if let Some(connection) = connection.filter(|&x| Some(x) != new_connection) {
+                    web.record_traversal(connection);
+                }
+                match updated {
+                    Updated::Alive(enemy) => new_enemies.push(enemy),
+                    Updated::Dead(_) => escaped += 1,
+                }
+            }
+            (new_enemies, escaped)
+        }
+
+        /// The web connection this enemy is currently travelling along
+        pub fn connection(&self) -> Option<usize> {
+            self.enemy.route().connection_at(self.enemy.progress())
+        }
+    }
+
+    impl Enemy<Alive> {
+        /// Enemies wait at the start of their route until they've finished spawning
+        pub fn update(mut self) -> Updated<Enemy<Alive>, Enemy<Dead>> {
+            self.frames += 1;
+            self.animator
+                .update(self.enemy.route().direction_at(self.enemy.progress()));
+            let spawning = self.spawning();
+            let before = self.enemy.progress();
+            let mut alive = spawning || self.enemy.update();
+            // slowed or hastened enemies are moved back or on part of the way they went
+            let speed = self.speed * self.modifier.map_or(1.0, |x| x.speed());
+            if !spawning && speed != 1.0 {
+                let after = self.enemy.progress();
+                self.enemy.set_progress(before + (after - before) * speed);
+                alive = self.enemy.progress() < 1.0;
+            }
+            // enemies with no health left are about to be killed, so they can't heal
+            if let Some(modifier) = self.modifier.filter(|_| alive && !spawning) {
+                if self.enemy.health() > 0.0 {
+                    self.enemy.heal(modifier.regeneration());
+                }
+            }
+            self.speed = 1.0;
+            if alive {
+                Updated::Alive(self)
+            } else {
+                Updated::Dead(self.kill())
+            }
+        }
+
+        /// How far the enemy has faded in, normalised [0-1]
+        pub fn visibility(&self) -> f32 {
+            (self.frames as f32 / SPAWN_FRAMES as f32).min(1.0)
+        }
+
+        /// Pushes the enemy sideways off its route, by as much of `push` as is sideways.
+        /// Enemies slowly drift back to their route when they aren't pushed.
+        pub fn nudge(&mut self, push: Vector) {
+            let Some(direction) = self.enemy.route().direction_at(self.enemy.progress()) else {
+                return;
+            };
+            let offset = self.enemy.lateral_offset() * (1.0 - SPREAD_RETURN)
+                + push.dot(direction.anticlockwise_90deg());
+            self.enemy
+                .set_lateral_offset(offset.clamp(-MAX_SPREAD, MAX_SPREAD));
+        }
+
+        /// Moves the enemy along its route to the closest point to `point`
+        pub fn move_nearest(&mut self, point: Vector) {
+            let progress = self.enemy.route().progress_nearest(point);
+            self.enemy.set_progress(progress);
+        }
+
+        /// Slows the enemy down for this step, to `speed` times as fast as normal.
+        /// Slows don't add up, so only the strongest counts.
+        pub fn slow(&mut self, speed: f32) {
+            self.speed = self.speed.min(speed.max(0.0));
+        }
+
+        /// Takes the enemies this one has just released, if any.
+        /// They're part of the same wave, so they share its modifier.
+        pub fn take_cargo(&mut self) -> Vec<Enemy<Alive>> {
+            let modifier = self.modifier;
+            self.enemy
+                .take_cargo(false)
+                .into_iter()
+                .map(|enemy| enemy.with_modifier(modifier))
+                .collect()
+        }
+
+        /// Lets an enemy which attacks towers hit the closest one it can reach, and stops it
+        /// while it does
+        pub fn siege(&mut self, towers: &mut [Box<dyn Tower>]) {
+            let Some((reach, damage)) = self.enemy.siege().filter(|_| !self.spawning()) else {
+                return;
+            };
+            let position = self.position();
+            let mut target = towers
+                .iter_mut()
+                .map(|tower| {
+                    let gap = (tower.position() - position).length()
+                        - tower.radius()
+                        - self.enemy.radius();
+                    (tower, gap)
+                })
+                .filter(|&(_, gap)| gap <= reach)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((tower, _)) = &mut target {
+                tower.damage(damage);
+            }
+            self.enemy.set_besieging(target.is_some());
+        }
+
+        /// Hurts the enemy, which might take more or less than `dmg` depending on its kind
+        /// and its wave's modifier
+        pub fn damage(&mut self, dmg: f32) -> Damage {
+            let before = self.enemy.health();
+            let toughness = self.modifier.map_or(1.0, |x| x.health());
+            let overkill = self.enemy.damage(dmg / toughness) * toughness;
+            Damage {
+                lost: before - self.enemy.health(),
+                overkill,
+            }
+        }
+
+        /// Whether the enemy is still fading in, and so can't be hit
+        pub fn spawning(&self) -> bool {
+            self.frames < SPAWN_FRAMES
+        }
+
+        /// Spawning enemies don't collide with anything
+        pub fn collides(&self, position: Vector, radius: f32) -> bool {
+            !self.spawning() && self.enemy.collides(position, radius)
+        }
+
+        /// Starts the enemy's death animation
+        pub fn kill(mut self) -> Enemy<Dead> {
+            self.animator.die();
+            Enemy {
+                id: self.id,
+                enemy: self.enemy,
+                state: std::marker::PhantomData::<Dead>,
+                frames: 0,
+                animator: self.animator,
+                speed: 1.0,
+                modifier: self.modifier,
+            }
+        }
+
+        pub fn id(&self) -> EnemyId {
+            self.id
+        }
+
+        /// Normalised [0-1]
+        pub fn health(&self) -> f32 {
+            self.enemy.health()
+        }
+    }
+
+    impl<State> Enemy<State> {
+        pub fn position(&self) -> Vector {
+            self.enemy.position()
+        }
+
+        pub fn radius(&self) -> f32 {
+            self.enemy.radius()
+        }
+
+        pub fn look(&self) -> EnemyLook {
+            self.enemy.look()
+        }
+
+        /// Where the enemy is in its walk or death animation
+        pub fn animator(&self) -> &Animator {
+            &self.animator
+        }
+    }
+
+    impl Enemy<Dead> {
+        /// Moves the death animation on.
+        /// Returns false once it has finished, and the enemy can be thrown away.
+        pub fn update(&mut self) -> bool {
+            self.frames += 1;
+            self.animator.update(None);
+            self.frames < DEATH_FRAMES
+        }
+
+        /// Takes the enemies this one released as it was killed, if any
+        pub fn take_cargo(&mut self) -> Vec<Enemy<Alive>> {
+            let modifier = self.modifier;
+            self.enemy
+                .take_cargo(true)
+                .into_iter()
+                .map(|enemy| enemy.with_modifier(modifier))
+                .collect()
+        }
+
+        /// How much of the enemy is left as it fades out, normalised [0-1]
+        pub fn visibility(&self) -> f32 {
+            1.0 - self.frames as f32 / DEATH_FRAMES as f32
+        }
+    }
+
+    /// Takes `dmg` times `multiplier` away from `health`, which stops at 0.
+    /// Returns how much of `dmg` was left over once the health ran out, before the
+    /// multiplier, so it can be passed on to another enemy.
+    fn take_damage(health: &mut f32, dmg: f32, multiplier: f32) -> f32 {
+        let taken = dmg * multiplier;
+        let overkill = (taken - *health).max(0.0);
+        *health = (*health - taken).max(0.0);
+        overkill / multiplier
+    }
+
+    /// What an enemy looks like at the moment, which is everything a front end needs to
+    /// know to draw it, beyond where it is and how big it is
+    #[derive(Debug, Clone, Copy)]
+    pub enum EnemyLook {
+        Test,
+        Brave,
+        Carrier {
+            /// Whether its swarm is still inside it
+            carrying: bool,
+        },
+        Swarm,
+        Teleporter {
+            /// Where it last jumped from, and how much of the flash there is left [0-1]
+            jumped_from: Option<(Vector, f32)>,
+            /// Whether it's about to jump, and has flickered out for this step
+            flickering: bool,
+        },
+        Juggernaut {
+            /// How far past its edge it can reach, while it's attacking a tower
+            besieging: Option<f32>,
+        },
+    }
+
+    pub trait EnemyTrait: std::fmt::Debug {
+        /// What the enemy looks like, for drawing it
+        fn look(&self) -> EnemyLook;
+        /// Spawn an enemy on a path
+        fn spawn(route: Route) -> Enemy<Alive>
+        where
+            Self: Sized;
+        /// Update the enemy (move it one frame forward)
+        /// Return true if the enemy is still alive
+        fn update(&mut self) -> bool;
+        /// Get the health of the enemy, normalised [0-1]
+        fn health(&self) -> f32;
+        /// Get the progress along the route, normalised [0-1]
+        fn progress(&self) -> f32;
+        fn set_progress(&mut self, progress: f32);
+        /// Is the enemy dead? should it be updated?
+        fn dead(&self) -> bool {
+            self.health() <= 0.0 || self.progress() >= 1.0
+        }
+        /// The radius of the enemy
+        /// Originally intended to replace this with a Collider struct but that's overkill.
+        /// I can guarantee that I'll always use circles
+        fn radius(&self) -> f32;
+        /// Damage the enemy.
+        /// Returns the damage left over once its health has run out, as in `take_damage`.
+        fn damage(&mut self, dmg: f32) -> f32;
+        /// Gives the enemy back some health, up to full
+        fn heal(&mut self, amount: f32);
+        /// Get the route this enemy is following
+        fn route<'b>(&'b self) -> &'b Route;
+        /// How far the enemy has been pushed to the side of its route, anticlockwise
+        fn lateral_offset(&self) -> f32;
+        fn set_lateral_offset(&mut self, offset: f32);
+        /// Enemies released by this one, which join the others on the next step.
+        /// `killed` is whether this enemy has just been killed.
+        fn take_cargo(&mut self, _killed: bool) -> Vec<Enemy<Alive>> {
+            vec![]
+        }
+        /// How far past its edge this enemy can hit towers from, and how much damage it does
+        /// to them each step, or None if it leaves towers alone
+        fn siege(&self) -> Option<(f32, f32)> {
+            None
+        }
+        /// Whether the enemy has stopped to attack a tower this step
+        fn set_besieging(&mut self, _besieging: bool) {}
+        /// Get the position of the enemy
+        fn position(&self) -> Vector {
+            // progress is kept between 0 and 1, but the end of the route is the best guess
+            // if it isn't
+            let on_route = self
+                .route()
+                .get_position(self.progress().clamp(0.0, 1.0))
+                .or_else(|| self.route().points().last().copied())
+                .unwrap_or_default();
+            match self.route().direction_at(self.progress()) {
+                Some(direction) => {
+                    on_route + direction.anticlockwise_90deg() * self.lateral_offset()
+                }
+                None => on_route,
+            }
+        }
+        /// Check if this collides with another circle
+        fn collides(&self, position: Vector, radius: f32) -> bool {
+            (self.position() - position).sqr_length()
+                <= (radius + self.radius()) * (radius + self.radius())
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+    }
+
+    impl EnemyTrait for TestEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Test
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            self.progress += 0.0012 * self.path.speed_at(self.progress);
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            15.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 1.0)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+    }
+
+    /// Ignores the towers and takes the shortest route, but moves a little slower
+    #[derive(Debug)]
+    struct BraveEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+    }
+
+    impl EnemyTrait for BraveEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Brave
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            self.progress += 0.001 * self.path.speed_at(self.progress);
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            15.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 1.0)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+    }
+
+    /// Carries a swarm of small enemies, which it releases halfway along its route or when
+    /// it's killed. It moves slowly and shrugs off half of every hit.
+    #[derive(Debug)]
+    struct CarrierEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+        /// Whether the swarm has been released yet
+        released: bool,
+    }
+
+    impl EnemyTrait for CarrierEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Carrier {
+                carrying: !self.released,
+            }
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+                released: false,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            self.progress += 0.0008 * self.path.speed_at(self.progress);
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            22.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 0.5)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+
+        fn take_cargo(&mut self, killed: bool) -> Vec<Enemy<Alive>> {
+            if self.released || !(killed || self.progress >= CARRIER_RELEASE_PROGRESS) {
+                return vec![];
+            }
+            self.released = true;
+            swarm_offsets()
+                .map(|offset| {
+                    Enemy::new(Box::new(SwarmEnemy {
+                        path: self.path.clone(),
+                        progress: self.progress.min(1.0),
+                        health: 1.0,
+                        lateral_offset: self.lateral_offset + offset,
+                    }))
+                })
+                .collect()
+        }
+    }
+
+    /// How far along its route a carrier releases its swarm, normalised [0-1]
+    const CARRIER_RELEASE_PROGRESS: f32 = 0.5;
+    /// How many enemies each carrier releases
+    const SWARM_SIZE: usize = 4;
+
+    /// How far to the side of the carrier each enemy in its swarm starts
+    pub fn swarm_offsets() -> impl Iterator<Item = f32> {
+        (0..SWARM_SIZE).map(|i| (i as f32 - (SWARM_SIZE - 1) as f32 / 2.0) * 8.0)
+    }
+
+    /// Small and fast, but goes down in one hit.
+    /// Only released by carriers, so never spawned on its own.
+    #[derive(Debug)]
+    struct SwarmEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+    }
+
+    impl EnemyTrait for SwarmEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Swarm
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            self.progress += 0.0024 * self.path.speed_at(self.progress);
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            8.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 2.0)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+    }
+
+    /// How many steps a teleporter walks between jumps
+    const TELEPORT_STEPS: usize = 150;
+    /// How far along its route a teleporter jumps
+    const TELEPORT_DISTANCE: f32 = 160.0;
+    /// How many steps a teleporter flickers for before it jumps, and how long the flash
+    /// where it left lasts
+    const BLINK_STEPS: usize = 20;
+
+    /// Walks slowly, but every so often jumps a long way along its route
+    #[derive(Debug)]
+    struct TeleporterEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+        /// How many steps until the next jump
+        steps_until_jump: usize,
+        /// Where it last jumped from, and how many steps ago, while the flash is showing
+        jumped_from: Option<(Vector, usize)>,
+    }
+
+    impl EnemyTrait for TeleporterEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Teleporter {
+                jumped_from: self
+                    .jumped_from
+                    .map(|(position, steps)| (position, 1.0 - steps as f32 / BLINK_STEPS as f32)),
+                // it flickers just before it jumps
+                flickering: self.steps_until_jump < BLINK_STEPS
+                    && self.steps_until_jump / 3 % 2 == 0,
+            }
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+                steps_until_jump: TELEPORT_STEPS,
+                jumped_from: None,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            self.jumped_from = self
+                .jumped_from
+                .map(|(position, steps)| (position, steps + 1))
+                .filter(|&(_, steps)| steps < BLINK_STEPS);
+            if self.steps_until_jump == 0 {
+                self.jumped_from = Some((self.position(), 0));
+                self.progress = self.path.advance(self.progress, TELEPORT_DISTANCE);
+                self.steps_until_jump = TELEPORT_STEPS;
+            } else {
+                self.steps_until_jump -= 1;
+                self.progress += 0.0008 * self.path.speed_at(self.progress);
+            }
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            14.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 1.0)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+    }
+
+    /// How much damage a juggernaut does to a tower each step
+    const JUGGERNAUT_DAMAGE: f32 = 0.004;
+    /// How far past its edge a juggernaut can reach towers
+    const JUGGERNAUT_REACH: f32 = 25.0;
+
+    /// Slow and very tough, and stops to smash any tower it can reach
+    #[derive(Debug)]
+    struct JuggernautEnemy {
+        path: Route,
+        progress: f32,
+        health: f32,
+        lateral_offset: f32,
+        /// Whether it's stopped to attack a tower
+        besieging: bool,
+    }
+
+    impl EnemyTrait for JuggernautEnemy {
+        fn look(&self) -> EnemyLook {
+            EnemyLook::Juggernaut {
+                besieging: Some(JUGGERNAUT_REACH).filter(|_| self.besieging),
+            }
+        }
+
+        fn spawn(path: Route) -> Enemy<Alive> {
+            Enemy::new(Box::new(Self {
+                path,
+                progress: 0.0,
+                health: 1.0,
+                lateral_offset: 0.0,
+                besieging: false,
+            }))
+        }
+
+        fn update(&mut self) -> bool {
+            if !self.besieging {
+                self.progress += 0.0007 * self.path.speed_at(self.progress);
+            }
+            self.progress < 1.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn progress(&self) -> f32 {
+            self.progress
+        }
+
+        fn set_progress(&mut self, progress: f32) {
+            self.progress = progress;
+        }
+
+        fn radius(&self) -> f32 {
+            20.0
+        }
+
+        fn damage(&mut self, dmg: f32) -> f32 {
+            take_damage(&mut self.health, dmg, 1.0 / 3.0)
+        }
+
+        fn heal(&mut self, amount: f32) {
+            self.health = (self.health + amount).min(1.0);
+        }
+
+        fn route<'b>(&'b self) -> &'b Route {
+            &self.path
+        }
+
+        fn lateral_offset(&self) -> f32 {
+            self.lateral_offset
+        }
+
+        fn set_lateral_offset(&mut self, offset: f32) {
+            self.lateral_offset = offset;
+        }
+
+        fn siege(&self) -> Option<(f32, f32)> {
+            Some((JUGGERNAUT_REACH, JUGGERNAUT_DAMAGE))
+        }
+
+        fn set_besieging(&mut self, besieging: bool) {
+            self.besieging = besieging;
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    path::{Terrain, Web},
+    pathfind::Pathfinder,
+    polygon::Polygon,
+    registry::{TowerDescriptor, TowerRegistry},
+    vec2d,
+    vector::Vector,
+    wave::{EnemyMix, Modifier, SpawnEvent, SpawnScheduler, Wave},
+    world::World,
+    SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+/// How many simulations are run for each estimate
+const SIMULATIONS: usize = 16;
+/// How many steps to wait between estimates
+const ESTIMATE_INTERVAL: usize = 600;
+/// Simulations are stopped after this many steps, in case a wave never finishes
+const MAX_STEPS: usize = 60 * 60 * 10;
+/// The chance a simulated spawn timer ticks each step, so simulations differ from each other
+const SPAWN_TICK_CHANCE: f64 = 0.8;
+
+/// Everything needed to simulate a wave away from the game.
+/// The game's own types use `Rc` and so can't be sent to another thread,
+/// so the simulation rebuilds them from this.
+#[derive(Debug, Clone)]
+pub struct SimulationSetup {
+    pub positions: Vec<Vector>,
+    pub connections: Vec<(usize, usize)>,
+    pub curves: Vec<((usize, usize), Vector)>,
+    pub terrain: Vec<((usize, usize), Terrain)>,
+    pub obstacles: Vec<Polygon>,
+    pub start: usize,
+    pub end: usize,
+    /// Every tower, walls included, with its upgrades and rotation
+    pub towers: Vec<TowerDescriptor>,
+    pub wave: Wave,
+    pub modifier: Option<Modifier>,
+    /// The round the wave is for, which decides the enemy mix
+    pub round: usize,
+    pub lives: u32,
+}
+
+impl SimulationSetup {
+    /// Runs the wave once without drawing anything, returning whether the player survives
+    fn survives(&self, registry: &TowerRegistry, seed: u64) -> bool {
+        let Some(mut simulation) = Simulation::new(self, registry, seed) else {
+            return true;
+        };
+        loop {
+            if let Some(survived) = simulation.step() {
+                return survived;
+            }
+        }
+    }
+
+    /// The fraction of simulations the player survives
+    fn estimate(&self, registry: &TowerRegistry, seed: u64) -> f32 {
+        let survived = (0..SIMULATIONS)
+            .filter(|&i| self.survives(registry, seed.wrapping_add(i as u64)))
+            .count();
+        survived as f32 / SIMULATIONS as f32
+    }
+}
+
+/// A wave being simulated away from the game, one step at a time, so it can be watched
+pub struct Simulation {
+    web: Web,
+    world: World,
+    scheduler: SpawnScheduler,
+    mix: EnemyMix,
+    rng: StdRng,
+    round: usize,
+    lives: u32,
+    steps: usize,
+    /// Whether the player survived, once the simulation is over
+    result: Option<bool>,
+}
+
+impl Simulation {
+    /// Rebuilds the game from a setup, making its towers from `registry`.
+    /// Returns None if enemies have no route, as then there's nothing to simulate.
+    pub fn new(setup: &SimulationSetup, registry: &TowerRegistry, seed: u64) -> Option<Self> {
+        let route = Pathfinder::new(setup.positions.clone(), setup.connections.clone())
+            .find(setup.start, setup.end)?;
+        let mut web = Web::new(setup.positions.clone(), setup.connections.clone(), route).ok()?;
+        for &(connection, control) in &setup.curves {
+            web.set_curve(connection, control);
+        }
+        for &(connection, terrain) in &setup.terrain {
+            web.set_terrain(connection, terrain);
+        }
+        web.set_obstacles(setup.obstacles.clone());
+        let mut world = World::new();
+        world.towers = setup
+            .towers
+            .iter()
+            .filter_map(|descriptor| registry.spawn(descriptor))
+            .collect();
+        if !web.recalculate_weights(&world.towers) {
+            return None;
+        }
+        let mut scheduler = SpawnScheduler::new(vec![setup.wave.clone()]);
+        scheduler.set_modifier(setup.modifier);
+        Some(Self {
+            web,
+            world,
+            scheduler,
+            mix: EnemyMix::default(),
+            rng: StdRng::seed_from_u64(seed),
+            round: setup.round,
+            lives: setup.lives,
+            steps: 0,
+            result: None,
+        })
+    }
+
+    pub fn web(&self) -> &Web {
+        &self.web
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn modifier(&self) -> Option<Modifier> {
+        self.scheduler.modifier()
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Whether the player survived, once the simulation is over
+    pub fn result(&self) -> Option<bool> {
+        self.result
+    }
+
+    /// Moves the simulation forward one step.
+    /// Returns whether the player survived once the simulation is over.
+    pub fn step(&mut self) -> Option<bool> {
+        if self.result.is_some() {
+            return self.result;
+        }
+        self.steps += 1;
+        let event = if self.rng.gen_bool(SPAWN_TICK_CHANCE) {
+            self.scheduler.update(self.world.enemies.len())
+        } else {
+            SpawnEvent::Nothing
+        };
+        for kind in event.spawns() {
+            let kind = kind.unwrap_or_else(|| self.mix.pick(self.round, &mut self.rng));
+            let enemy = kind.spawn(&self.web);
+            self.world
+                .enemies
+                .push(enemy.with_modifier(self.scheduler.modifier()));
+        }
+        let escaped = self.world.movement_system(&mut self.web, false);
+        self.lives = self.lives.saturating_sub(escaped as u32);
+        if self.lives == 0 {
+            self.result = Some(false);
+            return self.result;
+        }
+        if !self.world.siege_system().is_empty() {
+            self.web.recalculate_weights(&self.world.towers);
+        }
+        self.world.combat_system(
+            vec2d![SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
+            None,
+            self.web.obstacles(),
+            &mut self.rng,
+        );
+        if self.scheduler.is_finished() || self.steps >= MAX_STEPS {
+            self.result = Some(true);
+        }
+        self.result
+    }
+}
+
+/// Estimates the chance of surviving the next wave every so often, in a background thread
+#[derive(Debug, Default)]
+pub struct WinEstimator {
+    estimate: Option<f32>,
+    running: Option<Receiver<f32>>,
+    timer: usize,
+}
+
+impl WinEstimator {
+    /// The latest estimate, normalised [0-1]
+    pub fn estimate(&self) -> Option<f32> {
+        self.estimate
+    }
+
+    /// Picks up finished estimates.
+    /// Returns true when it's time to start a new one.
+    pub fn update(&mut self) -> bool {
+        if let Some(receiver) = &self.running {
+            match receiver.try_recv() {
+                Ok(estimate) => {
+                    self.estimate = Some(estimate);
+                    self.running = None;
+                }
+                Err(TryRecvError::Disconnected) => self.running = None,
+                Err(TryRecvError::Empty) => (),
+            }
+        }
+
+        self.timer = self.timer.saturating_sub(1);
+        self.timer == 0 && self.running.is_none()
+    }
+
+    /// Starts simulating in the background, with a copy of the game's tower registry
+    pub fn start(&mut self, seed: u64, setup: SimulationSetup, registry: TowerRegistry) {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // the game may have ended and dropped the receiver, which is fine
+            let _ = sender.send(setup.estimate(&registry, seed));
+        });
+        self.running = Some(receiver);
+        self.timer = ESTIMATE_INTERVAL;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tower::tower::{spawn_sector_tower, spawn_wall, TowerState, Upgrade};
+
+    #[test]
+    fn simulation_rebuilds_the_placed_towers() {
+        let mut sector = spawn_sector_tower(vec2d![100.0, 100.0]);
+        sector.rotate(1.0);
+        assert!(Upgrade::Radius.apply(sector.as_mut()));
+        let towers = [sector, spawn_wall(vec2d![300.0, 300.0])];
+        let setup = SimulationSetup {
+            positions: vec![vec2d![0.0, 0.0], vec2d![100.0, 0.0], vec2d![200.0, 0.0]],
+            connections: vec![(0, 1), (1, 2)],
+            curves: vec![],
+            terrain: vec![],
+            obstacles: vec![],
+            start: 0,
+            end: 2,
+            towers: towers
+                .iter()
+                .map(|tower| TowerDescriptor::of(tower.as_ref()))
+                .collect(),
+            wave: Wave::new(1, 10),
+            modifier: None,
+            round: 0,
+            lives: 1,
+        };
+
+        let simulation = Simulation::new(&setup, &TowerRegistry::default(), 0).unwrap();
+        let rebuilt = &simulation.world().towers;
+        assert_eq!(rebuilt.len(), towers.len());
+        for (rebuilt, tower) in rebuilt.iter().zip(&towers) {
+            assert_eq!(rebuilt.name(), tower.name());
+            assert_eq!(rebuilt.position().distance(tower.position()), 0.0);
+            let (rebuilt, tower) = (
+                TowerState::of(rebuilt.as_ref()),
+                TowerState::of(tower.as_ref()),
+            );
+            assert_eq!(rebuilt.radius, tower.radius);
+            assert_eq!(rebuilt.direction.is_some(), tower.direction.is_some());
+            if let (Some(a), Some(b)) = (rebuilt.direction, tower.direction) {
+                assert!((a - b).abs() < 1e-4, "turned to {} rather than {}", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    pathfind::{Pathfinder, Weight},
+    tower::tower::Tower,
+    vec2d,
+    vector::Vector,
+};
+
+/// Which neighbouring tiles enemies can walk to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// Only the tiles which share an edge
+    #[default]
+    Four,
+    /// Diagonal tiles too, as long as no corner is cut
+    Eight,
+}
+
+/// The size and place of a grid, which is all a map needs to say about it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GridLayout {
+    pub width: usize,
+    pub height: usize,
+    pub tile_size: f32,
+    /// The top-left corner of the grid, in world coordinates
+    pub origin: Vector,
+    pub connectivity: Connectivity,
+}
+
+/// A map made of square tiles, where enemies walk between the centres of tiles.
+/// Unlike a `Web`, towers fill up whole tiles, so placing them changes the shape of
+/// the path, maze building style. Routes are found by the same `Pathfinder` as webs,
+/// with a point at the centre of every tile.
+#[derive(Debug, Clone)]
+pub struct GridMap {
+    width: usize,
+    height: usize,
+    tile_size: f32,
+    /// The top-left corner of the grid, in world coordinates
+    origin: Vector,
+    /// How much more it costs to walk onto each tile, row by row.
+    /// None means the tile is solid, such as when a tower is on it.
+    costs: Vec<Option<f32>>,
+    connectivity: Connectivity,
+    pathfinder: Pathfinder,
+}
+
+impl GridMap {
+    /// Every tile starts empty, with a cost of 1
+    pub fn new(layout: GridLayout) -> Self {
+        let GridLayout {
+            width,
+            height,
+            tile_size,
+            origin,
+            connectivity,
+        } = layout;
+        let positions = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| origin + vec2d![x as f32 + 0.5, y as f32 + 0.5] * tile_size)
+            .collect();
+        let offsets: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(1, 0), (-1, 0), (0, 1), (0, -1)],
+            Connectivity::Eight => &[
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ],
+        };
+        let mut connections = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                for &(dx, dy) in offsets {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+                        connections.push((y * width + x, ny as usize * width + nx as usize));
+                    }
+                }
+            }
+        }
+        let mut grid = Self {
+            width,
+            height,
+            tile_size,
+            origin,
+            costs: vec![Some(1.0); width * height],
+            connectivity,
+            pathfinder: Pathfinder::new(positions, connections),
+        };
+        grid.update_weights();
+        grid
+    }
+
+    pub fn layout(&self) -> GridLayout {
+        GridLayout {
+            width: self.width,
+            height: self.height,
+            tile_size: self.tile_size,
+            origin: self.origin,
+            connectivity: self.connectivity,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    pub fn pathfinder(&self) -> &Pathfinder {
+        &self.pathfinder
+    }
+
+    /// The centre of every tile, row by row, which are the points of the pathfinder
+    pub fn positions(&self) -> Vec<Vector> {
+        (0..self.costs.len())
+            .map(|i| self.centre(self.tile_of(i)))
+            .collect()
+    }
+
+    /// The index of a tile's point, or None if the tile is off the grid
+    pub fn index(&self, (x, y): (usize, usize)) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn tile_of(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    /// The tile a position in the world is on, if it's on the grid at all
+    pub fn tile_at(&self, position: Vector) -> Option<(usize, usize)> {
+        let local = (position - self.origin) / self.tile_size;
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// The centre of a tile, in world coordinates
+    pub fn centre(&self, (x, y): (usize, usize)) -> Vector {
+        self.origin + vec2d![x as f32 + 0.5, y as f32 + 0.5] * self.tile_size
+    }
+
+    /// How much it costs to walk onto a tile, or None if it's solid or off the grid
+    pub fn cost(&self, tile: (usize, usize)) -> Option<f32> {
+        self.costs[self.index(tile)?]
+    }
+
+    /// Tiles off the grid count as solid, as nothing can walk onto them
+    pub fn is_solid(&self, tile: (usize, usize)) -> bool {
+        self.cost(tile).is_none()
+    }
+
+    /// Changes how much it costs to walk onto a tile, or makes it solid if `cost` is None.
+    /// Returns false if the tile is off the grid.
+    pub fn set_cost(&mut self, tile: (usize, usize), cost: Option<f32>) -> bool {
+        let Some(index) = self.index(tile) else {
+            return false;
+        };
+        self.costs[index] = cost;
+        self.update_weights();
+        true
+    }
+
+    /// Empties every tile, back to a cost of 1
+    pub fn clear(&mut self) {
+        self.costs.fill(Some(1.0));
+        self.update_weights();
+    }
+
+    /// Works out the weight of every connection from the costs of the tiles.
+    /// Connections into a solid tile are blocked, as are diagonals which cut the corner
+    /// of a solid tile.
+    fn update_weights(&mut self) {
+        let weights = self
+            .pathfinder
+            .connections()
+            .iter()
+            .map(|&(a, b)| {
+                let ((ax, ay), (bx, by)) = (self.tile_of(a), self.tile_of(b));
+                let corners_solid =
+                    ax != bx && ay != by && (self.is_solid((ax, by)) || self.is_solid((bx, ay)));
+                match self.costs[b] {
+                    Some(cost) if self.costs[a].is_some() && !corners_solid => {
+                        Weight::from_multiplier(cost)
+                    }
+                    _ => Weight::BLOCKED,
+                }
+            })
+            .collect();
+        self.pathfinder.set_weights(weights);
+    }
+
+    /// Finds the cheapest route between two tiles, as the centre of each tile along it
+    pub fn find(&self, start: (usize, usize), end: (usize, usize)) -> Option<Vec<Vector>> {
+        let route = self.pathfinder.find(self.index(start)?, self.index(end)?)?;
+        Some(
+            route
+                .into_iter()
+                .map(|i| self.centre(self.tile_of(i)))
+                .collect(),
+        )
+    }
+
+    /// Fills a tile with a tower, as long as that leaves a route from `start` to `end`.
+    /// Returns whether the tile was filled.
+    pub fn occupy(
+        &mut self,
+        tile: (usize, usize),
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> bool {
+        let Some(old_cost) = self.cost(tile) else {
+            return false;
+        };
+        self.set_cost(tile, None);
+        if self.find(start, end).is_none() {
+            self.set_cost(tile, Some(old_cost));
+            return false;
+        }
+        true
+    }
+
+    /// Empties the grid and fills the tile under every tower, as long as that leaves a
+    /// route from `start` to `end`. If it doesn't, the grid is left as it was and this
+    /// returns false.
+    pub fn fill(&mut self, towers: &[Box<dyn Tower>], start: usize, end: usize) -> bool {
+        let (start, end) = (self.tile_of(start), self.tile_of(end));
+        let mut filled = self.clone();
+        filled.clear();
+        let sealed = towers.iter().any(|tower| {
+            filled
+                .tile_at(tower.position())
+                .map_or(false, |tile| !filled.occupy(tile, start, end))
+        });
+        if !sealed {
+            *self = filled;
+        }
+        !sealed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(width: usize, height: usize, connectivity: Connectivity) -> GridMap {
+        GridMap::new(GridLayout {
+            width,
+            height,
+            tile_size: 10.0,
+            origin: vec2d![0.0, 0.0],
+            connectivity,
+        })
+    }
+
+    #[test]
+    fn tiles_off_the_grid_are_solid() {
+        let mut grid = grid(3, 2, Connectivity::Four);
+        assert_eq!(grid.cost((2, 1)), Some(1.0));
+        assert_eq!(grid.cost((3, 0)), None);
+        assert_eq!(grid.cost((0, 2)), None);
+        assert!(grid.is_solid((5, 5)));
+        assert!(!grid.set_cost((3, 0), Some(2.0)));
+        assert!(grid.find((0, 0), (3, 0)).is_none());
+        assert!(!grid.occupy((0, 7), (0, 0), (2, 1)));
+    }
+
+    #[test]
+    fn occupy_refuses_to_seal_off_the_route() {
+        // a corridor one tile high, so any tile along it would cut it in two
+        let mut grid = grid(5, 1, Connectivity::Four);
+        for x in 1..4 {
+            assert!(!grid.occupy((x, 0), (0, 0), (4, 0)));
+            assert!(!grid.is_solid((x, 0)));
+        }
+        assert!(grid.find((0, 0), (4, 0)).is_some());
+    }
+
+    #[test]
+    fn occupy_allows_a_maze() {
+        let mut grid = grid(3, 3, Connectivity::Four);
+        // a wall down the middle with a gap at the bottom
+        assert!(grid.occupy((1, 0), (0, 0), (2, 0)));
+        assert!(grid.occupy((1, 1), (0, 0), (2, 0)));
+        assert!(!grid.occupy((1, 2), (0, 0), (2, 0)));
+        assert_eq!(grid.find((0, 0), (2, 0)).unwrap().len(), 7);
+        // the start and end can't be filled either
+        assert!(!grid.occupy((0, 0), (0, 0), (2, 0)));
+    }
+
+    #[test]
+    fn diagonals_cant_cut_corners() {
+        let mut grid = grid(2, 2, Connectivity::Eight);
+        assert_eq!(grid.find((0, 0), (1, 1)).unwrap().len(), 2);
+        assert!(grid.occupy((1, 0), (0, 0), (1, 1)));
+        assert_eq!(grid.find((0, 0), (1, 1)).unwrap().len(), 3);
+        assert!(!grid.occupy((0, 1), (0, 0), (1, 1)));
+    }
+}
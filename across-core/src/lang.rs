@@ -0,0 +1,123 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+/// Looks up the text shown to the player in the current language, like `format!`.
+/// The English text is the key, so anything without a translation stays in English.
+/// ```ignore
+/// tr!("Round {} complete!", round)
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($key:literal) => {
+        $crate::lang::translate($key)
+    };
+    ($key:literal, $($argument:expr),+ $(,)?) => {
+        $crate::lang::fill(
+            &$crate::lang::translate($key),
+            &[$(&$argument as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+/// Every language the game can be played in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    /// The language's name, in that language
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    /// The short code the language is saved as
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.code() == code.trim())
+    }
+
+    /// The language after this one, for cycling through them in the settings
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|x| x == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The language file, as lines of `English text = translated text`.
+    /// English has no file, as its text is already in the code.
+    fn file(&self) -> &'static str {
+        match self {
+            Language::English => "",
+            Language::French => include_str!("../lang/fr.lang"),
+        }
+    }
+}
+
+thread_local! {
+    /// The language being used, and its translations
+    static CURRENT: RefCell<(Language, HashMap<&'static str, &'static str>)> =
+        RefCell::new((Language::English, HashMap::new()));
+}
+
+/// Switches every piece of text to another language
+pub fn set_language(language: Language) {
+    let translations = language
+        .file()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    CURRENT.with(|current| *current.borrow_mut() = (language, translations));
+}
+
+pub fn language() -> Language {
+    CURRENT.with(|current| current.borrow().0)
+}
+
+/// The text in the current language, or the English text if it hasn't been translated
+pub fn translate(key: &str) -> String {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .1
+            .get(key)
+            .copied()
+            .unwrap_or(key)
+            .to_owned()
+    })
+}
+
+/// Puts each argument in place of a `{}` in the text, in order.
+/// Translations can't use `format!`, as they aren't known until the game runs.
+/// `{:.0}` and the like are filled in the same way, without the formatting.
+pub fn fill(template: &str, arguments: &[&dyn Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut arguments = arguments.iter();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        if let Some(argument) = arguments.next() {
+            text.push_str(&argument.to_string());
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    text
+}
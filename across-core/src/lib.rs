@@ -0,0 +1,62 @@
+pub mod ability;
+pub mod angle;
+pub mod animator;
+pub mod autoplay;
+pub mod build;
+pub mod bullet;
+pub mod cli;
+pub mod enemy;
+pub mod estimate;
+pub mod grid;
+pub mod lang;
+pub mod map;
+pub mod net;
+pub mod path;
+pub mod pathfind;
+pub mod polygon;
+pub mod registry;
+pub mod rng;
+pub mod shop;
+pub mod spatial;
+pub mod theme;
+pub mod tower;
+pub mod vector;
+pub mod versus;
+pub mod wave;
+pub mod world;
+
+use wave::Modifier;
+
+pub const SCREEN_WIDTH: usize = 1920;
+pub const SCREEN_HEIGHT: usize = 1080;
+
+pub const STARTING_MONEY: u64 = 50;
+/// The money earned for each enemy killed
+const KILL_REWARD: u64 = 2;
+
+/// The money earned for each enemy killed in a wave with `modifier`
+pub fn kill_reward(modifier: Option<Modifier>) -> u64 {
+    modifier.map_or(KILL_REWARD, |x| {
+        (KILL_REWARD as f32 * x.reward()).round() as u64
+    })
+}
+
+/// A zero-sized type to mark living enemies as alive
+/// This is so the compiler can find logic errors where dead enemies are left
+/// in the enemies array, for example
+#[derive(Debug, Clone, Copy)]
+pub struct Alive;
+/// A zero-sized type to mark enemies as dead
+/// as opposed to Alive
+#[derive(Debug, Clone, Copy)]
+pub struct Dead;
+
+/// Updating game elements such as enemies or bullets
+/// Can result in either a living element or a dead element
+/// This sum type defines those two possibilities so one function
+/// can return either of the two.
+#[derive(Debug)]
+pub enum Updated<AliveType, DeadType> {
+    Alive(AliveType),
+    Dead(DeadType),
+}
@@ -0,0 +1,548 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    build::BuildRules,
+    enemy::enemy::EnemyKind,
+    grid::{Connectivity, GridLayout, GridMap},
+    path::{Terrain, Web, WebCreationError},
+    polygon::Polygon,
+    theme::{NodeStyle, Rgb, Theme},
+    vec2d,
+    vector::Vector,
+    wave::{default_waves, Burst, Rush, Wave},
+};
+
+/// Where imported maps are installed, inside ggez's user config directory
+pub const MAPS_DIRECTORY: &str = "/maps";
+
+/// Everything needed to build a web, which can be loaded from a text file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Map {
+    pub name: String,
+    pub positions: Vec<Vector>,
+    pub connections: Vec<(usize, usize)>,
+    /// The indexes of the points enemies follow before any towers are placed
+    pub route: Vec<usize>,
+    /// Connections which curve towards a control point, rather than being straight
+    pub curves: Vec<((usize, usize), Vector)>,
+    /// The ground along connections which aren't plain, in both directions if they're
+    /// two-way
+    pub terrain: Vec<((usize, usize), Terrain)>,
+    /// Shapes which towers can't see through
+    pub obstacles: Vec<Polygon>,
+    /// The waves played on this map, or the normal ones if there aren't any
+    pub waves: Vec<Wave>,
+    /// Who made the map, if it was shared
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// How the map looks
+    pub theme: Theme,
+    /// Where towers can be built, and how many
+    pub build: BuildRules,
+    /// If there is one, the points and connections are the grid's tiles, which towers fill
+    #[serde(default)]
+    pub grid: Option<GridLayout>,
+}
+
+/// A line of a map file which couldn't be understood
+#[derive(Debug, Error)]
+#[error("line {line}: {message}")]
+pub struct MapParseError {
+    /// Starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl Map {
+    /// Reads a map from text like:
+    /// ```text
+    /// name Example
+    /// point 210 10
+    /// point 700 100
+    /// connection 0 1
+    /// two-way 1 2
+    /// curve 0 1 500 0
+    /// terrain 1 2 mud
+    /// obstacle 400 200 500 200 450 300
+    /// route 0 1
+    /// wave 5 90
+    /// wave 12 75 rush 6 10
+    /// scheduled-wave
+    /// burst 0 5
+    /// burst 120 1 carrier
+    /// burst 30 3 brave
+    /// author Someone
+    /// description A short map
+    /// background 10 15 40
+    /// texture /textures/night.png
+    /// path-colour 200 220 255
+    /// nodes diamond
+    /// build-zone 0 0 400 0 400 300 0 300
+    /// no-build 600 600 700 600 650 700
+    /// tower-limit 12
+    /// tower-limit 3 Wall
+    /// ```
+    /// A grid map has a `grid` line instead of points and connections, with its width and
+    /// height in tiles, the size of each tile and its top-left corner, and `diagonal` if
+    /// enemies can walk diagonally:
+    /// ```text
+    /// grid 16 12 50 100 50 diagonal
+    /// route 0 191
+    /// ```
+    /// Its points are the centres of its tiles, row by row, and its route only needs the
+    /// tiles enemies start and end at, as the rest is found across the grid.
+    /// Connections are one way, from the first point to the second, and `two-way` adds a
+    /// connection in each direction.
+    /// Terrain is `mud`, which slows enemies down, `road`, which speeds them up, or `water`,
+    /// which only flying enemies can cross.
+    /// A wave is a number of enemies and the steps between them, optionally followed by
+    /// `rush`, the number of enemies before the rush, and the steps between enemies in it.
+    /// A scheduled wave is made of the `burst` lines after it, each the steps since the last
+    /// burst, how many enemies spawn at once, and optionally which kind they all are.
+    /// Colours are red, green and blue from 0 to 255, and points can be drawn as a `circle`,
+    /// `square`, `diamond`, or `hidden`.
+    /// If there are any build zones, towers can only be built inside them, and never inside
+    /// a no-build zone. A tower limit caps every tower, or only those with the name after it.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, MapParseError> {
+        let mut map = Map {
+            name: "Unnamed".to_owned(),
+            positions: vec![],
+            connections: vec![],
+            route: vec![],
+            curves: vec![],
+            terrain: vec![],
+            obstacles: vec![],
+            waves: vec![],
+            author: None,
+            description: None,
+            theme: Theme::default(),
+            build: BuildRules::default(),
+            grid: None,
+        };
+        // the line of the grid, for errors found once every line is read
+        let mut grid_line = 0;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| MapParseError {
+                line: i + 1,
+                message: message.to_owned(),
+            };
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match keyword {
+                "name" => map.name = rest.trim().to_owned(),
+                "point" => {
+                    let numbers = parse_numbers::<f32>(rest)
+                        .filter(|x| x.len() == 2)
+                        .ok_or_else(|| error("a point needs an x and a y"))?;
+                    map.positions.push(vec2d![numbers[0], numbers[1]]);
+                }
+                "connection" => {
+                    let numbers = parse_numbers::<usize>(rest)
+                        .filter(|x| x.len() == 2)
+                        .ok_or_else(|| error("a connection needs two point indexes"))?;
+                    map.connections.push((numbers[0], numbers[1]));
+                }
+                "two-way" => {
+                    let numbers = parse_numbers::<usize>(rest)
+                        .filter(|x| x.len() == 2)
+                        .ok_or_else(|| error("a two-way connection needs two point indexes"))?;
+                    map.connections.push((numbers[0], numbers[1]));
+                    map.connections.push((numbers[1], numbers[0]));
+                }
+                "curve" => {
+                    let message = "a curve needs two point indexes and a control point";
+                    let words: Vec<_> = rest.split_whitespace().collect();
+                    let [a, b, x, y] = words[..] else {
+                        return Err(error(message));
+                    };
+                    match (a.parse(), b.parse(), x.parse(), y.parse()) {
+                        (Ok(a), Ok(b), Ok(x), Ok(y)) => map.curves.push(((a, b), vec2d![x, y])),
+                        _ => return Err(error(message)),
+                    }
+                }
+                "terrain" => {
+                    let message = "terrain needs two point indexes and mud, road or water";
+                    let words: Vec<_> = rest.split_whitespace().collect();
+                    let [a, b, terrain] = words[..] else {
+                        return Err(error(message));
+                    };
+                    match (a.parse(), b.parse(), Terrain::from_name(terrain)) {
+                        (Ok(a), Ok(b), Some(terrain)) => map.terrain.push(((a, b), terrain)),
+                        _ => return Err(error(message)),
+                    }
+                }
+                "obstacle" => map.obstacles.push(
+                    parse_polygon(rest)
+                        .ok_or_else(|| error("an obstacle needs at least 3 points"))?,
+                ),
+                "build-zone" => map.build.zones.push(
+                    parse_polygon(rest)
+                        .ok_or_else(|| error("a build zone needs at least 3 points"))?,
+                ),
+                "no-build" => map.build.forbidden.push(
+                    parse_polygon(rest)
+                        .ok_or_else(|| error("a no-build zone needs at least 3 points"))?,
+                ),
+                "tower-limit" => {
+                    let message = "a tower limit needs a count, and optionally a tower's name";
+                    let (count, name) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+                    let count = count.parse().map_err(|_| error(message))?;
+                    match name.trim() {
+                        "" => map.build.limit = Some(count),
+                        name => map.build.kind_limits.push((name.to_owned(), count)),
+                    }
+                }
+                "route" => {
+                    map.route = parse_numbers::<usize>(rest)
+                        .ok_or_else(|| error("a route is a list of point indexes"))?;
+                }
+                "wave" => {
+                    let message = "a wave needs a count and an interval, and optionally \
+                        'rush' with a count and an interval";
+                    let (wave, rush) = match rest.split_once("rush") {
+                        Some((wave, rush)) => (wave, Some(rush)),
+                        None => (rest, None),
+                    };
+                    let wave = parse_numbers::<usize>(wave)
+                        .filter(|x| x.len() == 2)
+                        .ok_or_else(|| error(message))?;
+                    let rush = match rush {
+                        Some(rush) => {
+                            let rush = parse_numbers::<usize>(rush)
+                                .filter(|x| x.len() == 2)
+                                .ok_or_else(|| error(message))?;
+                            Some(Rush {
+                                after: rush[0],
+                                interval: rush[1],
+                            })
+                        }
+                        None => None,
+                    };
+                    map.waves.push(Wave {
+                        count: wave[0],
+                        interval: wave[1],
+                        rush,
+                        schedule: vec![],
+                    });
+                }
+                "scheduled-wave" => map.waves.push(Wave::scheduled(vec![])),
+                "burst" => {
+                    let message = "a burst needs a delay and a count, and optionally an \
+                        enemy kind, and must follow a scheduled wave";
+                    let words: Vec<_> = rest.split_whitespace().collect();
+                    let (delay, count, kind) = match words[..] {
+                        [delay, count] => (delay, count, None),
+                        [delay, count, kind] => (delay, count, Some(kind)),
+                        _ => return Err(error(message)),
+                    };
+                    let kind = match kind {
+                        Some(kind) => Some(
+                            EnemyKind::from_name(kind)
+                                .ok_or_else(|| error(&format!("unknown enemy kind '{}'", kind)))?,
+                        ),
+                        None => None,
+                    };
+                    // a scheduled wave has no enemies until its first burst
+                    let wave = map
+                        .waves
+                        .last_mut()
+                        .filter(|x| x.is_scheduled() || x.count == 0)
+                        .ok_or_else(|| error(message))?;
+                    match (delay.parse(), count.parse()) {
+                        (Ok(delay), Ok(count)) => wave.push_burst(Burst { delay, count, kind }),
+                        _ => return Err(error(message)),
+                    }
+                }
+                "author" => map.author = Some(rest.trim().to_owned()),
+                "description" => map.description = Some(rest.trim().to_owned()),
+                "background" => {
+                    map.theme.background = Some(
+                        parse_colour(rest).ok_or_else(|| error("a background needs a colour"))?,
+                    );
+                }
+                "texture" => map.theme.texture = Some(rest.trim().to_owned()),
+                "path-colour" => {
+                    map.theme.path = Some(
+                        parse_colour(rest).ok_or_else(|| error("a path colour needs a colour"))?,
+                    );
+                }
+                "nodes" => {
+                    map.theme.nodes = NodeStyle::from_name(rest.trim()).ok_or_else(|| {
+                        error("nodes can be 'circle', 'square', 'diamond' or 'hidden'")
+                    })?;
+                }
+                "grid" => {
+                    let message = "a grid needs a width, a height, a tile size and a corner, \
+                        and optionally 'diagonal'";
+                    let (numbers, connectivity) = match rest.trim().strip_suffix("diagonal") {
+                        Some(numbers) => (numbers, Connectivity::Eight),
+                        None => (rest, Connectivity::Four),
+                    };
+                    let numbers = parse_numbers::<f32>(numbers)
+                        .filter(|x| x.len() == 5 && x[0] >= 1.0 && x[1] >= 1.0 && x[2] > 0.0)
+                        .ok_or_else(|| error(message))?;
+                    map.grid = Some(GridLayout {
+                        width: numbers[0] as usize,
+                        height: numbers[1] as usize,
+                        tile_size: numbers[2],
+                        origin: vec2d![numbers[3], numbers[4]],
+                        connectivity,
+                    });
+                    grid_line = i + 1;
+                }
+                _ => return Err(error(&format!("unknown keyword '{}'", keyword))),
+            }
+        }
+        if let Some(layout) = map.grid {
+            map.fill_grid(layout).map_err(|message| MapParseError {
+                line: grid_line,
+                message: message.to_owned(),
+            })?;
+        }
+        Ok(map)
+    }
+
+    /// Makes the points and connections the grid's tiles, and finds the route across it
+    /// between the first and last points of the map's route
+    fn fill_grid(&mut self, layout: GridLayout) -> Result<(), &'static str> {
+        if !self.positions.is_empty() || !self.connections.is_empty() {
+            return Err("a grid map can't have its own points or connections");
+        }
+        let grid = GridMap::new(layout);
+        let (Some(&start), Some(&end)) = (self.route.first(), self.route.last()) else {
+            return Err("a grid map needs a route from one tile to another");
+        };
+        self.route = grid
+            .pathfinder()
+            .find(start, end)
+            .filter(|route| route.len() >= 2)
+            .ok_or("the grid has no route between the ends of the map's route")?;
+        self.positions = grid.positions();
+        self.connections = grid.pathfinder().connections().clone();
+        Ok(())
+    }
+
+    /// Writes the map as text which `parse` can read back, to share it with other players
+    pub fn to_text(&self) -> String {
+        let mut text = format!("name {}\n", self.name);
+        if let Some(author) = &self.author {
+            text.push_str(&format!("author {}\n", author));
+        }
+        if let Some(description) = &self.description {
+            text.push_str(&format!("description {}\n", description));
+        }
+        if let Some(background) = self.theme.background {
+            text.push_str(&format!("background {}\n", colour_to_text(background)));
+        }
+        if let Some(texture) = &self.theme.texture {
+            text.push_str(&format!("texture {}\n", texture));
+        }
+        if let Some(path) = self.theme.path {
+            text.push_str(&format!("path-colour {}\n", colour_to_text(path)));
+        }
+        if self.theme.nodes != NodeStyle::default() {
+            text.push_str(&format!("nodes {}\n", self.theme.nodes.name()));
+        }
+        // the points and connections of a grid map come from its grid
+        if let Some(grid) = self.grid {
+            text.push_str(&format!(
+                "grid {} {} {} {} {}{}\n",
+                grid.width,
+                grid.height,
+                grid.tile_size,
+                grid.origin.x,
+                grid.origin.y,
+                if grid.connectivity == Connectivity::Eight {
+                    " diagonal"
+                } else {
+                    ""
+                }
+            ));
+        } else {
+            for position in &self.positions {
+                text.push_str(&format!("point {} {}\n", position.x, position.y));
+            }
+            for (a, b) in &self.connections {
+                text.push_str(&format!("connection {} {}\n", a, b));
+            }
+        }
+        for ((a, b), control) in &self.curves {
+            text.push_str(&format!("curve {} {} {} {}\n", a, b, control.x, control.y));
+        }
+        for ((a, b), terrain) in &self.terrain {
+            text.push_str(&format!("terrain {} {} {}\n", a, b, terrain.name()));
+        }
+        for obstacle in &self.obstacles {
+            text.push_str(&format!("obstacle {}\n", polygon_to_text(obstacle)));
+        }
+        for zone in &self.build.zones {
+            text.push_str(&format!("build-zone {}\n", polygon_to_text(zone)));
+        }
+        for zone in &self.build.forbidden {
+            text.push_str(&format!("no-build {}\n", polygon_to_text(zone)));
+        }
+        if let Some(limit) = self.build.limit {
+            text.push_str(&format!("tower-limit {}\n", limit));
+        }
+        for (name, limit) in &self.build.kind_limits {
+            text.push_str(&format!("tower-limit {} {}\n", limit, name));
+        }
+        let route: Vec<_> = match (self.grid, self.route.first(), self.route.last()) {
+            (Some(_), Some(start), Some(end)) => vec![start.to_string(), end.to_string()],
+            _ => self.route.iter().map(|x| x.to_string()).collect(),
+        };
+        text.push_str(&format!("route {}\n", route.join(" ")));
+        for wave in &self.waves {
+            if wave.is_scheduled() {
+                text.push_str("scheduled-wave\n");
+                for burst in &wave.schedule {
+                    text.push_str(&format!("burst {} {}", burst.delay, burst.count));
+                    if let Some(kind) = burst.kind {
+                        text.push_str(&format!(" {}", kind.name()));
+                    }
+                    text.push('\n');
+                }
+                continue;
+            }
+            text.push_str(&format!("wave {} {}", wave.count, wave.interval));
+            if let Some(rush) = wave.rush {
+                text.push_str(&format!(" rush {} {}", rush.after, rush.interval));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Where the map is saved when it's installed, named after the map
+    pub fn install_path(&self) -> String {
+        let file_name: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}/{}.map", MAPS_DIRECTORY, file_name)
+    }
+
+    /// The waves to play on this map
+    pub fn waves(&self) -> Vec<Wave> {
+        if self.waves.is_empty() {
+            default_waves()
+        } else {
+            self.waves.clone()
+        }
+    }
+
+    pub fn build_web(&self) -> Result<Web, WebCreationError> {
+        let mut web = Web::new(
+            self.positions.clone(),
+            self.connections.clone(),
+            self.route.clone(),
+        )?;
+        web.set_obstacles(self.obstacles.clone());
+        if let Some(layout) = self.grid {
+            if !web.set_grid(GridMap::new(layout)) {
+                return Err(WebCreationError::InvalidGrid);
+            }
+        }
+        let invalid_curves: Vec<_> = self
+            .curves
+            .iter()
+            .filter(|&&(connection, control)| !web.set_curve(connection, control))
+            .map(|&(connection, _)| connection)
+            .collect();
+        if !invalid_curves.is_empty() {
+            return Err(WebCreationError::InvalidCurves {
+                connections: invalid_curves,
+            });
+        }
+        let invalid_terrain: Vec<_> = self
+            .terrain
+            .iter()
+            .filter(|&&((a, b), terrain)| {
+                // `|` rather than `||`, so the way back is tagged too
+                !(web.set_terrain((a, b), terrain) | web.set_terrain((b, a), terrain))
+            })
+            .map(|&(connection, _)| connection)
+            .collect();
+        if invalid_terrain.is_empty() {
+            Ok(web)
+        } else {
+            Err(WebCreationError::InvalidTerrain {
+                connections: invalid_terrain,
+            })
+        }
+    }
+}
+
+impl Default for Map {
+    /// The map played when no other map is chosen
+    fn default() -> Self {
+        Self {
+            name: "Default".to_owned(),
+            positions: vec![
+                vec2d![210.0, 10.0],
+                vec2d![700.0, 100.0],
+                vec2d![350.0, 200.0],
+                vec2d![1000.0, 1000.0],
+            ],
+            connections: vec![(0, 1), (1, 3), (0, 2), (1, 2), (2, 3)],
+            route: vec![0, 1, 3],
+            curves: vec![],
+            terrain: vec![],
+            obstacles: vec![],
+            waves: vec![],
+            author: None,
+            description: None,
+            theme: Theme::default(),
+            build: BuildRules::default(),
+            grid: None,
+        }
+    }
+}
+
+/// Parses whitespace separated numbers, or returns None if any of them aren't numbers
+fn parse_numbers<T: std::str::FromStr>(text: &str) -> Option<Vec<T>> {
+    text.split_whitespace().map(|x| x.parse().ok()).collect()
+}
+
+/// Parses a polygon's points as x and y pairs, or returns None if there are fewer than 3
+fn parse_polygon(text: &str) -> Option<Polygon> {
+    let numbers = parse_numbers::<f32>(text).filter(|x| x.len() >= 6 && x.len() % 2 == 0)?;
+    Some(Polygon::new(
+        numbers.chunks(2).map(|x| vec2d![x[0], x[1]]).collect(),
+    ))
+}
+
+/// Writes a polygon as `parse_polygon` reads it
+fn polygon_to_text(polygon: &Polygon) -> String {
+    let points: Vec<_> = polygon
+        .points()
+        .iter()
+        .map(|point| format!("{} {}", point.x, point.y))
+        .collect();
+    points.join(" ")
+}
+
+/// Parses a colour as its red, green and blue, from 0 to 255
+fn parse_colour(text: &str) -> Option<Rgb> {
+    match parse_numbers::<u8>(text)?[..] {
+        [r, g, b] => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// Writes a colour as `parse_colour` reads it
+fn colour_to_text((r, g, b): Rgb) -> String {
+    format!("{} {} {}", r, g, b)
+}
@@ -0,0 +1,283 @@
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::{enemy::enemy::EnemyKind, vec2d, vector::Vector};
+
+/// The port games are hosted on
+pub const PORT: u16 = 7878;
+/// How long joining waits for the host to answer before giving up
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What the players tell each other, one per line.
+/// In co-op, the host runs the game, so towers sent to the host are requests, and towers
+/// sent by the host have already been placed or sold.
+/// In versus, each player runs their own game, and only enemies are sent.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Sent by the host when someone joins, so both games start from the same seed
+    Welcome {
+        seed: u64,
+        versus: bool,
+        map: String,
+    },
+    Place {
+        name: String,
+        position: Vector,
+    },
+    /// The tower at a position
+    Sell {
+        position: Vector,
+    },
+    /// The money and lives the players share, sent by the host whenever they change
+    Shared {
+        money: u64,
+        lives: u32,
+    },
+    /// Enemies bought in versus, to add to the other player's next wave
+    Send {
+        kind: EnemyKind,
+        count: usize,
+    },
+    /// Sent by the co-op host as it starts a round, as only the host can start them
+    StartRound {
+        round: usize,
+    },
+}
+
+impl Message {
+    fn to_line(&self) -> String {
+        match self {
+            Message::Welcome { seed, versus, map } => {
+                format!("welcome {} {} {}\n", seed, versus, map)
+            }
+            Message::Place { name, position } => {
+                format!("place {} {} {}\n", position.x, position.y, name)
+            }
+            Message::Sell { position } => format!("sell {} {}\n", position.x, position.y),
+            Message::Shared { money, lives } => format!("shared {} {}\n", money, lives),
+            Message::Send { kind, count } => format!("send {:?} {}\n", kind, count),
+            Message::StartRound { round } => format!("round {}\n", round),
+        }
+    }
+
+    /// Reads a line, or returns None if it isn't a message
+    fn parse(line: &str) -> Option<Self> {
+        let (keyword, rest) = line.split_once(' ')?;
+        // names come last, as they can have spaces in
+        let mut words = rest.splitn(3, ' ');
+        let message = match keyword {
+            "welcome" => Message::Welcome {
+                seed: words.next()?.parse().ok()?,
+                versus: words.next()?.parse().ok()?,
+                map: words.next()?.to_owned(),
+            },
+            "place" => Message::Place {
+                position: vec2d![words.next()?.parse().ok()?, words.next()?.parse().ok()?],
+                name: words.next()?.to_owned(),
+            },
+            "sell" => Message::Sell {
+                position: vec2d![words.next()?.parse().ok()?, words.next()?.parse().ok()?],
+            },
+            "shared" => Message::Shared {
+                money: words.next()?.parse().ok()?,
+                lives: words.next()?.parse().ok()?,
+            },
+            "send" => {
+                let kind = words.next()?;
+                Message::Send {
+                    kind: EnemyKind::ALL
+                        .into_iter()
+                        .find(|x| format!("{:?}", x) == kind)?,
+                    count: words.next()?.parse().ok()?,
+                }
+            }
+            "round" => Message::StartRound {
+                round: words.next()?.parse().ok()?,
+            },
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+/// A connection to the other player, which never waits for anything to arrive
+#[derive(Debug)]
+struct Connection {
+    stream: TcpStream,
+    /// What's arrived since the last full line
+    unread: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            unread: vec![],
+        })
+    }
+
+    fn send(&mut self, message: &Message) -> io::Result<()> {
+        // messages are tiny, so wait for them to be sent rather than keeping them around
+        self.stream.set_nonblocking(false)?;
+        let result = self.stream.write_all(message.to_line().as_bytes());
+        self.stream.set_nonblocking(true)?;
+        result
+    }
+
+    /// Every message which has arrived since the last call.
+    /// An error means the other player has gone.
+    fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut buffer = [0; 1024];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(io::ErrorKind::ConnectionAborted.into()),
+                Ok(read) => self.unread.extend_from_slice(&buffer[..read]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        let mut messages = vec![];
+        while let Some(end) = self.unread.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<_> = self.unread.drain(..=end).collect();
+            match Message::parse(String::from_utf8_lossy(&line).trim()) {
+                Some(message) => messages.push(message),
+                None => eprintln!("Ignoring a message which couldn't be read"),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// A co-op game over the local network, from one player's side
+#[derive(Debug)]
+pub struct Session {
+    /// Where players join from, if this player is hosting
+    listener: Option<TcpListener>,
+    peer: Option<Connection>,
+    /// The money and lives last shared, so they're only sent again when they change
+    last_shared: Option<(u64, u32)>,
+    /// Whether the players are against each other, rather than working together.
+    /// Players who join find out from the host.
+    versus: bool,
+}
+
+impl Session {
+    /// Starts waiting for another player to join
+    pub fn host(versus: bool) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", PORT))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener: Some(listener),
+            peer: None,
+            last_shared: None,
+            versus,
+        })
+    }
+
+    /// Joins a hosted game, from an address like "192.168.1.5".
+    /// The port can be left out if it's the usual one.
+    /// Gives up after `CONNECT_TIMEOUT`, so an unreachable host doesn't freeze the game.
+    pub fn join(address: &str) -> io::Result<Self> {
+        let addresses: Vec<SocketAddr> = if address.contains(':') {
+            address.to_socket_addrs()?.collect()
+        } else {
+            (address, PORT).to_socket_addrs()?.collect()
+        };
+        let mut error = io::Error::new(io::ErrorKind::NotFound, "no address to connect to");
+        let mut stream = None;
+        for address in addresses {
+            match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(e) => error = e,
+            }
+        }
+        let stream = stream.ok_or(error)?;
+        Ok(Self {
+            listener: None,
+            peer: Some(Connection::new(stream)?),
+            last_shared: None,
+            versus: false,
+        })
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.listener.is_some()
+    }
+
+    pub fn is_versus(&self) -> bool {
+        self.versus
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.peer.is_some()
+    }
+
+    /// Lets in a player who's waiting to join, if this player is hosting and nobody has
+    /// joined yet. Returns whether someone joined.
+    pub fn accept(&mut self) -> bool {
+        let Some(listener) = self.listener.as_ref().filter(|_| self.peer.is_none()) else {
+            return false;
+        };
+        match listener
+            .accept()
+            .and_then(|(stream, _)| Connection::new(stream))
+        {
+            Ok(connection) => {
+                self.peer = Some(connection);
+                self.last_shared = None;
+                true
+            }
+            Err(e) => {
+                if e.kind() != io::ErrorKind::WouldBlock {
+                    eprintln!("Failed to accept a player: {}", e);
+                }
+                false
+            }
+        }
+    }
+
+    /// Sends a message to the other player, if there is one.
+    /// If they've gone, the host waits for someone else to join.
+    pub fn send(&mut self, message: &Message) {
+        if let Some(Err(e)) = self.peer.as_mut().map(|peer| peer.send(message)) {
+            eprintln!("Lost the other player: {}", e);
+            self.peer = None;
+        }
+    }
+
+    /// Sends the money and lives, if they've changed since they were last sent
+    pub fn share(&mut self, money: u64, lives: u32) {
+        if self.peer.is_some() && self.last_shared != Some((money, lives)) {
+            self.last_shared = Some((money, lives));
+            self.send(&Message::Shared { money, lives });
+        }
+    }
+
+    /// Every message from the other player since the last call
+    pub fn receive(&mut self) -> Vec<Message> {
+        match self.peer.as_mut().map(|peer| peer.receive()) {
+            Some(Ok(messages)) => {
+                for message in &messages {
+                    if let Message::Welcome { versus, .. } = message {
+                        self.versus = *versus;
+                    }
+                }
+                messages
+            }
+            Some(Err(e)) => {
+                eprintln!("Lost the other player: {}", e);
+                self.peer = None;
+                vec![]
+            }
+            None => vec![],
+        }
+    }
+}
@@ -0,0 +1,1127 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{
+    grid::{GridLayout, GridMap},
+    pathfind::{Pathfinder, RouteProfile, Weight},
+    polygon::Polygon,
+    tower::tower::{blend_coverage, can_see, Tower},
+    vector::Vector,
+};
+
+/// How many straight lines each curved connection is split into
+const CURVE_SAMPLES: usize = 16;
+
+/// A point along a quadratic Bézier curve from `a` to `b`, bending towards `control`
+fn bezier(a: Vector, control: Vector, b: Vector, t: f32) -> Vector {
+    a * ((1.0 - t) * (1.0 - t)) + control * (2.0 * (1.0 - t) * t) + b * (t * t)
+}
+
+/// The shortest distance from `point` to the line from `a` to `b`
+fn distance_to_line(point: Vector, a: Vector, b: Vector) -> f32 {
+    let direction = b - a;
+    let t = ((point - a).dot(direction) / direction.sqr_length()).clamp(0.0, 1.0);
+    (a + direction * t - point).length()
+}
+
+/// The weight of each connection for a profile, from its coverage
+fn profile_weights(coverage: &[Option<f32>], profile: RouteProfile) -> Vec<Weight> {
+    coverage
+        .iter()
+        .map(|x| x.map_or(Weight::BLOCKED, |x| profile.weight(x)))
+        .collect()
+}
+
+impl Serialize for Web {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WebLayout {
+            positions: self.positions(),
+            connections: self.pathfinder.connections().clone(),
+            route: self.routes[0].1.clone(),
+            curves: self.curves(),
+            obstacles: self.obstacles.clone(),
+            terrain: self.terrain(),
+            grid: self.grid.as_ref().map(GridMap::layout),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Web {
+    /// Fails in the same ways as `Web::new`, if a curve's or terrain's connection
+    /// doesn't exist, or if the grid doesn't match the points and connections
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let layout = WebLayout::deserialize(deserializer)?;
+        let mut web = Web::new(layout.positions, layout.connections, layout.route)
+            .map_err(|e| D::Error::custom(format!("invalid web: {}", e)))?;
+        web.set_obstacles(layout.obstacles);
+        for (connection, control) in layout.curves {
+            if !web.set_curve(connection, control) {
+                return Err(D::Error::custom(format!(
+                    "invalid web: no connection {:?} to curve",
+                    connection
+                )));
+            }
+        }
+        for (connection, terrain) in layout.terrain {
+            if !web.set_terrain(connection, terrain) {
+                return Err(D::Error::custom(format!(
+                    "invalid web: no connection {:?} to put {} on",
+                    connection,
+                    terrain.name()
+                )));
+            }
+        }
+        if let Some(grid) = layout.grid {
+            if !web.set_grid(GridMap::new(grid)) {
+                return Err(D::Error::custom(format!(
+                    "invalid web: {}",
+                    WebCreationError::InvalidGrid
+                )));
+            }
+        }
+        Ok(web)
+    }
+}
+
+/// What the ground along a connection is like, which changes how fast enemies cross it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Terrain {
+    #[default]
+    Plain,
+    /// Slows enemies down
+    Mud,
+    /// Speeds enemies up
+    Road,
+    /// Only flying enemies can cross it
+    Water,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 4] = [Terrain::Plain, Terrain::Mud, Terrain::Road, Terrain::Water];
+
+    /// The name used in map files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Terrain::Plain => "plain",
+            Terrain::Mud => "mud",
+            Terrain::Road => "road",
+            Terrain::Water => "water",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    /// How fast enemies move across it, compared to plain ground
+    pub fn speed(&self) -> f32 {
+        match self {
+            Terrain::Plain | Terrain::Water => 1.0,
+            Terrain::Mud => 0.5,
+            Terrain::Road => 1.5,
+        }
+    }
+
+    /// What the pathfinder multiplies the cost of crossing it by, which is how much longer
+    /// it takes than plain ground.
+    /// No enemies fly yet, so water can't be crossed at all.
+    pub fn base_weight(&self) -> f32 {
+        match self {
+            Terrain::Water => f32::INFINITY,
+            terrain => 1.0 / terrain.speed(),
+        }
+    }
+}
+
+/// The total length of the lines joining each point to the next
+fn polyline_length(points: &[Vector]) -> f32 {
+    points.windows(2).map(|x| (x[1] - x[0]).length()).sum()
+}
+
+#[derive(Debug, Error)]
+#[error("{point_count} points, with no connection after points {invalid_connections:?}")]
+pub struct RouteCreationError {
+    invalid_connections: Vec<usize>,
+    point_count: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum WebCreationError {
+    #[error("the route doesn't follow the connections")]
+    InvalidRoute,
+    #[error("connections {invalid_connections:?} go to points past the last of {point_count}")]
+    InvalidConnections {
+        invalid_connections: Vec<(usize, usize)>,
+        point_count: usize,
+    },
+    /// Pairs of points which are in the same place
+    #[error("points {duplicates:?} are in the same place")]
+    DuplicatePoints { duplicates: Vec<(usize, usize)> },
+    /// Connections from a point back to itself
+    #[error("connections {connections:?} go from a point back to itself")]
+    SelfLoops { connections: Vec<(usize, usize)> },
+    /// Connections between two different points which are in the same place
+    #[error("connections {connections:?} join points in the same place")]
+    ZeroLengthConnections { connections: Vec<(usize, usize)> },
+    /// Curves given for connections which don't exist
+    #[error("curves are given for connections {connections:?}, which don't exist")]
+    InvalidCurves { connections: Vec<(usize, usize)> },
+    /// Terrain given for connections which don't exist
+    #[error("terrain is given for connections {connections:?}, which don't exist")]
+    InvalidTerrain { connections: Vec<(usize, usize)> },
+    /// A grid whose tiles aren't the web's points and connections
+    #[error("the grid's tiles don't match the points and connections")]
+    InvalidGrid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    points: Vec<Vector>,
+    length: f32,
+    /// The index of the web connection each segment of the route travels along
+    connections: Vec<usize>,
+    /// How fast enemies move along each segment, from its terrain
+    #[serde(default)]
+    speeds: Vec<f32>,
+}
+
+impl Route {
+    fn new(points: &Vec<Rc<RefCell<Point>>>) -> Result<Self, RouteCreationError> {
+        let mut invalid_connections = vec![];
+        for (i, point) in points.iter().enumerate().skip(1) {
+            // this was checking if the next point was a neighbour of the previous point, which led to runtime errors
+            if !points[i - 1].borrow().is_neighbour(point) {
+                invalid_connections.push(i - 1);
+            }
+        }
+        if invalid_connections.len() > 0 || points.len() < 2 {
+            Err(RouteCreationError {
+                invalid_connections,
+                point_count: points.len(),
+            })
+        } else {
+            let positions: Vec<_> = points.iter().map(|x| *x.borrow().position()).collect();
+            Ok(Self {
+                length: polyline_length(&positions),
+                points: positions,
+                connections: vec![],
+                speeds: vec![],
+            })
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn points(&self) -> &Vec<Vector> {
+        &self.points
+    }
+
+    /// The index of the web connection each segment of the route travels along
+    pub fn connections(&self) -> &Vec<usize> {
+        &self.connections
+    }
+
+    /// A copy of the route with its corners rounded off, so enemies turn smoothly rather
+    /// than sharply. At most `radius` is cut from each side of a corner, and never more
+    /// than half of a segment, so neighbouring corners don't overlap.
+    pub fn rounded(&self, radius: f32) -> Route {
+        const CORNER_SAMPLES: usize = 4;
+        let last = self.points.len() - 1;
+        let connection = |segment: usize| self.connections.get(segment).copied();
+        let speed = |segment: usize| self.speeds.get(segment).copied().unwrap_or(1.0);
+        let mut points = vec![self.points[0]];
+        let mut connections = vec![];
+        let mut speeds = vec![];
+        for i in 1..last {
+            let (before, corner, after) = (self.points[i - 1], self.points[i], self.points[i + 1]);
+            let enter =
+                corner + (before - corner).clamp_length(radius.min(before.distance(corner) / 2.0));
+            let exit =
+                corner + (after - corner).clamp_length(radius.min(corner.distance(after) / 2.0));
+            if !enter.approx_eq(points[points.len() - 1], 1e-3) {
+                points.push(enter);
+                connections.push(connection(i - 1));
+                speeds.push(speed(i - 1));
+            }
+            // the first half of the corner is still on the connection into it
+            for j in 1..=CORNER_SAMPLES {
+                points.push(bezier(
+                    enter,
+                    corner,
+                    exit,
+                    j as f32 / CORNER_SAMPLES as f32,
+                ));
+                let segment = if j <= CORNER_SAMPLES / 2 { i - 1 } else { i };
+                connections.push(connection(segment));
+                speeds.push(speed(segment));
+            }
+        }
+        if !self.points[last].approx_eq(points[points.len() - 1], 1e-3) {
+            points.push(self.points[last]);
+            connections.push(connection(last - 1));
+            speeds.push(speed(last - 1));
+        }
+        Route {
+            length: polyline_length(&points),
+            points,
+            connections: connections
+                .into_iter()
+                .collect::<Option<_>>()
+                .unwrap_or_default(),
+            speeds,
+        }
+    }
+
+    /// Finds which segment of the route is at this point of progress.
+    /// Segment i goes from point i to point i + 1.
+    pub fn segment_at(&self, progress: f32) -> Option<usize> {
+        if progress < 0.0 || progress > 1.0 {
+            None
+        } else {
+            let mut progress_made = 0.0;
+            for (i, dist) in self
+                .points
+                .iter()
+                .skip(1)
+                .enumerate()
+                .map(|(i, &x)| (i, (x - self.points[i]).length() / self.length))
+            {
+                if progress_made + dist >= progress {
+                    return Some(i);
+                }
+                progress_made += dist;
+            }
+            Some(self.points.len() - 2)
+        }
+    }
+
+    /// The direction the route is heading in at this point of progress, normalised
+    pub fn direction_at(&self, progress: f32) -> Option<Vector> {
+        let i = self.segment_at(progress)?;
+        let along = self.points[i + 1] - self.points[i];
+        (along.sqr_length() > 0.0).then(|| along.normalised())
+    }
+
+    /// How fast enemies move at this point of progress, compared to plain ground
+    pub fn speed_at(&self, progress: f32) -> f32 {
+        self.segment_at(progress)
+            .and_then(|i| self.speeds.get(i).copied())
+            .unwrap_or(1.0)
+    }
+
+    /// Finds which web connection is at this point of progress
+    pub fn connection_at(&self, progress: f32) -> Option<usize> {
+        self.segment_at(progress)
+            .and_then(|i| self.connections.get(i).copied())
+    }
+
+    /// How far along the route the closest point to `point` is, normalised [0-1]
+    pub fn progress_nearest(&self, point: Vector) -> f32 {
+        let mut travelled = 0.0;
+        let mut closest = (f32::INFINITY, 0.0);
+        for pair in self.points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let length = (b - a).length();
+            let along = if length > 0.0 {
+                ((point - a).dot(b - a) / (length * length)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance = (a + (b - a) * along - point).sqr_length();
+            if distance < closest.0 {
+                closest = (distance, travelled + along * length);
+            }
+            travelled += length;
+        }
+        if self.length > 0.0 {
+            closest.1 / self.length
+        } else {
+            0.0
+        }
+    }
+
+    /// The progress after travelling a world `distance` further along the route from
+    /// `progress`, which is never past the end
+    pub fn advance(&self, progress: f32, distance: f32) -> f32 {
+        if self.length > 0.0 {
+            (progress + distance / self.length).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn get_position(&self, progress: f32) -> Option<Vector> {
+        if progress < 0.0 || progress > 1.0 {
+            None
+        } else {
+            let mut progress_made = 0.0;
+            for (a, b, dist) in self
+                .points
+                .iter()
+                .skip(1)
+                .enumerate()
+                .map(|(i, &x)| (self.points[i], x, (x - self.points[i]).length()))
+                .map(|(a, b, dist)| (a, b, dist / self.length))
+            {
+                if progress_made + dist >= progress {
+                    return Some(a + (b - a) * (progress - progress_made) / dist);
+                }
+                progress_made += dist;
+            }
+            Some(self.points[self.points.len() - 1])
+        }
+    }
+}
+
+/// What a web is built from, which is all that's saved of it.
+/// Everything else, like the pathfinder, is worked out again when it's loaded.
+#[derive(Serialize, Deserialize)]
+struct WebLayout {
+    positions: Vec<Vector>,
+    connections: Vec<(usize, usize)>,
+    /// The indexes of the points along the default profile's route
+    route: Vec<usize>,
+    curves: Vec<((usize, usize), Vector)>,
+    obstacles: Vec<Polygon>,
+    #[serde(default)]
+    terrain: Vec<((usize, usize), Terrain)>,
+    #[serde(default)]
+    grid: Option<GridLayout>,
+}
+
+#[derive(Debug)]
+pub struct Web {
+    points: Vec<Rc<RefCell<Point>>>,
+    /// The route each profile takes, and the indexes of the points along it,
+    /// in the same order as `RouteProfile::ALL`
+    routes: Vec<(Route, Vec<usize>)>,
+    /// Has the weights of the default profile
+    pathfinder: Pathfinder,
+    /// The index of the point enemies spawn at
+    start: usize,
+    /// The index of the point enemies are trying to reach
+    end: usize,
+    /// How many enemies have travelled the whole way along each connection this round,
+    /// in the same order as the pathfinder's connections
+    traversals: Vec<u32>,
+    /// How long the last call to `recalculate_weights` took
+    pathfind_time: Duration,
+    /// The control point of each curved connection,
+    /// in the same order as the pathfinder's connections
+    curves: Vec<Option<Vector>>,
+    /// The ground along each connection, in the same order as the pathfinder's connections
+    terrain: Vec<Terrain>,
+    /// Shapes on the map which towers can't see through
+    obstacles: Vec<Polygon>,
+    /// How much to round off the corners of routes, or 0 to leave them sharp
+    corner_radius: f32,
+    /// The tiles of a grid map, whose points and connections are the web's own.
+    /// Every tower fills the tile it's on, blocking the connections into it.
+    grid: Option<GridMap>,
+}
+impl Web {
+    /// Connections are one way, from the first index to the second.
+    /// Two way connections are given as a connection in each direction.
+    /// Repeated connections are merged into one.
+    /// Fails if any connection or route index refers to a point which doesn't exist,
+    /// or if any points are in the same place.
+    pub fn new(
+        positions: Vec<Vector>,
+        connections: Vec<(usize, usize)>,
+        route_indexes: Vec<usize>,
+    ) -> Result<Self, WebCreationError> {
+        let same_place = |a: usize, b: usize| (positions[a] - positions[b]).sqr_length() == 0.0;
+        let mut connections = connections;
+        let mut seen = vec![];
+        connections.retain(|&x| {
+            let new = !seen.contains(&x);
+            seen.push(x);
+            new
+        });
+
+        let invalid_connections: Vec<_> = connections
+            .iter()
+            .copied()
+            .filter(|&(a, b)| a >= positions.len() || b >= positions.len())
+            .collect();
+        if !invalid_connections.is_empty() {
+            return Err(WebCreationError::InvalidConnections {
+                invalid_connections,
+                point_count: positions.len(),
+            });
+        }
+
+        let self_loops: Vec<_> = connections
+            .iter()
+            .copied()
+            .filter(|&(a, b)| a == b)
+            .collect();
+        if !self_loops.is_empty() {
+            return Err(WebCreationError::SelfLoops {
+                connections: self_loops,
+            });
+        }
+
+        let zero_length: Vec<_> = connections
+            .iter()
+            .copied()
+            .filter(|&(a, b)| same_place(a, b))
+            .collect();
+        if !zero_length.is_empty() {
+            return Err(WebCreationError::ZeroLengthConnections {
+                connections: zero_length,
+            });
+        }
+
+        let duplicates: Vec<_> = (0..positions.len())
+            .flat_map(|a| ((a + 1)..positions.len()).map(move |b| (a, b)))
+            .filter(|&(a, b)| same_place(a, b))
+            .collect();
+        if !duplicates.is_empty() {
+            return Err(WebCreationError::DuplicatePoints { duplicates });
+        }
+
+        if route_indexes.iter().any(|&x| x >= positions.len()) {
+            Err(WebCreationError::InvalidRoute)
+        } else {
+            let points: Vec<Rc<RefCell<Point>>> = positions
+                .iter()
+                .map(|&x| Rc::new(RefCell::new(Point::new(x))))
+                .collect();
+            for &(a, b) in connections.iter() {
+                points[a]
+                    .borrow_mut()
+                    .add_connections(&vec![Rc::clone(&points[b])])
+            }
+            let route: Vec<_> = route_indexes.iter().map(|&x| points[x].clone()).collect();
+            let route = Route::new(&route);
+            match route {
+                Ok(mut x) => {
+                    let mut web = Self {
+                        points,
+                        routes: vec![],
+                        traversals: vec![0; connections.len()],
+                        curves: vec![None; connections.len()],
+                        terrain: vec![Terrain::Plain; connections.len()],
+                        obstacles: vec![],
+                        corner_radius: 0.0,
+                        grid: None,
+                        pathfinder: Pathfinder::new(positions, connections),
+                        start: route_indexes[0],
+                        end: route_indexes[route_indexes.len() - 1],
+                        pathfind_time: Duration::ZERO,
+                    };
+                    // until any towers are placed, every profile follows the map's route
+                    x.connections = web.route_connections(&route_indexes);
+                    web.routes = vec![(x, route_indexes); RouteProfile::ALL.len()];
+                    Ok(web)
+                }
+                Err(_) => Err(WebCreationError::InvalidRoute),
+            }
+        }
+    }
+
+    /// Whether enemies can only travel along a connection in one direction
+    pub fn is_one_way(&self, connection: usize) -> bool {
+        let (a, b) = self.pathfinder.connections()[connection];
+        !self.pathfinder.connections().contains(&(b, a))
+    }
+
+    /// Makes a connection curve towards `control`, as a quadratic Bézier curve.
+    /// Returns false if there is no such connection.
+    pub fn set_curve(&mut self, connection: (usize, usize), control: Vector) -> bool {
+        let Some(index) = self
+            .pathfinder
+            .connections()
+            .iter()
+            .position(|&x| x == connection)
+        else {
+            return false;
+        };
+        self.curves[index] = Some(control);
+        let length = polyline_length(&self.connection_points(index));
+        self.pathfinder.set_length(index, length);
+        for i in 0..self.routes.len() {
+            if let Some(route) = self.build_route(&self.routes[i].1) {
+                self.routes[i].0 = route;
+            }
+        }
+        true
+    }
+
+    /// Changes the ground along a connection, which changes how fast enemies cross it and
+    /// how much the pathfinder avoids it.
+    /// Returns false if there is no such connection.
+    pub fn set_terrain(&mut self, connection: (usize, usize), terrain: Terrain) -> bool {
+        let Some(index) = self
+            .pathfinder
+            .connections()
+            .iter()
+            .position(|&x| x == connection)
+        else {
+            return false;
+        };
+        self.terrain[index] = terrain;
+        self.pathfinder
+            .set_base_weight(index, terrain.base_weight());
+        for i in 0..self.routes.len() {
+            if let Some(route) = self.build_route(&self.routes[i].1) {
+                self.routes[i].0 = route;
+            }
+        }
+        true
+    }
+
+    /// The ground along a connection, given its index in the pathfinder's connections
+    pub fn terrain_of(&self, connection: usize) -> Terrain {
+        self.terrain[connection]
+    }
+
+    /// Every connection which isn't plain ground, with its terrain
+    pub fn terrain(&self) -> Vec<((usize, usize), Terrain)> {
+        self.terrain
+            .iter()
+            .enumerate()
+            .filter(|&(_, &terrain)| terrain != Terrain::Plain)
+            .map(|(i, &terrain)| (self.pathfinder.connections()[i], terrain))
+            .collect()
+    }
+
+    pub fn obstacles(&self) -> &Vec<Polygon> {
+        &self.obstacles
+    }
+
+    /// Rounds off the corners of routes, as in `Route::rounded`, or leaves them sharp if
+    /// `radius` is 0. The weights should be recalculated afterwards.
+    pub fn set_corner_radius(&mut self, radius: f32) {
+        self.corner_radius = radius;
+    }
+
+    /// Replaces the obstacles. The weights should be recalculated afterwards.
+    pub fn set_obstacles(&mut self, obstacles: Vec<Polygon>) {
+        self.obstacles = obstacles;
+    }
+
+    pub fn grid(&self) -> Option<&GridMap> {
+        self.grid.as_ref()
+    }
+
+    /// Makes this a grid map. The grid's points and connections must be the same as the
+    /// web's, in the same order, as they are when the web is built from `GridMap::positions`
+    /// and its pathfinder's connections. Returns false, leaving the web as it was, if
+    /// they aren't.
+    pub fn set_grid(&mut self, grid: GridMap) -> bool {
+        if grid.pathfinder().connections() != self.pathfinder.connections()
+            || grid.positions().len() != self.points.len()
+        {
+            return false;
+        }
+        self.grid = Some(grid);
+        true
+    }
+
+    /// Every curved connection, with its control point
+    pub fn curves(&self) -> Vec<((usize, usize), Vector)> {
+        self.curves
+            .iter()
+            .enumerate()
+            .filter_map(|(i, curve)| {
+                curve.map(|control| (self.pathfinder.connections()[i], control))
+            })
+            .collect()
+    }
+
+    /// The points along a connection, which are just its two ends unless it is curved
+    pub fn connection_points(&self, connection: usize) -> Vec<Vector> {
+        let (a, b) = self.pathfinder.connections()[connection];
+        let a = *self.points[a].borrow().position();
+        let b = *self.points[b].borrow().position();
+        match self.curves[connection] {
+            Some(control) => (0..=CURVE_SAMPLES)
+                .map(|i| bezier(a, control, b, i as f32 / CURVE_SAMPLES as f32))
+                .collect(),
+            None => vec![a, b],
+        }
+    }
+
+    /// Builds a route through these points, following the curve of any curved connections
+    fn build_route(&self, indexes: &[usize]) -> Option<Route> {
+        let points: Vec<_> = indexes
+            .iter()
+            .map(|&i| Rc::clone(&self.points[i]))
+            .collect();
+        let route = Route::new(&points).ok()?;
+        let mut positions = vec![route.points[0]];
+        let mut connections = vec![];
+        let mut speeds = vec![];
+        for connection in self.route_connections(indexes) {
+            let points = self.connection_points(connection);
+            connections.extend(std::iter::repeat(connection).take(points.len() - 1));
+            speeds
+                .extend(std::iter::repeat(self.terrain[connection].speed()).take(points.len() - 1));
+            positions.extend(points.into_iter().skip(1));
+        }
+        let route = Route {
+            length: polyline_length(&positions),
+            points: positions,
+            connections,
+            speeds,
+        };
+        if self.corner_radius > 0.0 {
+            Some(route.rounded(self.corner_radius))
+        } else {
+            Some(route)
+        }
+    }
+
+    /// The route taken by enemies with the default profile
+    pub fn route<'a>(&'a self) -> &'a Route {
+        self.route_for(RouteProfile::default())
+    }
+
+    pub fn route_for(&self, profile: RouteProfile) -> &Route {
+        &self.routes[profile.index()].0
+    }
+
+    /// Works out how dangerous each connection is from how much of it the towers can see,
+    /// then finds the route each profile would take.
+    /// If there is no route at all, the old routes and weights are kept and this returns false.
+    pub fn recalculate_weights(&mut self, towers: &[Box<dyn Tower>]) -> bool {
+        let started = Instant::now();
+        let old_grid = self.grid.clone();
+        if let Some(grid) = &mut self.grid {
+            if !grid.fill(towers, self.start, self.end) {
+                self.pathfind_time = started.elapsed();
+                return false;
+            }
+        }
+        let old_weights = self.pathfinder.weights().clone();
+        let coverage = self.coverage(towers);
+
+        let mut routes = Vec::with_capacity(RouteProfile::ALL.len());
+        for profile in RouteProfile::ALL {
+            self.pathfinder
+                .set_weights(profile_weights(&coverage, profile));
+            let route = self
+                .pathfinder
+                .find(self.start, self.end)
+                .and_then(|indexes| Some((self.build_route(&indexes)?, indexes)));
+            match route {
+                Some(route) => routes.push(route),
+                None => break,
+            }
+        }
+        let found = routes.len() == RouteProfile::ALL.len();
+        if found {
+            self.routes = routes;
+            self.pathfinder
+                .set_weights(profile_weights(&coverage, RouteProfile::default()));
+        } else {
+            self.pathfinder.set_weights(old_weights);
+            self.grid = old_grid;
+        }
+        self.pathfind_time = started.elapsed();
+        found
+    }
+
+    /// The sum, over every tower, of how much of each connection that tower can see,
+    /// including how close it gets to the connection's nodes (see `Range::edge_coverage`),
+    /// scaled by how dangerous the tower is (see `Tower::danger`).
+    /// Connections which a wall sits on are None, as they're blocked, as are connections
+    /// into a filled tile on a grid map.
+    fn coverage(&self, towers: &[Box<dyn Tower>]) -> Vec<Option<f32>> {
+        (0..self.pathfinder.connections().len())
+            .map(|connection| {
+                let filled = self.grid.as_ref().map_or(false, |grid| {
+                    grid.is_solid(grid.tile_of(self.pathfinder.connections()[connection].1))
+                });
+                if filled {
+                    return None;
+                }
+                // curves are covered piece by piece, weighted by the length of each piece
+                let points = self.connection_points(connection);
+                let length = polyline_length(&points);
+                let blocked = towers.iter().filter(|tower| tower.is_wall()).any(|tower| {
+                    points
+                        .windows(2)
+                        .any(|x| distance_to_line(tower.position(), x[0], x[1]) <= tower.radius())
+                });
+                if blocked {
+                    return None;
+                }
+                Some(
+                    towers
+                        .iter()
+                        .map(|tower| {
+                            let along = points
+                                .windows(2)
+                                .map(|x| {
+                                    tower.range().coverage(x[0], x[1])
+                                        * self.visible_fraction(tower.position(), x[0], x[1])
+                                        * (x[1] - x[0]).length()
+                                })
+                                .sum::<f32>()
+                                / length;
+                            let near = [points[0], points[points.len() - 1]]
+                                .into_iter()
+                                .filter(|&node| can_see(tower.position(), node, &self.obstacles))
+                                .map(|node| tower.range().closeness(node))
+                                .fold(0.0, f32::max);
+                            blend_coverage(along, near) * tower.danger()
+                        })
+                        .sum(),
+                )
+            })
+            .collect()
+    }
+
+    /// Roughly how much of the line from `a` to `b` can be seen from `from` past the
+    /// obstacles, normalised [0-1]
+    fn visible_fraction(&self, from: Vector, a: Vector, b: Vector) -> f32 {
+        const SAMPLES: usize = 8;
+        if self.obstacles.is_empty() {
+            return 1.0;
+        }
+        (0..SAMPLES)
+            .map(|i| a + (b - a) * ((i as f32 + 0.5) / SAMPLES as f32))
+            .filter(|&point| can_see(from, point, &self.obstacles))
+            .count() as f32
+            / SAMPLES as f32
+    }
+
+    pub fn pathfind_time(&self) -> Duration {
+        self.pathfind_time
+    }
+
+    /// The total cost of travelling the default profile's route, with its weights
+    pub fn route_cost(&self) -> f32 {
+        self.route()
+            .connections
+            .iter()
+            .map(|&connection| self.pathfinder.cost(connection))
+            .sum()
+    }
+
+    /// Finds the connection between each consecutive pair of points in a route
+    fn route_connections(&self, indexes: &[usize]) -> Vec<usize> {
+        indexes
+            .windows(2)
+            .filter_map(|pair| {
+                self.pathfinder
+                    .connections()
+                    .iter()
+                    .position(|&(a, b)| a == pair[0] && b == pair[1])
+            })
+            .collect()
+    }
+
+    /// Records that an enemy has travelled the whole way along a connection
+    pub fn record_traversal(&mut self, connection: usize) {
+        if let Some(count) = self.traversals.get_mut(connection) {
+            *count += 1;
+        }
+    }
+
+    /// How many enemies have travelled along each connection since the last reset,
+    /// in the same order as `pathfinder().connections()`
+    pub fn traversals(&self) -> &Vec<u32> {
+        &self.traversals
+    }
+
+    /// This should be called at the start of every round
+    pub fn reset_traversals(&mut self) {
+        self.traversals.iter_mut().for_each(|count| *count = 0);
+    }
+
+    /// The position of every point in the web
+    pub fn positions(&self) -> Vec<Vector> {
+        self.points.iter().map(|x| *x.borrow().position()).collect()
+    }
+
+    /// The radius points are drawn with
+    pub fn node_radius(&self) -> f32 {
+        20.0
+    }
+
+    /// The index of the point enemies spawn at
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The index of the point enemies are trying to reach
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn pathfinder(&self) -> &Pathfinder {
+        &self.pathfinder
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Point {
+    position: Vector,
+    connections: Vec<Rc<RefCell<Point>>>,
+}
+impl Point {
+    pub fn new(position: Vector) -> Self {
+        Self {
+            position,
+            connections: vec![],
+        }
+    }
+    pub fn position(&self) -> &Vector {
+        &self.position
+    }
+
+    pub fn add_connection(&mut self, connection: &Rc<RefCell<Point>>) {
+        if self.connections.iter().all(|x| !Rc::ptr_eq(x, connection)) {
+            self.connections.push(connection.clone());
+        }
+    }
+
+    pub fn add_connections(&mut self, connections: &Vec<Rc<RefCell<Point>>>) {
+        self.connections.reserve(connections.len());
+        for p in connections.iter() {
+            if self.connections.iter().all(|x| !Rc::ptr_eq(x, p)) {
+                self.connections.push(p.clone());
+            }
+        }
+    }
+
+    pub fn is_neighbour(&self, point: &Rc<RefCell<Point>>) -> bool {
+        self.connections
+            .iter()
+            .any(|x| std::ptr::eq(x.as_ref().as_ptr(), point.as_ref().as_ptr()))
+    }
+
+    pub fn are_all_neighbours(&self, points: &Vec<Rc<RefCell<Point>>>) -> bool {
+        points.iter().all(|point| self.is_neighbour(point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        enemy::enemy::EnemyKind,
+        map::Map,
+        tower::tower::spawn_tower,
+        vec2d,
+        wave::{Burst, Rush, Wave},
+    };
+
+    /// Three points in a row, far enough apart not to count as the same place
+    fn positions() -> Vec<Vector> {
+        vec![vec2d![0.0, 0.0], vec2d![100.0, 0.0], vec2d![200.0, 0.0]]
+    }
+
+    #[test]
+    fn valid_web_builds() {
+        assert!(Web::new(positions(), vec![(0, 1), (1, 2)], vec![0, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn repeated_connections_are_merged() {
+        assert!(Web::new(positions(), vec![(0, 1), (0, 1), (1, 2)], vec![0, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn route_off_the_end_is_rejected() {
+        let web = Web::new(positions(), vec![(0, 1), (1, 2)], vec![0, 1, 3]);
+        assert!(matches!(web, Err(WebCreationError::InvalidRoute)));
+    }
+
+    #[test]
+    fn route_off_the_connections_is_rejected() {
+        let web = Web::new(positions(), vec![(0, 1)], vec![0, 1, 2]);
+        assert!(matches!(web, Err(WebCreationError::InvalidRoute)));
+    }
+
+    #[test]
+    fn connections_to_missing_points_are_rejected() {
+        let web = Web::new(positions(), vec![(0, 1), (1, 5)], vec![0, 1]);
+        assert!(matches!(
+            web,
+            Err(WebCreationError::InvalidConnections { invalid_connections, point_count: 3 })
+                if invalid_connections == vec![(1, 5)]
+        ));
+    }
+
+    #[test]
+    fn duplicate_points_are_rejected() {
+        let mut positions = positions();
+        positions.push(vec2d![100.0, 0.0]);
+        let web = Web::new(positions, vec![(0, 1), (1, 2)], vec![0, 1, 2]);
+        assert!(matches!(
+            web,
+            Err(WebCreationError::DuplicatePoints { duplicates }) if duplicates == vec![(1, 3)]
+        ));
+    }
+
+    #[test]
+    fn self_loops_are_rejected() {
+        let web = Web::new(positions(), vec![(0, 1), (1, 1), (1, 2)], vec![0, 1, 2]);
+        assert!(matches!(
+            web,
+            Err(WebCreationError::SelfLoops { connections }) if connections == vec![(1, 1)]
+        ));
+    }
+
+    #[test]
+    fn zero_length_connections_are_rejected() {
+        let mut positions = positions();
+        positions.push(vec2d![200.0, 0.0]);
+        let web = Web::new(positions, vec![(0, 1), (1, 2), (2, 3)], vec![0, 1, 2]);
+        assert!(matches!(
+            web,
+            Err(WebCreationError::ZeroLengthConnections { connections })
+                if connections == vec![(2, 3)]
+        ));
+    }
+
+    /// A map of the three points, which builds until curves or terrain are added
+    fn map() -> Map {
+        Map {
+            positions: positions(),
+            connections: vec![(0, 1), (1, 2)],
+            route: vec![0, 1, 2],
+            ..Map::default()
+        }
+    }
+
+    #[test]
+    fn curves_on_missing_connections_are_rejected() {
+        let mut map = map();
+        map.curves = vec![((0, 1), vec2d![50.0, 50.0]), ((0, 2), vec2d![100.0, 50.0])];
+        assert!(matches!(
+            map.build_web(),
+            Err(WebCreationError::InvalidCurves { connections }) if connections == vec![(0, 2)]
+        ));
+    }
+
+    #[test]
+    fn terrain_on_missing_connections_is_rejected() {
+        let mut map = map();
+        map.terrain = vec![((1, 2), Terrain::Mud), ((2, 0), Terrain::Road)];
+        assert!(matches!(
+            map.build_web(),
+            Err(WebCreationError::InvalidTerrain { connections }) if connections == vec![(2, 0)]
+        ));
+    }
+
+    /// Serialises a value, reads it back and serialises it again. The two only match if
+    /// nothing was lost on the way.
+    fn assert_round_trips<T: Serialize + for<'de> Deserialize<'de>>(value: &T) {
+        let json = serde_json::to_string(value).unwrap();
+        let read: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+    }
+
+    /// The three points with everything a map can add to them
+    fn detailed_map() -> Map {
+        let mut map = map();
+        map.name = "Detailed".to_owned();
+        map.curves = vec![((0, 1), vec2d![50.0, 50.0])];
+        map.terrain = vec![((1, 2), Terrain::Mud)];
+        map.obstacles = vec![Polygon::new(vec![
+            vec2d![50.0, 100.0],
+            vec2d![150.0, 100.0],
+            vec2d![100.0, 150.0],
+        ])];
+        map.waves = vec![
+            Wave::new(5, 30),
+            Wave::with_rush(
+                10,
+                20,
+                Rush {
+                    after: 4,
+                    interval: 5,
+                },
+            ),
+            Wave::scheduled(vec![Burst {
+                delay: 60,
+                count: 3,
+                kind: Some(EnemyKind::Brave),
+            }]),
+        ];
+        map
+    }
+
+    #[test]
+    fn web_round_trips() {
+        let web = detailed_map().build_web().unwrap();
+        assert_round_trips(&web);
+        let json = serde_json::to_string(&web).unwrap();
+        let read: Web = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.curves().len(), 1);
+        assert_eq!(read.terrain(), web.terrain());
+        assert_eq!(read.obstacles().len(), 1);
+    }
+
+    #[test]
+    fn web_with_a_missing_curve_is_rejected() {
+        let mut json: serde_json::Value =
+            serde_json::to_value(detailed_map().build_web().unwrap()).unwrap();
+        json["curves"] = serde_json::json!([[[0, 2], { "x": 100.0, "y": 50.0 }]]);
+        assert!(serde_json::from_value::<Web>(json).is_err());
+    }
+
+    #[test]
+    fn map_round_trips() {
+        let map = detailed_map();
+        assert_round_trips(&map);
+        let read: Map = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_eq!(read.to_text(), map.to_text());
+    }
+
+    #[test]
+    fn waves_round_trip() {
+        for wave in detailed_map().waves {
+            assert_round_trips(&wave);
+        }
+    }
+
+    #[test]
+    fn grid_map_builds_from_its_tiles() {
+        let map = Map::parse("grid 4 3 50 0 0\nroute 0 11\n").unwrap();
+        assert_eq!(map.positions.len(), 12);
+        assert_eq!(map.route.first(), Some(&0));
+        assert_eq!(map.route.last(), Some(&11));
+        assert!(map.build_web().unwrap().grid().is_some());
+        assert_eq!(Map::parse(&map.to_text()).unwrap().to_text(), map.to_text());
+    }
+
+    #[test]
+    fn towers_cant_seal_off_a_grid_map() {
+        let mut web = Map::parse("grid 4 3 50 0 0\nroute 0 11\n")
+            .unwrap()
+            .build_web()
+            .unwrap();
+        // a wall of towers down the second column, leaving a gap at the bottom
+        let mut towers = vec![
+            spawn_tower(vec2d![75.0, 25.0]),
+            spawn_tower(vec2d![75.0, 75.0]),
+        ];
+        assert!(web.recalculate_weights(&towers));
+        let grid = web.grid().unwrap();
+        assert!(grid.is_solid((1, 0)) && grid.is_solid((1, 1)));
+        assert!(web.route().connections().iter().all(|&connection| {
+            let (_, b) = web.pathfinder().connections()[connection];
+            !web.grid().unwrap().is_solid(web.grid().unwrap().tile_of(b))
+        }));
+
+        towers.push(spawn_tower(vec2d![75.0, 125.0]));
+        assert!(!web.recalculate_weights(&towers));
+        assert!(!web.grid().unwrap().is_solid((1, 2)));
+    }
+}
@@ -0,0 +1,786 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::vector::Vector;
+
+/// How much more dangerous towers make a connection.
+/// A connection which is fully covered by one tower costs this many times more to
+/// travel along, on top of its length.
+const DANGER_PER_TOWER: f32 = 5.0;
+
+/// The cost of travelling along a connection is its length multiplied by this.
+/// Raw danger values are normalised into multipliers which are never below
+/// `Weight::CHEAPEST`, and the pathfinder scales its heuristic down to the cheapest one,
+/// so the straight line distance between two points can never overestimate the cost
+/// of travelling between them, which A* needs to find the best route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weight(f32);
+
+impl Weight {
+    /// A connection which nothing can see
+    pub const NONE: Weight = Weight(1.0);
+    /// A connection which can't be travelled along at all
+    pub const BLOCKED: Weight = Weight(f32::INFINITY);
+    /// The lowest multiplier a connection can have, however much decoys draw enemies to it
+    pub const CHEAPEST: f32 = 0.5;
+
+    /// `coverage` is the sum, over every tower, of the fraction [0-1] of the connection
+    /// that tower can see, which is negative for decoys
+    pub fn from_coverage(coverage: f32) -> Self {
+        Self::from_multiplier(1.0 + DANGER_PER_TOWER * coverage)
+    }
+
+    /// Multipliers below `CHEAPEST` are clamped, and NaN is treated as 1
+    pub fn from_multiplier(multiplier: f32) -> Self {
+        if multiplier.is_nan() {
+            Self::NONE
+        } else {
+            Weight(multiplier.max(Self::CHEAPEST))
+        }
+    }
+
+    pub fn multiplier(&self) -> f32 {
+        self.0
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        self.0.is_infinite()
+    }
+}
+
+impl Default for Weight {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// How a kind of enemy judges connections when picking its route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RouteProfile {
+    /// Avoids whatever the towers can see
+    #[default]
+    Cautious,
+    /// Ignores the towers, and takes the shortest route
+    Brave,
+}
+
+impl RouteProfile {
+    pub const ALL: [RouteProfile; 2] = [RouteProfile::Cautious, RouteProfile::Brave];
+
+    /// The weight of a connection with this much coverage, as in `Weight::from_coverage`
+    pub fn weight(&self, coverage: f32) -> Weight {
+        match self {
+            RouteProfile::Cautious => Weight::from_coverage(coverage),
+            RouteProfile::Brave => Weight::NONE,
+        }
+    }
+
+    /// The index of this profile in `ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Webs with at least this many points find routes through clusters first,
+/// as searching every point would take too long
+const HIERARCHICAL_POINTS: usize = 400;
+/// The width and height of each cluster, in world coordinates
+const CLUSTER_SIZE: f32 = 200.0;
+
+/// Which points the last search looked at, for the pathfinding overlay
+#[derive(Debug, Clone, Default)]
+pub struct SearchSets {
+    /// Points which were still waiting to be searched when the search finished
+    pub open: Vec<usize>,
+    /// Points which were searched
+    pub closed: Vec<usize>,
+}
+
+impl SearchSets {
+    /// `open` may repeat points, and points which are also closed are left out of it
+    fn new(open: impl Iterator<Item = usize>, closed: &[bool]) -> Self {
+        let mut open: Vec<usize> = open.filter(|&point| !closed[point]).collect();
+        open.sort_unstable();
+        open.dedup();
+        Self {
+            open,
+            closed: (0..closed.len()).filter(|&point| closed[point]).collect(),
+        }
+    }
+}
+
+/// Finds the cheapest route through a set of points, using A*.
+/// Connections are one way, from the first index to the second.
+#[derive(Debug, Clone)]
+pub struct Pathfinder {
+    positions: Vec<Vector>,
+    connections: Vec<(usize, usize)>,
+    weights: Vec<Weight>,
+    /// The distance travelled along each connection, which is longer than the
+    /// straight line between its points if the connection is curved
+    lengths: Vec<f32>,
+    /// Multiplies the cost of each connection before its weight, from the ground it
+    /// crosses. Unlike weights these can be below 1, so the heuristic is scaled down to
+    /// the cheapest of them.
+    base_weights: Vec<f32>,
+    /// The lowest base weight, or 1 if none are lower
+    cheapest_base_weight: f32,
+    /// The lowest weight multiplier, or 1 if none are lower
+    cheapest_weight: f32,
+    /// The indexes of the connections leaving each point
+    outgoing: Vec<Vec<usize>>,
+    /// The indexes of the connections arriving at each point
+    incoming: Vec<Vec<usize>>,
+    /// Only used for very large webs, see `HIERARCHICAL_POINTS`
+    clusters: Option<Clusters>,
+    /// What was learnt from the last search, so it can be reused when only a few
+    /// weights change. `find` only takes &self, so this is filled in lazily.
+    replanner: RefCell<Option<Replanner>>,
+    last_search: RefCell<SearchSets>,
+}
+
+impl Pathfinder {
+    /// Every connection must refer to a valid position, and join two different points.
+    /// `Web::new` checks this before creating a pathfinder.
+    pub fn new(positions: Vec<Vector>, connections: Vec<(usize, usize)>) -> Self {
+        debug_assert!(connections
+            .iter()
+            .all(|&(a, b)| a != b && a < positions.len() && b < positions.len()));
+        let mut outgoing = vec![vec![]; positions.len()];
+        let mut incoming = vec![vec![]; positions.len()];
+        for (i, &(a, b)) in connections.iter().enumerate() {
+            outgoing[a].push(i);
+            incoming[b].push(i);
+        }
+        Self {
+            weights: vec![Weight::NONE; connections.len()],
+            base_weights: vec![1.0; connections.len()],
+            cheapest_base_weight: 1.0,
+            cheapest_weight: 1.0,
+            lengths: connections
+                .iter()
+                .map(|&(a, b)| (positions[b] - positions[a]).length())
+                .collect(),
+            clusters: (positions.len() >= HIERARCHICAL_POINTS)
+                .then(|| Clusters::new(&positions, CLUSTER_SIZE)),
+            outgoing,
+            incoming,
+            replanner: RefCell::new(None),
+            last_search: RefCell::new(SearchSets::default()),
+            positions,
+            connections,
+        }
+    }
+
+    pub fn connections(&self) -> &Vec<(usize, usize)> {
+        &self.connections
+    }
+
+    pub fn weights(&self) -> &Vec<Weight> {
+        &self.weights
+    }
+
+    /// Replaces the weight of every connection, in the same order as `connections`.
+    /// Only the weights which actually changed are replanned around.
+    pub fn set_weights(&mut self, weights: Vec<Weight>) {
+        assert_eq!(weights.len(), self.connections.len());
+        let changed: Vec<_> = weights
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, weight)| weight != self.weights[i])
+            .collect();
+        self.update_weights(&changed);
+    }
+
+    /// Changes the weights of a few connections, given as (connection index, new weight).
+    /// The next `find` between the same points reuses as much of the last search as it
+    /// can, rather than starting again from scratch. Webs large enough to use clusters
+    /// don't keep their last search, so they always start again.
+    pub fn update_weights(&mut self, changed_edges: &[(usize, Weight)]) {
+        for &(connection, weight) in changed_edges {
+            self.weights[connection] = weight;
+        }
+        let cheapest_weight = self
+            .weights
+            .iter()
+            .map(|x| x.multiplier())
+            .fold(1.0, f32::min);
+        if cheapest_weight != self.cheapest_weight {
+            // the heuristic has changed, so nothing from the last search can be trusted
+            self.cheapest_weight = cheapest_weight;
+            self.replanner.get_mut().take();
+        }
+        if let Some(mut replanner) = self.replanner.get_mut().take() {
+            for &(connection, _) in changed_edges {
+                replanner.update_point(self, self.connections[connection].1);
+            }
+            *self.replanner.get_mut() = Some(replanner);
+        }
+    }
+
+    /// Which points the last call to `find` looked at
+    pub fn last_search(&self) -> SearchSets {
+        self.last_search.borrow().clone()
+    }
+
+    /// The length of a connection
+    pub fn length(&self, connection: usize) -> f32 {
+        self.lengths[connection]
+    }
+
+    /// Sets the distance travelled along a connection.
+    /// Nothing is shorter than a straight line, so shorter lengths are clamped to keep
+    /// the heuristic admissible.
+    pub fn set_length(&mut self, connection: usize, length: f32) {
+        let (a, b) = self.connections[connection];
+        self.lengths[connection] = length.max(self.distance(a, b));
+        self.replanner.get_mut().take();
+    }
+
+    pub fn base_weight(&self, connection: usize) -> f32 {
+        self.base_weights[connection]
+    }
+
+    /// Sets what the cost of a connection is multiplied by before its weight, which can
+    /// be infinite to block it. Negative multipliers (or NaN) are treated as 1.
+    pub fn set_base_weight(&mut self, connection: usize, multiplier: f32) {
+        self.base_weights[connection] = if multiplier >= 0.0 { multiplier } else { 1.0 };
+        self.cheapest_base_weight = self.base_weights.iter().copied().fold(1.0, f32::min);
+        self.replanner.get_mut().take();
+    }
+
+    /// The cost of travelling along a connection
+    pub fn cost(&self, connection: usize) -> f32 {
+        self.length(connection)
+            * self.base_weights[connection]
+            * self.weights[connection].multiplier()
+    }
+
+    /// Whether a connection can't be travelled along at all
+    pub fn is_blocked(&self, connection: usize) -> bool {
+        self.weights[connection].is_blocked() || self.base_weights[connection].is_infinite()
+    }
+
+    /// The straight line distance between two points
+    fn distance(&self, from: usize, to: usize) -> f32 {
+        (self.positions[to] - self.positions[from]).length()
+    }
+
+    /// The estimated cost of getting from one point to another.
+    /// This is the straight line distance, scaled down by the cheapest base weight and
+    /// weight below 1, which is always admissible.
+    fn heuristic(&self, from: usize, to: usize) -> f32 {
+        self.distance(from, to) * self.cheapest_base_weight * self.cheapest_weight
+    }
+
+    /// Checks that the heuristic never overestimates the cost of any connection.
+    /// If it does, A* could return a route which isn't the cheapest.
+    pub fn heuristic_is_admissible(&self) -> bool {
+        (0..self.connections.len()).all(|i| {
+            let (a, b) = self.connections[i];
+            self.heuristic(a, b) <= self.cost(i) + f32::EPSILON
+        })
+    }
+
+    /// Finds the cheapest route from `start` to `end`, as a list of point indexes.
+    /// Blocked connections are never used.
+    /// Returns None if there is no route, or if either point doesn't exist.
+    ///
+    /// Very large webs first pick which clusters of points to go through, and then only
+    /// search those clusters. The route found this way might cost slightly more than
+    /// the cheapest one, but if it can't find a route at all every point is searched.
+    /// These searches don't go through the replanner, as a search limited to a corridor
+    /// can't be updated when the corridor moves, so they're always done from scratch.
+    pub fn find(&self, start: usize, end: usize) -> Option<Vec<usize>> {
+        debug_assert!(self.heuristic_is_admissible());
+
+        let count = self.positions.len();
+        if start >= count || end >= count {
+            return None;
+        }
+        if let Some(clusters) = &self.clusters {
+            if let Some(allowed) = clusters.corridor(self, start, end) {
+                if let Some(route) = self.search(start, end, Some(&allowed)) {
+                    return Some(route);
+                }
+            }
+            return self.search(start, end, None);
+        }
+
+        let mut replanner = self.replanner.borrow_mut();
+        if !matches!(&*replanner, Some(x) if x.start == start && x.end == end) {
+            *replanner = Some(Replanner::new(self, start, end));
+        }
+        let Some(replanner) = replanner.as_mut() else {
+            return self.search(start, end, None);
+        };
+        replanner.replan(self);
+        *self.last_search.borrow_mut() = replanner.sets();
+        replanner.route(self)
+    }
+
+    /// A* from `start` to `end`, only visiting the points which are `allowed`, if given
+    fn search(&self, start: usize, end: usize, allowed: Option<&[bool]>) -> Option<Vec<usize>> {
+        let count = self.positions.len();
+        let mut g_cost = vec![f32::INFINITY; count];
+        let mut came_from: Vec<Option<usize>> = vec![None; count];
+        let mut closed = vec![false; count];
+        let mut open = BinaryHeap::new();
+        g_cost[start] = 0.0;
+        open.push(OpenPoint {
+            f_cost: self.heuristic(start, end),
+            point: start,
+        });
+
+        while let Some(OpenPoint { point: current, .. }) = open.pop() {
+            // points can be pushed more than once, when a cheaper way to them is found
+            if closed[current] {
+                continue;
+            }
+            if current == end {
+                *self.last_search.borrow_mut() =
+                    SearchSets::new(open.iter().map(|x| x.point), &closed);
+                let mut route = vec![end];
+                let mut point = end;
+                while let Some(previous) = came_from[point] {
+                    route.push(previous);
+                    point = previous;
+                }
+                route.reverse();
+                return Some(route);
+            }
+            closed[current] = true;
+
+            for &i in &self.outgoing[current] {
+                let neighbour = self.connections[i].1;
+                if closed[neighbour]
+                    || self.is_blocked(i)
+                    || allowed.map_or(false, |allowed| !allowed[neighbour])
+                {
+                    continue;
+                }
+                let cost = g_cost[current] + self.cost(i);
+                if cost < g_cost[neighbour] {
+                    g_cost[neighbour] = cost;
+                    came_from[neighbour] = Some(current);
+                    open.push(OpenPoint {
+                        f_cost: cost + self.heuristic(neighbour, end),
+                        point: neighbour,
+                    });
+                }
+            }
+        }
+        *self.last_search.borrow_mut() = SearchSets::new(std::iter::empty(), &closed);
+        None
+    }
+}
+
+/// A point waiting to be searched, ordered so the lowest f cost comes out of the heap first
+#[derive(Debug, Clone, Copy)]
+struct OpenPoint {
+    f_cost: f32,
+    point: usize,
+}
+
+impl PartialEq for OpenPoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenPoint {}
+
+impl PartialOrd for OpenPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenPoint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f_cost.total_cmp(&self.f_cost)
+    }
+}
+
+/// Lifelong Planning A*, which is D* Lite for a start which doesn't move.
+/// Keeps the cost of getting to every point searched so far, so when a few weights change
+/// only the points whose costs depend on them have to be searched again.
+#[derive(Debug, Clone)]
+struct Replanner {
+    start: usize,
+    end: usize,
+    /// The cost to each point, as of the last time it was searched
+    g_cost: Vec<f32>,
+    /// The cost to each point, looking one step back from the best point before it.
+    /// Points where this differs from `g_cost` need searching again.
+    rhs_cost: Vec<f32>,
+    /// May contain stale entries, which are skipped when they come out
+    open: BinaryHeap<OpenKey>,
+}
+
+impl Replanner {
+    fn new(pathfinder: &Pathfinder, start: usize, end: usize) -> Self {
+        let count = pathfinder.positions.len();
+        let mut replanner = Self {
+            start,
+            end,
+            g_cost: vec![f32::INFINITY; count],
+            rhs_cost: vec![f32::INFINITY; count],
+            open: BinaryHeap::new(),
+        };
+        replanner.rhs_cost[start] = 0.0;
+        replanner.open.push(OpenKey {
+            key: replanner.key(pathfinder, start),
+            point: start,
+        });
+        replanner
+    }
+
+    fn key(&self, pathfinder: &Pathfinder, point: usize) -> (f32, f32) {
+        let cost = self.g_cost[point].min(self.rhs_cost[point]);
+        (cost + pathfinder.heuristic(point, self.end), cost)
+    }
+
+    /// Recalculates a point's cost from the points before it, and queues it to be
+    /// searched again if that doesn't match its last searched cost
+    fn update_point(&mut self, pathfinder: &Pathfinder, point: usize) {
+        if point != self.start {
+            self.rhs_cost[point] = pathfinder.incoming[point]
+                .iter()
+                .map(|&i| self.g_cost[pathfinder.connections[i].0] + pathfinder.cost(i))
+                .fold(f32::INFINITY, f32::min);
+        }
+        if self.g_cost[point] != self.rhs_cost[point] {
+            self.open.push(OpenKey {
+                key: self.key(pathfinder, point),
+                point,
+            });
+        }
+    }
+
+    /// Searches until the cost of getting to the end is known again
+    fn replan(&mut self, pathfinder: &Pathfinder) {
+        while let Some(&OpenKey { key, point }) = self.open.peek() {
+            if self.g_cost[point] == self.rhs_cost[point] {
+                self.open.pop();
+                continue;
+            }
+            let current_key = self.key(pathfinder, point);
+            if key != current_key {
+                self.open.pop();
+                self.open.push(OpenKey {
+                    key: current_key,
+                    point,
+                });
+                continue;
+            }
+            if compare_keys(key, self.key(pathfinder, self.end)) != Ordering::Less
+                && self.g_cost[self.end] == self.rhs_cost[self.end]
+            {
+                break;
+            }
+            self.open.pop();
+            if self.g_cost[point] > self.rhs_cost[point] {
+                self.g_cost[point] = self.rhs_cost[point];
+            } else {
+                self.g_cost[point] = f32::INFINITY;
+                self.update_point(pathfinder, point);
+            }
+            for &i in &pathfinder.outgoing[point] {
+                self.update_point(pathfinder, pathfinder.connections[i].1);
+            }
+        }
+    }
+
+    /// Walks back from the end, always to the point before which gives the lowest cost
+    /// Points with a known cost count as searched, and points queued to be searched
+    /// again are still open
+    fn sets(&self) -> SearchSets {
+        let closed: Vec<bool> = (0..self.g_cost.len())
+            .map(|point| {
+                self.g_cost[point].is_finite() && self.g_cost[point] == self.rhs_cost[point]
+            })
+            .collect();
+        let open = self
+            .open
+            .iter()
+            .map(|x| x.point)
+            .filter(|&point| self.g_cost[point] != self.rhs_cost[point]);
+        SearchSets::new(open, &closed)
+    }
+
+    fn route(&self, pathfinder: &Pathfinder) -> Option<Vec<usize>> {
+        if self.g_cost[self.end].is_infinite() {
+            return None;
+        }
+        let mut route = vec![self.end];
+        let mut point = self.end;
+        while point != self.start {
+            if route.len() > pathfinder.positions.len() {
+                return None;
+            }
+            let previous = pathfinder.incoming[point]
+                .iter()
+                .filter(|&&i| !pathfinder.is_blocked(i))
+                .map(|&i| {
+                    let before = pathfinder.connections[i].0;
+                    (before, self.g_cost[before] + pathfinder.cost(i))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            if previous.1.is_infinite() {
+                return None;
+            }
+            route.push(previous.0);
+            point = previous.0;
+        }
+        route.reverse();
+        Some(route)
+    }
+}
+
+fn compare_keys(a: (f32, f32), b: (f32, f32)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+/// A point waiting to be searched by the replanner, ordered so the lowest key comes out
+/// of the heap first
+#[derive(Debug, Clone, Copy)]
+struct OpenKey {
+    key: (f32, f32),
+    point: usize,
+}
+
+impl PartialEq for OpenKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenKey {}
+
+impl PartialOrd for OpenKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_keys(other.key, self.key)
+    }
+}
+
+/// Groups the points of a web into square cells, so a rough route can be found
+/// through the cells before the points inside them are searched
+#[derive(Debug, Clone)]
+struct Clusters {
+    /// The cluster each point is in
+    cluster_of: Vec<usize>,
+    count: usize,
+}
+
+impl Clusters {
+    fn new(positions: &[Vector], size: f32) -> Self {
+        let mut cells: HashMap<(i32, i32), usize> = HashMap::new();
+        let cluster_of = positions
+            .iter()
+            .map(|position| {
+                let cell = (
+                    (position.x / size).floor() as i32,
+                    (position.y / size).floor() as i32,
+                );
+                let next = cells.len();
+                *cells.entry(cell).or_insert(next)
+            })
+            .collect();
+        Self {
+            cluster_of,
+            count: cells.len(),
+        }
+    }
+
+    /// Finds the cheapest route between clusters, where moving between two clusters
+    /// costs as much as the cheapest connection between them.
+    /// Returns which points are in a cluster along that route.
+    fn corridor(&self, pathfinder: &Pathfinder, start: usize, end: usize) -> Option<Vec<bool>> {
+        let mut edges: HashMap<(usize, usize), f32> = HashMap::new();
+        for (i, &(a, b)) in pathfinder.connections.iter().enumerate() {
+            let (from, to) = (self.cluster_of[a], self.cluster_of[b]);
+            if from == to || pathfinder.is_blocked(i) {
+                continue;
+            }
+            let cost = edges.entry((from, to)).or_insert(f32::INFINITY);
+            *cost = cost.min(pathfinder.cost(i));
+        }
+        let mut outgoing = vec![vec![]; self.count];
+        for (&(from, to), &cost) in &edges {
+            outgoing[from].push((to, cost));
+        }
+
+        // Dijkstra, as the clusters have no single position to estimate from
+        let (start, end) = (self.cluster_of[start], self.cluster_of[end]);
+        let mut cost_to = vec![f32::INFINITY; self.count];
+        let mut came_from: Vec<Option<usize>> = vec![None; self.count];
+        let mut open = BinaryHeap::new();
+        cost_to[start] = 0.0;
+        open.push(OpenPoint {
+            f_cost: 0.0,
+            point: start,
+        });
+        while let Some(OpenPoint {
+            f_cost,
+            point: current,
+        }) = open.pop()
+        {
+            if current == end {
+                let mut in_corridor = vec![false; self.count];
+                let mut cluster = Some(end);
+                while let Some(x) = cluster {
+                    in_corridor[x] = true;
+                    cluster = came_from[x];
+                }
+                return Some(
+                    self.cluster_of
+                        .iter()
+                        .map(|&cluster| in_corridor[cluster])
+                        .collect(),
+                );
+            }
+            if f_cost > cost_to[current] {
+                continue;
+            }
+            for &(neighbour, cost) in &outgoing[current] {
+                let cost = cost_to[current] + cost;
+                if cost < cost_to[neighbour] {
+                    cost_to[neighbour] = cost;
+                    came_from[neighbour] = Some(current);
+                    open.push(OpenPoint {
+                        f_cost: cost,
+                        point: neighbour,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::vec2d;
+
+    /// How many points along each side of the test grid
+    const SIDE: usize = 8;
+
+    /// A square grid of points, each joined both ways to the points right of, below
+    /// and diagonally below it
+    fn grid() -> Pathfinder {
+        let index = |x: usize, y: usize| y * SIDE + x;
+        let positions = (0..SIDE * SIDE)
+            .map(|i| vec2d![(i % SIDE) as f32 * 50.0, (i / SIDE) as f32 * 50.0])
+            .collect();
+        let mut connections = vec![];
+        for y in 0..SIDE {
+            for x in 0..SIDE {
+                let neighbours = [(x + 1, y), (x, y + 1), (x + 1, y + 1)];
+                for (nx, ny) in neighbours {
+                    if nx < SIDE && ny < SIDE {
+                        connections.push((index(x, y), index(nx, ny)));
+                        connections.push((index(nx, ny), index(x, y)));
+                    }
+                }
+            }
+        }
+        Pathfinder::new(positions, connections)
+    }
+
+    /// The total cost of a route, or None if it uses a connection which doesn't exist
+    fn route_cost(pathfinder: &Pathfinder, route: &[usize]) -> Option<f32> {
+        route
+            .windows(2)
+            .map(|pair| {
+                let connections = pathfinder.connections().iter().enumerate();
+                connections
+                    .filter(|&(_, &(a, b))| a == pair[0] && b == pair[1])
+                    .map(|(i, _)| pathfinder.cost(i))
+                    .reduce(f32::min)
+            })
+            .sum()
+    }
+
+    fn random_weight(rng: &mut StdRng) -> Weight {
+        match rng.gen_range(0..4) {
+            0 => Weight::BLOCKED,
+            1 => Weight::NONE,
+            _ => Weight::from_coverage(rng.gen_range(-0.2..2.0)),
+        }
+    }
+
+    #[test]
+    fn replanning_matches_a_fresh_search() {
+        let mut rng = StdRng::seed_from_u64(2102);
+        let mut pathfinder = grid();
+        let (mut start, mut end) = (0, SIDE * SIDE - 1);
+        for _ in 0..300 {
+            let batch: Vec<_> = (0..rng.gen_range(1..8))
+                .map(|_| {
+                    let connection = rng.gen_range(0..pathfinder.connections().len());
+                    (connection, random_weight(&mut rng))
+                })
+                .collect();
+            pathfinder.update_weights(&batch);
+            // now and then the replanner has to start again between different points
+            if rng.gen_bool(0.1) {
+                start = rng.gen_range(0..SIDE * SIDE);
+                end = rng.gen_range(0..SIDE * SIDE);
+            }
+
+            let replanned = pathfinder.find(start, end);
+            let fresh = pathfinder.search(start, end, None);
+            match (replanned, fresh) {
+                (None, None) => (),
+                (Some(replanned), Some(fresh)) => {
+                    assert_eq!(replanned.first(), Some(&start));
+                    assert_eq!(replanned.last(), Some(&end));
+                    let replanned = route_cost(&pathfinder, &replanned).unwrap();
+                    let fresh = route_cost(&pathfinder, &fresh).unwrap();
+                    assert!(
+                        (replanned - fresh).abs() <= fresh * 1e-4,
+                        "replanned route costs {} but a fresh search costs {}",
+                        replanned,
+                        fresh
+                    );
+                }
+                (replanned, fresh) => panic!(
+                    "replanning found {:?} but a fresh search found {:?}",
+                    replanned, fresh
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn replanning_never_uses_blocked_connections() {
+        let mut rng = StdRng::seed_from_u64(21020);
+        let mut pathfinder = grid();
+        for _ in 0..100 {
+            let batch: Vec<_> = (0..4)
+                .map(|_| {
+                    let connection = rng.gen_range(0..pathfinder.connections().len());
+                    (connection, random_weight(&mut rng))
+                })
+                .collect();
+            pathfinder.update_weights(&batch);
+            let Some(route) = pathfinder.find(0, SIDE * SIDE - 1) else {
+                continue;
+            };
+            assert!(route_cost(&pathfinder, &route).unwrap().is_finite());
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Vector;
+
+/// A closed shape made from straight edges between consecutive points.
+/// The last point joins back up to the first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Polygon {
+    points: Vec<Vector>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Vector>) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> &Vec<Vector> {
+        &self.points
+    }
+
+    pub fn push(&mut self, point: Vector) {
+        self.points.push(point);
+    }
+
+    /// A polygon needs at least 3 points to have an inside
+    pub fn is_valid(&self) -> bool {
+        self.points.len() >= 3
+    }
+
+    /// Iterates over each edge as (start, end), including the closing edge
+    fn edges(&self) -> impl Iterator<Item = (Vector, Vector)> + '_ {
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, &a)| (a, self.points[(i + 1) % self.points.len()]))
+    }
+
+    /// Checks if a point is inside the polygon, by counting how many edges
+    /// a horizontal ray from the point crosses
+    pub fn contains(&self, point: Vector) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+        self.edges()
+            .filter(|&(a, b)| {
+                (a.y > point.y) != (b.y > point.y)
+                    && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            })
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Checks if a circle touches or is inside the polygon
+    pub fn overlaps_circle(&self, centre: Vector, radius: f32) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+        self.contains(centre)
+            || self.edges().any(|(a, b)| {
+                let along = b - a;
+                let t = ((centre - a).dot(along) / along.sqr_length()).clamp(0.0, 1.0);
+                (a + along * t - centre).sqr_length() <= radius * radius
+            })
+    }
+
+    /// Checks if the line from `a` to `b` crosses an edge of the polygon, or starts or ends
+    /// inside it
+    pub fn blocks_line(&self, a: Vector, b: Vector) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+        // which side of the line from p to q the point r is on
+        let side = |p: Vector, q: Vector, r: Vector| (q - p).perpendicular_dot(r - p);
+        self.contains(a)
+            || self.contains(b)
+            || self.edges().any(|(c, d)| {
+                side(a, b, c) * side(a, b, d) < 0.0 && side(c, d, a) * side(c, d, b) < 0.0
+            })
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    tower::tower::{
+        spawn_bank, spawn_bouncer_tower, spawn_decoy, spawn_freeze_tower, spawn_lance_tower,
+        spawn_missile_tower, spawn_sector_tower, spawn_sniper, spawn_tesla, spawn_tower,
+        spawn_wall, Tower, TowerState,
+    },
+    vector::Vector,
+};
+
+/// Everything the shop needs to know about a kind of tower
+#[derive(Clone)]
+pub struct TowerKind {
+    pub name: &'static str,
+    pub price: u64,
+    pub spawn: fn(Vector) -> Box<dyn Tower>,
+}
+
+/// Enough to place a tower again, for saving it or sending it to another player.
+/// A tower made from this comes back with its upgrades, health and kills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TowerDescriptor {
+    /// The name of the tower's kind
+    pub name: String,
+    pub position: Vector,
+    pub state: TowerState,
+}
+
+impl TowerDescriptor {
+    pub fn of(tower: &dyn Tower) -> Self {
+        Self {
+            name: tower.name().to_owned(),
+            position: tower.position(),
+            state: TowerState::of(tower),
+        }
+    }
+}
+
+/// Every kind of tower the player can buy.
+/// The shop buttons and the radial menu are generated from this,
+/// so a new tower only needs to be registered here to show up in them.
+#[derive(Clone)]
+pub struct TowerRegistry {
+    kinds: Vec<TowerKind>,
+}
+
+impl TowerRegistry {
+    pub fn new() -> Self {
+        Self { kinds: Vec::new() }
+    }
+
+    pub fn register(&mut self, kind: TowerKind) {
+        self.kinds.push(kind);
+    }
+
+    pub fn kinds(&self) -> &Vec<TowerKind> {
+        &self.kinds
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TowerKind> {
+        self.kinds.get(index)
+    }
+
+    /// Makes the tower a descriptor describes, unless its kind isn't registered
+    pub fn spawn(&self, descriptor: &TowerDescriptor) -> Option<Box<dyn Tower>> {
+        let kind = self.kinds.iter().find(|x| x.name == descriptor.name)?;
+        let mut tower = (kind.spawn)(descriptor.position);
+        descriptor.state.apply(tower.as_mut());
+        Some(tower)
+    }
+}
+
+impl Default for TowerRegistry {
+    /// Every tower in the base game
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(TowerKind {
+            name: "Test Tower",
+            price: 10,
+            spawn: spawn_tower,
+        });
+        registry.register(TowerKind {
+            name: "Sector Tower",
+            price: 15,
+            spawn: spawn_sector_tower,
+        });
+        registry.register(TowerKind {
+            name: "Wall",
+            price: 5,
+            spawn: spawn_wall,
+        });
+        registry.register(TowerKind {
+            name: "Freeze Tower",
+            price: 20,
+            spawn: spawn_freeze_tower,
+        });
+        registry.register(TowerKind {
+            name: "Bank",
+            price: 30,
+            spawn: spawn_bank,
+        });
+        registry.register(TowerKind {
+            name: "Sniper Tower",
+            price: 35,
+            spawn: spawn_sniper,
+        });
+        registry.register(TowerKind {
+            name: "Tesla Tower",
+            price: 25,
+            spawn: spawn_tesla,
+        });
+        registry.register(TowerKind {
+            name: "Decoy",
+            price: 15,
+            spawn: spawn_decoy,
+        });
+        registry.register(TowerKind {
+            name: "Missile Tower",
+            price: 30,
+            spawn: spawn_missile_tower,
+        });
+        registry.register(TowerKind {
+            name: "Bouncer Tower",
+            price: 8,
+            spawn: spawn_bouncer_tower,
+        });
+        registry.register(TowerKind {
+            name: "Lance Tower",
+            price: 25,
+            spawn: spawn_lance_tower,
+        });
+        registry
+    }
+}
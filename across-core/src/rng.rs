@@ -0,0 +1,59 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The separate sources of randomness in a run.
+/// Each has its own generator, so using more or less of one (such as turning off a visual
+/// effect) never changes the numbers another produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Spawning,
+    Crits,
+    Mutations,
+    Visuals,
+    /// Seeds for the win chance simulations
+    Estimates,
+}
+
+impl Stream {
+    pub const ALL: [Stream; 5] = [
+        Stream::Spawning,
+        Stream::Crits,
+        Stream::Mutations,
+        Stream::Visuals,
+        Stream::Estimates,
+    ];
+}
+
+/// Every random number generator used in a run, all seeded from one master seed
+#[derive(Debug, Clone)]
+pub struct RunRng {
+    seed: u64,
+    /// In the same order as `Stream::ALL`
+    streams: [StdRng; 5],
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: Stream::ALL
+                .map(|stream| StdRng::seed_from_u64(Self::stream_seed(seed, stream))),
+        }
+    }
+
+    /// Each stream's seed only depends on the master seed and which stream it is,
+    /// not on how much any other stream has been used
+    fn stream_seed(seed: u64, stream: Stream) -> u64 {
+        let index = Stream::ALL.iter().position(|&x| x == stream).unwrap() as u64;
+        // spread the streams' seeds out, so similar master seeds don't give similar streams
+        seed ^ (index + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: Stream) -> &mut StdRng {
+        let index = Stream::ALL.iter().position(|&x| x == stream).unwrap();
+        &mut self.streams[index]
+    }
+}
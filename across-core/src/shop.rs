@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether the current round is being played, or the player is shopping before the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundPhase {
+    #[default]
+    Fighting,
+    /// Time is stopped until the player starts the next round
+    Shopping,
+}
+
+/// Something which can be bought once each time the shop is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShopItem {
+    ExtraLife,
+    /// Every tower shoots twice as fast for the next round
+    Overcharge,
+    /// Trades a life for money
+    CashIn,
+}
+
+impl ShopItem {
+    pub const ALL: [ShopItem; 3] = [ShopItem::ExtraLife, ShopItem::Overcharge, ShopItem::CashIn];
+
+    /// The name shown to the player, with what it costs
+    pub fn label(&self) -> &'static str {
+        match self {
+            ShopItem::ExtraLife => "Extra life (30)",
+            ShopItem::Overcharge => "Overcharge (40)",
+            ShopItem::CashIn => "1 life for 40",
+        }
+    }
+
+    pub fn price(&self) -> u64 {
+        match self {
+            ShopItem::ExtraLife => 30,
+            ShopItem::Overcharge => 40,
+            ShopItem::CashIn => 0,
+        }
+    }
+}
+
+/// What has been bought since the shop last opened
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Shop {
+    bought: Vec<ShopItem>,
+}
+
+impl Shop {
+    pub fn is_bought(&self, item: ShopItem) -> bool {
+        self.bought.contains(&item)
+    }
+
+    pub fn buy(&mut self, item: ShopItem) {
+        self.bought.push(item);
+    }
+
+    /// Called when the shop opens, so everything can be bought again
+    pub fn restock(&mut self) {
+        self.bought.clear();
+    }
+}
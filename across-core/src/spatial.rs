@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::{bullet::bullet::Hit, enemy::enemy::Enemy, vec2d, vector::Vector, Alive};
+
+/// The width of each square cell. This should be a bit bigger than most enemies.
+const CELL_SIZE: f32 = 64.0;
+
+/// Splits the world into a grid of cells, so only the enemies near a point need to be
+/// checked for collisions, rather than every enemy.
+/// This is built once per frame, and refers to enemies by their index.
+#[derive(Debug)]
+pub struct SpatialHash {
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    /// Enemies which have been killed since the grid was built
+    removed: Vec<bool>,
+    /// Every hit since the grid was built, in order
+    hits: Vec<Hit>,
+}
+
+impl SpatialHash {
+    pub fn new(enemies: &[Enemy<Alive>]) -> Self {
+        let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (i, enemy) in enemies.iter().enumerate() {
+            let (min, max) = Self::cell_range(enemy.position(), enemy.radius());
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    cells.entry((x, y)).or_default().push(i);
+                }
+            }
+        }
+        Self {
+            cells,
+            removed: vec![false; enemies.len()],
+            hits: vec![],
+        }
+    }
+
+    fn cell(position: Vector) -> (i32, i32) {
+        (
+            (position.x / CELL_SIZE).floor() as i32,
+            (position.y / CELL_SIZE).floor() as i32,
+        )
+    }
+
+    /// The first and last cells a circle could overlap
+    fn cell_range(position: Vector, radius: f32) -> ((i32, i32), (i32, i32)) {
+        (
+            Self::cell(position - vec2d![radius, radius]),
+            Self::cell(position + vec2d![radius, radius]),
+        )
+    }
+
+    /// Every enemy which might overlap the circle and hasn't been removed, in index order.
+    /// The caller still needs to check for collisions.
+    pub fn query(&self, position: Vector, radius: f32) -> Vec<usize> {
+        let (min, max) = Self::cell_range(position, radius);
+        let mut found = vec![];
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(cell) = self.cells.get(&(x, y)) {
+                    found.extend(cell.iter().copied().filter(|&i| !self.removed[i]));
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Marks an enemy as killed, so it won't be found again
+    pub fn remove(&mut self, index: usize) {
+        self.removed[index] = true;
+    }
+
+    pub fn record_hit(&mut self, hit: Hit) {
+        self.hits.push(hit);
+    }
+
+    /// Every hit recorded since the grid was built
+    pub fn hits(&self) -> &[Hit] {
+        &self.hits
+    }
+
+    /// Whether an enemy has been killed since the grid was built
+    pub fn is_removed(&self, index: usize) -> bool {
+        self.removed[index]
+    }
+
+    /// Splits the enemies into those which are still in the grid and those which have been
+    /// removed, keeping both in order.
+    /// `enemies` must be the same list the grid was built from.
+    pub fn partition(&self, enemies: Vec<Enemy<Alive>>) -> (Vec<Enemy<Alive>>, Vec<Enemy<Alive>>) {
+        let (kept, removed): (Vec<_>, Vec<_>) = enemies
+            .into_iter()
+            .zip(self.removed.iter())
+            .partition(|(_, &removed)| !removed);
+        (
+            kept.into_iter().map(|(enemy, _)| enemy).collect(),
+            removed.into_iter().map(|(enemy, _)| enemy).collect(),
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// A colour as its red, green and blue, from 0 to 255, as map files write it
+pub type Rgb = (u8, u8, u8);
+
+/// How the points of the web are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStyle {
+    #[default]
+    Circle,
+    Square,
+    Diamond,
+    /// Only the connections are drawn
+    Hidden,
+}
+
+impl NodeStyle {
+    pub const ALL: [NodeStyle; 4] = [
+        NodeStyle::Circle,
+        NodeStyle::Square,
+        NodeStyle::Diamond,
+        NodeStyle::Hidden,
+    ];
+
+    /// The name used for the style in map files
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeStyle::Circle => "circle",
+            NodeStyle::Square => "square",
+            NodeStyle::Diamond => "diamond",
+            NodeStyle::Hidden => "hidden",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+}
+
+/// How a map looks, like a night map with a dark blue background and a pale path.
+/// Anything a map doesn't set is drawn in the palette's colours, and only the standard
+/// palette can be changed by a map, so the other palettes always stay easy to see.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub background: Option<Rgb>,
+    /// An image drawn over the background, from the resources folder
+    pub texture: Option<String>,
+    /// The colour of the route and the points
+    pub path: Option<Rgb>,
+    pub nodes: NodeStyle,
+}
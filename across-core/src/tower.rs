@@ -0,0 +1,2421 @@
+pub mod tower {
+    use std::{
+        cell::RefCell,
+        f32::consts::PI,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use rand::rngs::StdRng;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        angle::Angle,
+        bullet::bullet::{
+            hit, Bouncer, Bullet, BulletTrait, Crit, Hit, Missile, Piercer, Projectile,
+        },
+        enemy::enemy::{Enemy, EnemyId},
+        polygon::Polygon,
+        spatial::SpatialHash,
+        tr, vec2d,
+        vector::Vector,
+        Alive,
+    };
+
+    /// Identifies a tower for as long as the game runs, even as others are placed and sold.
+    /// Entities refer to towers by their ID rather than by reference, so they can be owned
+    /// and stored anywhere.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct TowerId(u64);
+
+    impl TowerId {
+        /// A new ID, different from every other one given out
+        fn next() -> Self {
+            static NEXT: AtomicU64 = AtomicU64::new(0);
+            Self(NEXT.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+
+    pub trait Tower {
+        fn id(&self) -> TowerId;
+        /// The name shown to the player
+        fn name(&self) -> &'static str;
+        /// What this tower has done so far this game
+        fn stats(&self) -> &TowerStats;
+        /// For putting back what a saved tower had done
+        fn stats_mut(&mut self) -> &mut TowerStats;
+        /// How many enemies this tower's bullets have killed
+        fn kills(&self) -> usize {
+            self.stats().kills
+        }
+        /// The veterancy level this tower has reached, from its kills
+        fn level(&self) -> usize {
+            veterancy_level(self.kills())
+        }
+        fn price(&self) -> u64;
+        fn time_until_shot(&self) -> f32;
+        /// Enemies killed by this tower's bullets are removed from the grid.
+        /// `rng` decides which hits are critical.
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        );
+        /// Affects every enemy the tower wants to, every step, rather than by shooting.
+        /// Towers without an aura can ignore this.
+        fn apply_aura(&self, _enemies: &mut [Enemy<Alive>]) {}
+        /// What the tower looks like, for drawing it
+        fn look(&self) -> TowerLook;
+        fn position(&self) -> Vector;
+        fn radius(&self) -> f32;
+        /// Normalised [0-1]. The tower is destroyed when it runs out.
+        fn health(&self) -> f32;
+        /// Only enemies which attack towers do this
+        fn damage(&mut self, dmg: f32);
+        /// Restores the tower to full health
+        fn heal(&mut self);
+        fn range<'a>(&'a self) -> &dyn Range;
+        fn range_mut(&mut self) -> &mut dyn Range;
+        fn spawn(bounds: Vector) -> Box<dyn Tower>
+        where
+            Self: Sized;
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>>;
+        /// Walls block any connection they sit on, so enemies have to go around them
+        fn is_wall(&self) -> bool {
+            false
+        }
+        /// How much the connections this tower can see put enemies off them, where 1 is
+        /// as much as a normal tower. Decoys are negative, to draw enemies in instead.
+        fn danger(&self) -> f32 {
+            1.0
+        }
+        /// Makes the tower shoot this many times as often.
+        /// Towers which don't shoot can ignore this.
+        fn set_fire_rate(&mut self, _multiplier: f32) {}
+        /// Stops the tower shooting, or lets it start again.
+        /// Towers which don't shoot can ignore this.
+        fn set_disabled(&mut self, _disabled: bool) {}
+        /// How likely the tower's hits are to be critical, or None if it never hits anything
+        fn crit(&self) -> Option<Crit> {
+            None
+        }
+        /// Does nothing for towers which never hit anything
+        fn set_crit(&mut self, _crit: Crit) {}
+        /// The labels of extra buttons shown next to the tower while it's selected.
+        /// Most towers don't have any.
+        fn panel(&self) -> Vec<String> {
+            vec![]
+        }
+        /// Presses one of the buttons from `panel`, given its index, which can spend or
+        /// give the player money.
+        /// Returns a message to show the player, if there is one.
+        fn press(&mut self, _button: usize, _money: &mut u64) -> Option<String> {
+            None
+        }
+        /// Called at the end of every round
+        fn end_round(&mut self) {}
+        /// Money given back when the tower is sold or destroyed, on top of any sale price
+        fn refund(&self) -> u64 {
+            0
+        }
+        /// Turns the tower's range by an angle, in radians.
+        /// Returns false if the tower's range can't be turned.
+        fn rotate(&mut self, _angle: f32) -> bool {
+            false
+        }
+        /// The money held by the tower, or None if it can't hold any
+        fn account(&self) -> Option<Account> {
+            None
+        }
+        /// Does nothing for towers which can't hold money
+        fn set_account(&mut self, _account: Account) {}
+    }
+
+    /// What a tower looks like at the moment, which is everything a front end needs to
+    /// know to draw it, beyond where it is, how big it is and what it can see
+    #[derive(Debug, Clone)]
+    pub enum TowerLook {
+        Wall,
+        Test,
+        /// Faces the same way as its range
+        Sector,
+        Freeze {
+            /// Whether its field is switched on
+            active: bool,
+        },
+        Bank,
+        Sniper {
+            /// Where the enemy it's locking on to is, and how close the lock is [0-1]
+            locking_on: Option<(Vector, f32)>,
+        },
+        Tesla {
+            /// Where the lightning from the last burst struck
+            strikes: Vec<Vector>,
+            /// How much of the lightning is left on screen [0-1]
+            flash: f32,
+            /// Normalised [0-1]
+            charge: f32,
+        },
+        Decoy,
+        Missile,
+        Bouncer,
+        Lance,
+    }
+
+    /// The money held by a tower such as a bank
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+    pub struct Account {
+        /// Given back when the tower is sold
+        pub deposited: u64,
+        /// What's been put in, with the interest
+        pub balance: f64,
+    }
+
+    /// Everything about a tower which can change after it's placed, so a saved tower
+    /// comes back just as it was, upgrades, kills and all
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct TowerState {
+        /// Normalised [0-1]
+        pub health: f32,
+        /// The kills in here give the tower its veterancy
+        pub stats: TowerStats,
+        pub radius: f32,
+        pub fov: Option<f32>,
+        /// In radians
+        pub direction: Option<f32>,
+        pub crit: Option<Crit>,
+        pub account: Option<Account>,
+    }
+
+    impl TowerState {
+        pub fn of(tower: &dyn Tower) -> Self {
+            Self {
+                health: tower.health(),
+                stats: *tower.stats(),
+                radius: tower.range().radius(),
+                fov: tower.range().fov(),
+                direction: tower.range().direction(),
+                crit: tower.crit(),
+                account: tower.account(),
+            }
+        }
+
+        /// Puts a tower back into this state. Anything the tower doesn't have, such as a
+        /// direction for a tower which sees all the way around, is left alone.
+        pub fn apply(&self, tower: &mut dyn Tower) {
+            tower.heal();
+            tower.damage(1.0 - self.health);
+            *tower.stats_mut() = self.stats;
+            let range = tower.range_mut();
+            range.set_radius(self.radius);
+            if let Some(fov) = self.fov {
+                range.set_fov(fov);
+            }
+            if let (Some(direction), Some(current)) = (self.direction, range.direction()) {
+                tower.rotate(direction - current);
+            }
+            if let Some(crit) = self.crit {
+                tower.set_crit(crit);
+            }
+            if let Some(account) = self.account {
+                tower.set_account(account);
+            }
+        }
+    }
+
+    /// The kills needed to reach each veterancy level after the first.
+    /// Each kill is worth one experience point.
+    const LEVEL_KILLS: [usize; 3] = [5, 15, 30];
+    /// How much faster a tower shoots for each veterancy level
+    const FIRE_RATE_PER_LEVEL: f32 = 0.1;
+
+    /// The veterancy level reached with this many kills, from 0 to 3
+    pub fn veterancy_level(kills: usize) -> usize {
+        LEVEL_KILLS.iter().filter(|&&x| kills >= x).count()
+    }
+
+    /// How much of a tower's price it costs to repair it from nothing
+    const REPAIR_COST: f32 = 0.5;
+
+    /// The price of repairing a tower to full health, which is more the more damaged it is
+    pub fn repair_price(tower: &dyn Tower) -> u64 {
+        ((1.0 - tower.health().clamp(0.0, 1.0)) * tower.price() as f32 * REPAIR_COST).ceil() as u64
+    }
+
+    /// Everything a tower has done, for showing to the player at the end of a game
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+    pub struct TowerStats {
+        pub shots_fired: usize,
+        /// The total health taken away from enemies
+        pub damage: f32,
+        pub kills: usize,
+    }
+
+    impl TowerStats {
+        /// Counts the damage and kills from the tower's hits
+        fn record(&mut self, hits: &[Hit]) {
+            self.damage += hits.iter().map(|hit| hit.damage).sum::<f32>();
+            self.kills += hits.iter().filter(|hit| hit.killed).count();
+        }
+    }
+
+    /// The view of a tower
+    pub trait Range {
+        /// Picks an enemy in range to shoot at, which isn't hidden behind an obstacle.
+        /// Enemies inside the priority zone are preferred, if there is one.
+        fn get_target<'b>(
+            &self,
+            enemies: &'b [Enemy<Alive>],
+            grid: &SpatialHash,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+        ) -> Option<&'b Enemy<Alive>>;
+        /// How much of the line from `a` to `b` can be seen, normalised [0-1]
+        fn coverage(&self, a: Vector, b: Vector) -> f32;
+        /// How close a point is to the tower, from 0 (out of range) to 1 (on the tower)
+        fn closeness(&self, point: Vector) -> f32;
+        /// How dangerous the edge from `a` to `b` is, normalised [0-1].
+        /// Unlike `coverage`, this also counts how close the range is to the nodes at each
+        /// end, as enemies slow down to turn there.
+        fn edge_coverage(&self, a: Vector, b: Vector) -> f32 {
+            blend_coverage(
+                self.coverage(a, b),
+                self.closeness(a).max(self.closeness(b)),
+            )
+        }
+        fn radius(&self) -> f32;
+        fn set_radius(&mut self, radius: f32);
+        /// The angle the range can see across, or None if it can see all the way around
+        fn fov(&self) -> Option<f32> {
+            None
+        }
+        /// Does nothing for ranges which can see all the way around
+        fn set_fov(&mut self, _fov: f32) {}
+        /// The direction the range faces in radians, or None if it can see all the way around
+        fn direction(&self) -> Option<f32> {
+            None
+        }
+    }
+
+    /// Something which can be bought to improve a placed tower
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Upgrade {
+        /// Makes the range reach further
+        Radius,
+        /// Widens a sector range
+        Fov,
+        /// Makes critical hits more likely
+        Crit,
+    }
+
+    impl Upgrade {
+        pub fn price(&self) -> u64 {
+            match self {
+                Upgrade::Radius => 20,
+                Upgrade::Fov => 20,
+                Upgrade::Crit => 25,
+            }
+        }
+
+        /// Applies the upgrade to a tower.
+        /// Returns false if the tower can't be upgraded any more.
+        pub fn apply(&self, tower: &mut dyn Tower) -> bool {
+            const MAX_RADIUS: f32 = 400.0;
+            const MAX_CRIT_CHANCE: f32 = 0.5;
+            if let (Upgrade::Crit, Some(crit)) = (self, tower.crit()) {
+                if crit.chance >= MAX_CRIT_CHANCE {
+                    return false;
+                }
+                tower.set_crit(Crit {
+                    chance: (crit.chance + 0.1).min(MAX_CRIT_CHANCE),
+                    ..crit
+                });
+                return true;
+            }
+            let range = tower.range_mut();
+            match self {
+                Upgrade::Radius if range.radius() > 0.0 && range.radius() < MAX_RADIUS => {
+                    range.set_radius((range.radius() * 1.25).min(MAX_RADIUS));
+                    true
+                }
+                Upgrade::Fov => match range.fov() {
+                    Some(fov) if fov < PI => {
+                        range.set_fov((fov + PI / 6.0).min(PI));
+                        true
+                    }
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+    }
+
+    /// How much of an edge's coverage comes from how close the range is to its nodes
+    pub const NODE_COVERAGE_WEIGHT: f32 = 0.25;
+
+    /// Combines the fraction of an edge in range with how close the range gets to
+    /// the edge's nodes, as in `Range::edge_coverage`
+    pub fn blend_coverage(along: f32, near: f32) -> f32 {
+        (1.0 - NODE_COVERAGE_WEIGHT) * along + NODE_COVERAGE_WEIGHT * near
+    }
+
+    /// Whether nothing blocks the line from `from` to `to`
+    pub fn can_see(from: Vector, to: Vector, obstacles: &[Polygon]) -> bool {
+        !obstacles
+            .iter()
+            .any(|obstacle| obstacle.blocks_line(from, to))
+    }
+
+    /// The final filter stage for targeting.
+    /// Takes every enemy in range and picks the first one inside the priority zone,
+    /// falling back to the first one in range if none are in the zone.
+    fn prioritise<'b>(
+        mut in_range: impl Iterator<Item = &'b Enemy<Alive>>,
+        priority_zone: Option<&Polygon>,
+    ) -> Option<&'b Enemy<Alive>> {
+        match priority_zone {
+            Some(zone) if zone.is_valid() => {
+                let first = in_range.next()?;
+                if zone.contains(first.position()) {
+                    return Some(first);
+                }
+                Some(
+                    in_range
+                        .find(|enemy| zone.contains(enemy.position()))
+                        .unwrap_or(first),
+                )
+            }
+            _ => in_range.next(),
+        }
+    }
+    pub struct CircularRange {
+        position: Vector,
+        radius: f32,
+    }
+    impl Range for CircularRange {
+        fn get_target<'b>(
+            &self,
+            enemies: &'b [Enemy<Alive>],
+            grid: &SpatialHash,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+        ) -> Option<&'b Enemy<Alive>> {
+            prioritise(
+                grid.query(self.position, self.radius)
+                    .into_iter()
+                    .map(|i| &enemies[i])
+                    .filter(|enemy| enemy.collides(self.position, self.radius))
+                    .filter(|enemy| can_see(self.position, enemy.position(), obstacles)),
+                priority_zone,
+            )
+        }
+
+        fn coverage(&self, a: Vector, b: Vector) -> f32 {
+            // solve |a + t(b - a) - centre| = radius for t
+            let direction = b - a;
+            let offset = a - self.position;
+            let qa = direction.dot(direction);
+            let qb = 2.0 * offset.dot(direction);
+            let qc = offset.dot(offset) - self.radius * self.radius;
+            let discriminant = qb * qb - 4.0 * qa * qc;
+            if qa == 0.0 || discriminant <= 0.0 {
+                return 0.0;
+            }
+            let root = discriminant.sqrt();
+            let enter = ((-qb - root) / (2.0 * qa)).clamp(0.0, 1.0);
+            let exit = ((-qb + root) / (2.0 * qa)).clamp(0.0, 1.0);
+            exit - enter
+        }
+
+        fn closeness(&self, point: Vector) -> f32 {
+            if self.radius <= 0.0 {
+                return 0.0;
+            }
+            (1.0 - (point - self.position).length() / self.radius).max(0.0)
+        }
+
+        fn radius(&self) -> f32 {
+            self.radius
+        }
+
+        fn set_radius(&mut self, radius: f32) {
+            self.radius = radius;
+        }
+    }
+
+    pub struct SectorRange {
+        position: Vector,
+        radius: f32,
+        /// The direction in which this sector faces
+        direction: Angle,
+        /// The field of view (an angle) of this range
+        fov: f32,
+    }
+    impl SectorRange {
+        /// Whether a point is inside the sector, including its edges
+        fn contains(&self, point: Vector) -> bool {
+            let offset = point - self.position;
+            offset.sqr_length() <= self.radius * self.radius
+                && self.direction.distance_to(Angle::of(offset)).abs() <= self.fov / 2.0
+        }
+    }
+
+    impl Range for SectorRange {
+        fn get_target<'b>(
+            &self,
+            enemies: &'b [Enemy<Alive>],
+            grid: &SpatialHash,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+        ) -> Option<&'b Enemy<Alive>> {
+            prioritise(
+                grid.query(self.position, self.radius)
+                    .into_iter()
+                    .map(|i| &enemies[i])
+                    .filter(|enemy| {
+                        enemy.collides(self.position, self.radius)
+                            && self
+                                .direction
+                                .distance_to(Angle::of(enemy.position() - self.position))
+                                .abs()
+                                <= self.fov / 2.0
+                    })
+                    .filter(|enemy| can_see(self.position, enemy.position(), obstacles)),
+                priority_zone,
+            )
+        }
+
+        fn coverage(&self, a: Vector, b: Vector) -> f32 {
+            // The line can only move into or out of the sector where it crosses the arc or
+            // one of the straight edges, so split it at those points. Each piece is then
+            // either completely inside or completely outside, which its middle shows.
+            let direction = b - a;
+            let offset = a - self.position;
+            let qa = direction.dot(direction);
+            if qa == 0.0 {
+                return 0.0;
+            }
+            let mut splits = vec![0.0, 1.0];
+            let qb = 2.0 * offset.dot(direction);
+            let qc = offset.dot(offset) - self.radius * self.radius;
+            let discriminant = qb * qb - 4.0 * qa * qc;
+            if discriminant > 0.0 {
+                let root = discriminant.sqrt();
+                splits.push((-qb - root) / (2.0 * qa));
+                splits.push((-qb + root) / (2.0 * qa));
+            }
+            for edge in [-1.0, 1.0] {
+                let along = (self.direction + edge * self.fov / 2.0).direction();
+                let denominator = along.perpendicular_dot(direction);
+                if denominator != 0.0 {
+                    splits.push(-along.perpendicular_dot(offset) / denominator);
+                }
+            }
+            let mut splits: Vec<f32> = splits
+                .into_iter()
+                .filter(|t| t.is_finite())
+                .map(|t| t.clamp(0.0, 1.0))
+                .collect();
+            splits.sort_by(|x, y| x.total_cmp(y));
+            splits
+                .windows(2)
+                .filter(|x| self.contains(a + direction * ((x[0] + x[1]) / 2.0)))
+                .map(|x| x[1] - x[0])
+                .sum::<f32>()
+                .clamp(0.0, 1.0)
+        }
+
+        fn closeness(&self, point: Vector) -> f32 {
+            if self.radius <= 0.0 || !self.contains(point) {
+                return 0.0;
+            }
+            1.0 - (point - self.position).length() / self.radius
+        }
+
+        fn radius(&self) -> f32 {
+            self.radius
+        }
+
+        fn set_radius(&mut self, radius: f32) {
+            self.radius = radius;
+        }
+
+        fn fov(&self) -> Option<f32> {
+            Some(self.fov)
+        }
+
+        fn set_fov(&mut self, fov: f32) {
+            self.fov = fov;
+        }
+
+        fn direction(&self) -> Option<f32> {
+            Some(self.direction.radians())
+        }
+    }
+
+    /// The enemy with the most health left
+    fn strongest<'b>(enemies: impl Iterator<Item = &'b Enemy<Alive>>) -> Option<&'b Enemy<Alive>> {
+        enemies.max_by(|a, b| a.health().total_cmp(&b.health()))
+    }
+
+    /// Sees the whole map, so only obstacles can hide enemies from it.
+    /// It picks the strongest enemy rather than the first.
+    pub struct GlobalRange {
+        position: Vector,
+    }
+    impl Range for GlobalRange {
+        fn get_target<'b>(
+            &self,
+            enemies: &'b [Enemy<Alive>],
+            grid: &SpatialHash,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+        ) -> Option<&'b Enemy<Alive>> {
+            let visible = || {
+                enemies
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, enemy)| !grid.is_removed(i) && !enemy.spawning())
+                    .map(|(_, enemy)| enemy)
+                    .filter(|enemy| can_see(self.position, enemy.position(), obstacles))
+            };
+            priority_zone
+                .filter(|zone| zone.is_valid())
+                .and_then(|zone| {
+                    strongest(visible().filter(|enemy| zone.contains(enemy.position())))
+                })
+                .or_else(|| strongest(visible()))
+        }
+
+        fn coverage(&self, _a: Vector, _b: Vector) -> f32 {
+            1.0
+        }
+
+        /// Nowhere is any more dangerous for being close to the tower
+        fn closeness(&self, _point: Vector) -> f32 {
+            0.0
+        }
+
+        fn radius(&self) -> f32 {
+            f32::INFINITY
+        }
+
+        /// The range already reaches everywhere
+        fn set_radius(&mut self, _radius: f32) {}
+    }
+
+    pub fn spawn_tower(position: Vector) -> Box<dyn Tower> {
+        TestTower::spawn(position)
+    }
+
+    pub fn spawn_sector_tower(position: Vector) -> Box<dyn Tower> {
+        SectorTower::spawn(position)
+    }
+
+    pub fn spawn_wall(position: Vector) -> Box<dyn Tower> {
+        WallTower::spawn(position)
+    }
+
+    pub fn spawn_freeze_tower(position: Vector) -> Box<dyn Tower> {
+        FreezeTower::spawn(position)
+    }
+
+    pub fn spawn_bank(position: Vector) -> Box<dyn Tower> {
+        BankTower::spawn(position)
+    }
+
+    pub fn spawn_sniper(position: Vector) -> Box<dyn Tower> {
+        SniperTower::spawn(position)
+    }
+
+    pub fn spawn_tesla(position: Vector) -> Box<dyn Tower> {
+        TeslaTower::spawn(position)
+    }
+
+    pub fn spawn_decoy(position: Vector) -> Box<dyn Tower> {
+        DecoyTower::spawn(position)
+    }
+
+    pub fn spawn_missile_tower(position: Vector) -> Box<dyn Tower> {
+        MissileTower::spawn(position)
+    }
+
+    pub fn spawn_bouncer_tower(position: Vector) -> Box<dyn Tower> {
+        BouncerTower::spawn(position)
+    }
+
+    pub fn spawn_lance_tower(position: Vector) -> Box<dyn Tower> {
+        LanceTower::spawn(position)
+    }
+
+    /// A cheap tower which never shoots, but blocks the connections it's placed on
+    pub struct WallTower {
+        id: TowerId,
+        position: Vector,
+        /// Walls can't see anything, so this has no radius
+        range: CircularRange,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        stats: TowerStats,
+        health: f32,
+    }
+    impl Tower for WallTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Wall"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            5
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            f32::INFINITY
+        }
+
+        fn update(
+            &mut self,
+            _enemies: &mut [Enemy<Alive>],
+            _grid: &mut SpatialHash,
+            _bounds: Vector,
+            _priority_zone: Option<&Polygon>,
+            _obstacles: &[Polygon],
+            _rng: &mut StdRng,
+        ) {
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Wall
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            15.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        /// Walls are built to be hit, so take half damage
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg * 0.5);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                position,
+                range: CircularRange {
+                    position,
+                    radius: 0.0,
+                },
+                bullets: RefCell::new(vec![]),
+                stats: TowerStats::default(),
+                health: 1.0,
+            }) as Box<dyn Tower>
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn is_wall(&self) -> bool {
+            true
+        }
+    }
+
+    pub struct TestTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: CircularRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+    }
+    impl TestTower {
+        #[inline(always)]
+        fn cooldown() -> usize {
+            60
+        }
+
+        fn new(position: Vector) -> Self {
+            Self {
+                id: TowerId::next(),
+                time_to_next_shot: Self::cooldown(),
+                position,
+                bullets: RefCell::new(vec![]),
+                range: CircularRange {
+                    position,
+                    radius: 150.0,
+                },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+            }
+        }
+
+        /// Just for testing
+        pub fn debug_spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self::new(position)) as Box<dyn Tower>
+        }
+    }
+    impl Tower for TestTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Test Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        #[inline(always)]
+        fn price(&self) -> u64 {
+            10
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            match self.time_to_next_shot {
+                _ if self.disabled => (),
+                0 => {
+                    // shoot!
+                    match self
+                        .range
+                        .get_target(enemies, grid, priority_zone, obstacles)
+                    {
+                        Some(enemy) => {
+                            self.bullets
+                                .borrow_mut()
+                                .push(Bullet::new(Projectile::spawn(self, enemy.position())));
+                            let fire_rate =
+                                self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                            self.time_to_next_shot =
+                                (TestTower::cooldown() as f32 / fire_rate) as usize;
+                            self.stats.shots_fired += 1;
+                        }
+                        None => (),
+                    }
+                }
+                _ => self.time_to_next_shot -= 1,
+            }
+            let bullets = Vec::with_capacity(self.bullets.borrow().len());
+            let bullets = self.bullets.replace(bullets);
+            let (new_bullets, hits) = Bullet::update_all(bullets, enemies, grid, bounds, rng);
+            self.stats.record(&hits);
+            self.bullets.replace(new_bullets);
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Test
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self::new(vec2d![position.x, position.y])) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            self.time_to_next_shot as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            10.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    /// Sees further than a test tower, but only in one direction, which can be turned
+    pub struct SectorTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: SectorRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+    }
+    impl SectorTower {
+        fn cooldown() -> usize {
+            45
+        }
+    }
+    impl Tower for SectorTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Sector Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            15
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            match self.time_to_next_shot {
+                _ if self.disabled => (),
+                0 => {
+                    if let Some(enemy) =
+                        self.range
+                            .get_target(enemies, grid, priority_zone, obstacles)
+                    {
+                        self.bullets
+                            .borrow_mut()
+                            .push(Bullet::new(Projectile::spawn(self, enemy.position())));
+                        let fire_rate =
+                            self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                        self.time_to_next_shot =
+                            (SectorTower::cooldown() as f32 / fire_rate) as usize;
+                        self.stats.shots_fired += 1;
+                    }
+                }
+                _ => self.time_to_next_shot -= 1,
+            }
+            let bullets = Vec::with_capacity(self.bullets.borrow().len());
+            let bullets = self.bullets.replace(bullets);
+            let (new_bullets, hits) = Bullet::update_all(bullets, enemies, grid, bounds, rng);
+            self.stats.record(&hits);
+            self.bullets.replace(new_bullets);
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Sector
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                time_to_next_shot: Self::cooldown(),
+                position,
+                bullets: RefCell::new(vec![]),
+                range: SectorRange {
+                    position,
+                    radius: 250.0,
+                    direction: Angle::from_radians(-PI / 2.0),
+                    fov: PI / 3.0,
+                },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            self.time_to_next_shot as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            10.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+
+        fn rotate(&mut self, angle: f32) -> bool {
+            self.range.direction += angle;
+            true
+        }
+    }
+
+    /// How fast enemies move inside a freeze tower's field, compared to normal
+    const FREEZE_SPEED: f32 = 0.4;
+
+    /// Never shoots, but slows down every enemy inside its range
+    pub struct FreezeTower {
+        id: TowerId,
+        position: Vector,
+        range: CircularRange,
+        /// Always empty, as freeze towers don't shoot
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        stats: TowerStats,
+        health: f32,
+        /// Whether the field has been switched off, while the tower is repaired
+        disabled: bool,
+    }
+    impl Tower for FreezeTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Freeze Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            20
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            f32::INFINITY
+        }
+
+        fn update(
+            &mut self,
+            _enemies: &mut [Enemy<Alive>],
+            _grid: &mut SpatialHash,
+            _bounds: Vector,
+            _priority_zone: Option<&Polygon>,
+            _obstacles: &[Polygon],
+            _rng: &mut StdRng,
+        ) {
+        }
+
+        fn apply_aura(&self, enemies: &mut [Enemy<Alive>]) {
+            if self.disabled {
+                return;
+            }
+            for enemy in enemies.iter_mut() {
+                let reach = self.range.radius + enemy.radius();
+                if (enemy.position() - self.position).sqr_length() <= reach * reach {
+                    enemy.slow(FREEZE_SPEED);
+                }
+            }
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Freeze {
+                active: !self.disabled,
+            }
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            12.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                position,
+                range: CircularRange {
+                    position,
+                    radius: 120.0,
+                },
+                bullets: RefCell::new(vec![]),
+                stats: TowerStats::default(),
+                health: 1.0,
+                disabled: false,
+            }) as Box<dyn Tower>
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+    }
+
+    /// How much a bank tower's balance grows by at the end of every round
+    const INTEREST_RATE: f64 = 0.1;
+    /// How much each press of a bank tower's deposit button puts in
+    const DEPOSIT: u64 = 10;
+
+    /// Never shoots, but holds money and pays compound interest on it every round.
+    /// Selling or losing the tower only gives back what was put in, without the interest.
+    pub struct BankTower {
+        id: TowerId,
+        position: Vector,
+        /// Banks can't see anything, so this has no radius
+        range: CircularRange,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        stats: TowerStats,
+        health: f32,
+        /// The money put in since the last withdrawal
+        deposited: u64,
+        /// What's been put in, with the interest
+        balance: f64,
+    }
+    impl Tower for BankTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Bank"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            30
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            f32::INFINITY
+        }
+
+        fn update(
+            &mut self,
+            _enemies: &mut [Enemy<Alive>],
+            _grid: &mut SpatialHash,
+            _bounds: Vector,
+            _priority_zone: Option<&Polygon>,
+            _obstacles: &[Polygon],
+            _rng: &mut StdRng,
+        ) {
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Bank
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            12.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                position,
+                range: CircularRange {
+                    position,
+                    radius: 0.0,
+                },
+                bullets: RefCell::new(vec![]),
+                stats: TowerStats::default(),
+                health: 1.0,
+                deposited: 0,
+                balance: 0.0,
+            }) as Box<dyn Tower>
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn panel(&self) -> Vec<String> {
+            vec![
+                tr!("Deposit {}", DEPOSIT),
+                tr!("Withdraw {}", self.balance.floor()),
+            ]
+        }
+
+        fn press(&mut self, button: usize, money: &mut u64) -> Option<String> {
+            match button {
+                0 if *money < DEPOSIT => Some(tr!("Not enough money")),
+                0 => {
+                    *money -= DEPOSIT;
+                    self.deposited += DEPOSIT;
+                    self.balance += DEPOSIT as f64;
+                    None
+                }
+                1 => {
+                    let withdrawn = self.balance.floor() as u64;
+                    *money += withdrawn;
+                    self.deposited = 0;
+                    self.balance = 0.0;
+                    Some(tr!("Withdrew {}", withdrawn))
+                }
+                _ => None,
+            }
+        }
+
+        fn end_round(&mut self) {
+            self.balance *= 1.0 + INTEREST_RATE;
+        }
+
+        fn refund(&self) -> u64 {
+            self.deposited
+        }
+
+        fn account(&self) -> Option<Account> {
+            Some(Account {
+                deposited: self.deposited,
+                balance: self.balance,
+            })
+        }
+
+        fn set_account(&mut self, account: Account) {
+            self.deposited = account.deposited;
+            self.balance = account.balance;
+        }
+    }
+
+    /// How many steps a sniper has to stay locked on to an enemy before it can shoot
+    const LOCK_ON_STEPS: usize = 60;
+    /// How much damage a sniper's shot does, which is enough to kill even a juggernaut
+    const SNIPER_DAMAGE: f32 = 3.0;
+
+    /// Can hit any enemy it can see, anywhere on the map, but has to lock on to the
+    /// strongest one for a while first, and takes a long time to reload
+    pub struct SniperTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        /// Always empty, as the sniper hits its target straight away
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: GlobalRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+        /// The enemy being locked on to, with where it was last step
+        target: Option<(EnemyId, Vector)>,
+        /// How many steps the sniper has been locked on to its target
+        lock: usize,
+    }
+    impl SniperTower {
+        fn cooldown() -> usize {
+            240
+        }
+
+        /// Where the enemy being locked on to is in the list, if it's still alive and in sight
+        fn find_target(
+            &self,
+            enemies: &[Enemy<Alive>],
+            grid: &SpatialHash,
+            obstacles: &[Polygon],
+        ) -> Option<usize> {
+            let (id, _) = self.target?;
+            enemies.iter().enumerate().position(|(i, enemy)| {
+                enemy.id() == id
+                    && !grid.is_removed(i)
+                    && can_see(self.position, enemy.position(), obstacles)
+            })
+        }
+    }
+    impl Tower for SniperTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Sniper Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            35
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            _bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            if self.disabled {
+                self.target = None;
+                return;
+            }
+            if self.time_to_next_shot > 0 {
+                self.time_to_next_shot -= 1;
+                return;
+            }
+            let index = match self.find_target(enemies, grid, obstacles) {
+                Some(index) => Some(index),
+                // a target which dies or goes out of sight is swapped for a new one
+                None => {
+                    self.lock = 0;
+                    self.range
+                        .get_target(enemies, grid, priority_zone, obstacles)
+                        .and_then(|enemy| enemies.iter().position(|x| x.id() == enemy.id()))
+                }
+            };
+            let Some(index) = index else {
+                self.target = None;
+                return;
+            };
+            let enemy = &enemies[index];
+            self.target = Some((enemy.id(), enemy.position()));
+            self.lock += 1;
+            if self.lock >= LOCK_ON_STEPS {
+                // shoot!
+                let hit = hit(enemies, grid, index, SNIPER_DAMAGE, self.crit, rng);
+                self.stats.shots_fired += 1;
+                self.stats.record(&[hit]);
+                self.target = None;
+                self.lock = 0;
+                let fire_rate = self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                self.time_to_next_shot = (SniperTower::cooldown() as f32 / fire_rate) as usize;
+            }
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Sniper {
+                locking_on: self
+                    .target
+                    .map(|(_, target)| (target, self.lock as f32 / LOCK_ON_STEPS as f32)),
+            }
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                time_to_next_shot: 0,
+                position,
+                bullets: RefCell::new(vec![]),
+                range: GlobalRange { position },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+                target: None,
+                lock: 0,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            (self.time_to_next_shot + LOCK_ON_STEPS.saturating_sub(self.lock)) as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            10.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    /// How many steps a tesla tower takes to charge up fully
+    const TESLA_CHARGE_STEPS: usize = 300;
+    /// The least charge a tesla tower needs to release a burst, normalised [0-1]
+    const TESLA_MIN_CHARGE: f32 = 0.2;
+    /// How many of the closest enemies a burst strikes
+    const TESLA_STRIKES: usize = 3;
+    /// How much damage a fully charged burst does to each enemy it strikes
+    const TESLA_MAX_DAMAGE: f32 = 2.0;
+    /// How many steps the lightning from a burst stays on screen
+    const TESLA_FLASH_STEPS: usize = 15;
+
+    /// Builds up charge while it waits, then releases it all in one burst as soon as an
+    /// enemy comes into range. The more charge it has, the more damage the burst does.
+    pub struct TeslaTower {
+        id: TowerId,
+        position: Vector,
+        range: CircularRange,
+        /// Always empty, as the burst strikes straight away
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        stats: TowerStats,
+        /// How many times as fast as normal this tower charges
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from charging, while it's repaired
+        disabled: bool,
+        crit: Crit,
+        /// Normalised [0-1]
+        charge: f32,
+        /// Where the enemies the last burst struck were, for drawing the lightning
+        strikes: Vec<Vector>,
+        /// How many more steps the lightning is drawn for
+        flash: usize,
+    }
+    impl Tower for TeslaTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Tesla Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            25
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            _bounds: Vector,
+            _priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            self.flash = self.flash.saturating_sub(1);
+            if self.disabled {
+                return;
+            }
+            let fire_rate = self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+            self.charge = (self.charge + fire_rate / TESLA_CHARGE_STEPS as f32).min(1.0);
+            if self.charge < TESLA_MIN_CHARGE {
+                return;
+            }
+            let mut in_range: Vec<usize> = grid
+                .query(self.position, self.range.radius)
+                .into_iter()
+                .filter(|&i| enemies[i].collides(self.position, self.range.radius))
+                .filter(|&i| can_see(self.position, enemies[i].position(), obstacles))
+                .collect();
+            if in_range.is_empty() {
+                return;
+            }
+            // the closest enemies are struck first
+            in_range.sort_by(|&a, &b| {
+                let a = (enemies[a].position() - self.position).sqr_length();
+                let b = (enemies[b].position() - self.position).sqr_length();
+                a.total_cmp(&b)
+            });
+            in_range.truncate(TESLA_STRIKES);
+            let damage = self.charge * TESLA_MAX_DAMAGE;
+            let hits: Vec<Hit> = in_range
+                .into_iter()
+                .map(|i| hit(enemies, grid, i, damage, self.crit, rng))
+                .collect();
+            self.stats.shots_fired += 1;
+            self.stats.record(&hits);
+            self.strikes = hits.iter().map(|hit| hit.position).collect();
+            self.flash = TESLA_FLASH_STEPS;
+            self.charge = 0.0;
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Tesla {
+                strikes: self.strikes.clone(),
+                flash: self.flash as f32 / TESLA_FLASH_STEPS as f32,
+                charge: self.charge,
+            }
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                position,
+                range: CircularRange {
+                    position,
+                    radius: 100.0,
+                },
+                bullets: RefCell::new(vec![]),
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+                charge: 0.0,
+                strikes: vec![],
+                flash: 0,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            ((TESLA_MIN_CHARGE - self.charge).max(0.0) * TESLA_CHARGE_STEPS as f32).ceil()
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            10.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    /// How much a decoy draws enemies to the connections it can see.
+    /// A connection it covers completely costs half as much to travel along.
+    const DECOY_DANGER: f32 = -0.1;
+
+    /// Never shoots, but makes the connections around it look safer than they are, so
+    /// enemies can be led past other towers
+    pub struct DecoyTower {
+        id: TowerId,
+        position: Vector,
+        range: CircularRange,
+        /// Always empty, as decoys don't shoot
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        stats: TowerStats,
+        health: f32,
+    }
+    impl Tower for DecoyTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Decoy"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            15
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            f32::INFINITY
+        }
+
+        fn update(
+            &mut self,
+            _enemies: &mut [Enemy<Alive>],
+            _grid: &mut SpatialHash,
+            _bounds: Vector,
+            _priority_zone: Option<&Polygon>,
+            _obstacles: &[Polygon],
+            _rng: &mut StdRng,
+        ) {
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Decoy
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            12.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                position,
+                range: CircularRange {
+                    position,
+                    radius: 120.0,
+                },
+                bullets: RefCell::new(vec![]),
+                stats: TowerStats::default(),
+                health: 1.0,
+            }) as Box<dyn Tower>
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn danger(&self) -> f32 {
+            DECOY_DANGER
+        }
+    }
+
+    /// Shoots slow missiles which burst into bomblets where they land, to catch groups
+    pub struct MissileTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: CircularRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+    }
+    impl MissileTower {
+        fn cooldown() -> usize {
+            150
+        }
+    }
+    impl Tower for MissileTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Missile Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            30
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            match self.time_to_next_shot {
+                _ if self.disabled => (),
+                0 => {
+                    if let Some(enemy) =
+                        self.range
+                            .get_target(enemies, grid, priority_zone, obstacles)
+                    {
+                        self.bullets
+                            .borrow_mut()
+                            .push(Bullet::new(Missile::spawn(self, enemy.position())));
+                        let fire_rate =
+                            self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                        self.time_to_next_shot =
+                            (MissileTower::cooldown() as f32 / fire_rate) as usize;
+                        self.stats.shots_fired += 1;
+                    }
+                }
+                _ => self.time_to_next_shot -= 1,
+            }
+            let bullets = Vec::with_capacity(self.bullets.borrow().len());
+            let bullets = self.bullets.replace(bullets);
+            let (new_bullets, hits) = Bullet::update_all(bullets, enemies, grid, bounds, rng);
+            self.stats.record(&hits);
+            self.bullets.replace(new_bullets);
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Missile
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                time_to_next_shot: Self::cooldown(),
+                position,
+                bullets: RefCell::new(vec![]),
+                range: CircularRange {
+                    position,
+                    radius: 200.0,
+                },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            self.time_to_next_shot as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            12.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    /// Cheap and inaccurate, but its bullets bounce off the edges of the screen, so they
+    /// can hit something on the way back
+    pub struct BouncerTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: CircularRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+    }
+    impl BouncerTower {
+        fn cooldown() -> usize {
+            50
+        }
+    }
+    impl Tower for BouncerTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Bouncer Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            8
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            match self.time_to_next_shot {
+                _ if self.disabled => (),
+                0 => {
+                    if let Some(enemy) =
+                        self.range
+                            .get_target(enemies, grid, priority_zone, obstacles)
+                    {
+                        self.bullets
+                            .borrow_mut()
+                            .push(Bullet::new(Bouncer::spawn(self, enemy.position())));
+                        let fire_rate =
+                            self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                        self.time_to_next_shot =
+                            (BouncerTower::cooldown() as f32 / fire_rate) as usize;
+                        self.stats.shots_fired += 1;
+                    }
+                }
+                _ => self.time_to_next_shot -= 1,
+            }
+            let bullets = Vec::with_capacity(self.bullets.borrow().len());
+            let bullets = self.bullets.replace(bullets);
+            let (new_bullets, hits) = Bullet::update_all(bullets, enemies, grid, bounds, rng);
+            self.stats.record(&hits);
+            self.bullets.replace(new_bullets);
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Bouncer
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                time_to_next_shot: Self::cooldown(),
+                position,
+                bullets: RefCell::new(vec![]),
+                range: CircularRange {
+                    position,
+                    radius: 120.0,
+                },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            self.time_to_next_shot as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            9.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    /// Shoots fast bolts which go through every enemy they kill, to pick off lines of enemies
+    pub struct LanceTower {
+        id: TowerId,
+        time_to_next_shot: usize,
+        position: Vector,
+        bullets: RefCell<Vec<Bullet<Alive>>>,
+        range: CircularRange,
+        stats: TowerStats,
+        /// How many times as often as normal this tower shoots
+        fire_rate: f32,
+        health: f32,
+        /// Whether the tower has been stopped from shooting, while it's repaired
+        disabled: bool,
+        crit: Crit,
+    }
+    impl LanceTower {
+        fn cooldown() -> usize {
+            90
+        }
+    }
+    impl Tower for LanceTower {
+        fn id(&self) -> TowerId {
+            self.id
+        }
+
+        fn name(&self) -> &'static str {
+            "Lance Tower"
+        }
+
+        fn stats(&self) -> &TowerStats {
+            &self.stats
+        }
+
+        fn stats_mut(&mut self) -> &mut TowerStats {
+            &mut self.stats
+        }
+
+        fn price(&self) -> u64 {
+            25
+        }
+
+        fn update(
+            &mut self,
+            enemies: &mut [Enemy<Alive>],
+            grid: &mut SpatialHash,
+            bounds: Vector,
+            priority_zone: Option<&Polygon>,
+            obstacles: &[Polygon],
+            rng: &mut StdRng,
+        ) {
+            match self.time_to_next_shot {
+                _ if self.disabled => (),
+                0 => {
+                    if let Some(enemy) =
+                        self.range
+                            .get_target(enemies, grid, priority_zone, obstacles)
+                    {
+                        self.bullets
+                            .borrow_mut()
+                            .push(Bullet::new(Piercer::spawn(self, enemy.position())));
+                        let fire_rate =
+                            self.fire_rate * (1.0 + FIRE_RATE_PER_LEVEL * self.level() as f32);
+                        self.time_to_next_shot =
+                            (LanceTower::cooldown() as f32 / fire_rate) as usize;
+                        self.stats.shots_fired += 1;
+                    }
+                }
+                _ => self.time_to_next_shot -= 1,
+            }
+            let bullets = Vec::with_capacity(self.bullets.borrow().len());
+            let bullets = self.bullets.replace(bullets);
+            let (new_bullets, hits) = Bullet::update_all(bullets, enemies, grid, bounds, rng);
+            self.stats.record(&hits);
+            self.bullets.replace(new_bullets);
+        }
+
+        fn look(&self) -> TowerLook {
+            TowerLook::Lance
+        }
+
+        fn spawn(position: Vector) -> Box<dyn Tower> {
+            Box::new(Self {
+                id: TowerId::next(),
+                time_to_next_shot: Self::cooldown(),
+                position,
+                bullets: RefCell::new(vec![]),
+                range: CircularRange {
+                    position,
+                    radius: 220.0,
+                },
+                stats: TowerStats::default(),
+                fire_rate: 1.0,
+                health: 1.0,
+                disabled: false,
+                crit: Crit::BASE,
+            }) as Box<dyn Tower>
+        }
+
+        fn time_until_shot(&self) -> f32 {
+            self.time_to_next_shot as f32
+        }
+
+        fn position(&self) -> Vector {
+            self.position
+        }
+
+        fn radius(&self) -> f32 {
+            12.0
+        }
+
+        fn health(&self) -> f32 {
+            self.health
+        }
+
+        fn damage(&mut self, dmg: f32) {
+            self.health = 0.0f32.max(self.health - dmg);
+        }
+
+        fn heal(&mut self) {
+            self.health = 1.0;
+        }
+
+        fn set_disabled(&mut self, disabled: bool) {
+            self.disabled = disabled;
+        }
+
+        fn crit(&self) -> Option<Crit> {
+            Some(self.crit)
+        }
+
+        fn set_crit(&mut self, crit: Crit) {
+            self.crit = crit;
+        }
+
+        fn range<'b>(&'b self) -> &dyn Range {
+            &self.range as &dyn Range
+        }
+
+        fn range_mut(&mut self) -> &mut dyn Range {
+            &mut self.range
+        }
+
+        fn bullets(&self) -> &RefCell<Vec<Bullet<Alive>>> {
+            &self.bullets
+        }
+
+        fn set_fire_rate(&mut self, multiplier: f32) {
+            self.fire_rate = multiplier;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use rand::{Rng, SeedableRng};
+
+        use super::*;
+
+        /// A sector at the origin facing along +x
+        fn sector(radius: f32, fov: f32) -> SectorRange {
+            SectorRange {
+                position: vec2d![0.0, 0.0],
+                radius,
+                direction: Angle::from_radians(0.0),
+                fov,
+            }
+        }
+
+        /// How much of the line is in the sector, by checking many points along it
+        fn sampled_coverage(range: &SectorRange, a: Vector, b: Vector) -> f32 {
+            const SAMPLES: usize = 4000;
+            let inside = (0..SAMPLES)
+                .filter(|&i| range.contains(a.lerp(b, (i as f32 + 0.5) / SAMPLES as f32)))
+                .count();
+            inside as f32 / SAMPLES as f32
+        }
+
+        fn assert_close(actual: f32, expected: f32) {
+            assert!(
+                (actual - expected).abs() < 1e-3,
+                "expected {}, got {}",
+                expected,
+                actual
+            );
+        }
+
+        #[test]
+        fn crossing_both_edges_and_the_arc() {
+            // crosses the edges at y = ±50 and the arc at y = ±86.6, and only the part
+            // between the edges is inside
+            let range = sector(100.0, PI / 2.0);
+            let coverage = range.coverage(vec2d![50.0, -120.0], vec2d![50.0, 120.0]);
+            assert_close(coverage, 100.0 / 240.0);
+        }
+
+        #[test]
+        fn crossing_both_edges_and_the_arc_of_a_wide_sector() {
+            // only the gap behind the tower, where |y| < 50, and beyond the arc are outside
+            let range = sector(100.0, 1.5 * PI);
+            let coverage = range.coverage(vec2d![-50.0, -120.0], vec2d![-50.0, 120.0]);
+            let inside = 2.0 * (7500.0f32.sqrt() - 50.0);
+            assert_close(coverage, inside / 240.0);
+        }
+
+        #[test]
+        fn both_ends_inside() {
+            let range = sector(100.0, PI / 2.0);
+            assert_close(range.coverage(vec2d![10.0, 0.0], vec2d![50.0, 20.0]), 1.0);
+        }
+
+        #[test]
+        fn one_end_inside() {
+            let range = sector(100.0, PI / 2.0);
+            assert_close(range.coverage(vec2d![50.0, 0.0], vec2d![150.0, 0.0]), 0.5);
+            assert_close(range.coverage(vec2d![50.0, -100.0], vec2d![50.0, 0.0]), 0.5);
+        }
+
+        #[test]
+        fn zero_length() {
+            let range = sector(100.0, PI / 2.0);
+            assert_eq!(range.coverage(vec2d![50.0, 0.0], vec2d![50.0, 0.0]), 0.0);
+            assert_eq!(range.coverage(vec2d![500.0, 0.0], vec2d![500.0, 0.0]), 0.0);
+        }
+
+        #[test]
+        fn coverage_matches_sampling() {
+            let mut rng = StdRng::seed_from_u64(2095);
+            let point = |rng: &mut StdRng| {
+                vec2d![rng.gen_range(-200.0..200.0), rng.gen_range(-200.0..200.0)]
+            };
+            for _ in 0..500 {
+                let range = SectorRange {
+                    position: point(&mut rng),
+                    radius: rng.gen_range(10.0..200.0),
+                    direction: Angle::from_radians(rng.gen_range(-PI..PI)),
+                    fov: rng.gen_range(0.1..2.0 * PI),
+                };
+                let (a, b) = (point(&mut rng), point(&mut rng));
+                let coverage = range.coverage(a, b);
+                assert!((0.0..=1.0).contains(&coverage));
+                let sampled = sampled_coverage(&range, a, b);
+                assert!(
+                    (coverage - sampled).abs() < 0.01,
+                    "coverage {} but sampling found {}, from {:?} to {:?}",
+                    coverage,
+                    sampled,
+                    a,
+                    b
+                );
+            }
+        }
+
+        #[test]
+        fn state_survives_a_respawn() {
+            let mut tower = spawn_sector_tower(vec2d![100.0, 100.0]);
+            tower.rotate(2.0);
+            assert!(Upgrade::Fov.apply(tower.as_mut()));
+            assert!(Upgrade::Radius.apply(tower.as_mut()));
+            tower.damage(0.25);
+            tower.stats_mut().kills = 20;
+            let state = TowerState::of(tower.as_ref());
+
+            let mut copy = spawn_sector_tower(vec2d![100.0, 100.0]);
+            state.apply(copy.as_mut());
+            let copied = TowerState::of(copy.as_ref());
+            assert_close(copied.direction.unwrap(), state.direction.unwrap());
+            assert_eq!(copied.fov, state.fov);
+            assert_eq!(copied.radius, state.radius);
+            assert_eq!(copied.health, state.health);
+            assert_eq!(copy.level(), tower.level());
+        }
+
+        #[test]
+        fn bank_keeps_its_money() {
+            let mut bank = spawn_bank(vec2d![0.0, 0.0]);
+            let mut money = 100;
+            bank.press(0, &mut money);
+            bank.end_round();
+            let state = TowerState::of(bank.as_ref());
+            let mut copy = spawn_bank(vec2d![0.0, 0.0]);
+            state.apply(copy.as_mut());
+            assert_eq!(copy.account(), bank.account());
+            assert_eq!(copy.refund(), 10);
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use std::{f32::consts::PI, ops::*};
+use std::ops::*;
 
-use ggez::mint::Point2;
+use mint::Point2;
+use serde::{Deserialize, Serialize};
 
 #[macro_export]
 macro_rules! vec2d {
@@ -10,7 +11,7 @@ macro_rules! vec2d {
 }
 
 /// Represents a point in 2D space, using 2 f32's for the x and y
-#[derive(Debug, Clone, Default, Copy)]
+#[derive(Debug, Clone, Default, Copy, Serialize, Deserialize)]
 pub struct Vector {
     pub x: f32,
     pub y: f32,
@@ -78,16 +79,6 @@ impl Vector {
         }
     }
 
-    /// returns the signed angle distance between two angles
-    pub fn angle_distnace(angle1: f32, angle2: f32) -> f32 {
-        let diff = (angle2 - angle1 + PI) % (2.0 * PI) - PI;
-        if diff < -PI {
-            diff + 2.0 * PI
-        } else {
-            diff
-        }
-    }
-
     pub fn min(&self, other: Vector) -> Vector {
         vec2d!(self.x.min(other.x), self.y.min(other.y))
     }
@@ -95,6 +86,100 @@ impl Vector {
     pub fn max(&self, other: Vector) -> Vector {
         vec2d!(self.x.max(other.x), self.y.max(other.y))
     }
+
+    /// moves `t` of the way from self to `other`
+    pub fn lerp(&self, other: Vector, t: f32) -> Vector {
+        *self + (other - *self) * t
+    }
+
+    /// shortens the vector to `max` if it's any longer, keeping its direction
+    pub fn clamp_length(&self, max: f32) -> Vector {
+        let sqr_length = self.sqr_length();
+        if sqr_length > max * max {
+            *self * (max / sqr_length.sqrt())
+        } else {
+            *self
+        }
+    }
+
+    pub fn distance(&self, other: Vector) -> f32 {
+        (other - *self).length()
+    }
+
+    pub fn distance_squared(&self, other: Vector) -> f32 {
+        (other - *self).sqr_length()
+    }
+
+    /// bounces the vector off a surface with the given normal, which should be normalised
+    pub fn reflect(&self, normal: Vector) -> Vector {
+        *self - normal * (2.0 * self.dot(normal))
+    }
+
+    /// the z component of the 3D cross product, which is positive
+    /// if `other` is anticlockwise from self
+    pub fn perpendicular_dot(&self, other: Vector) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// checks both components are within `epsilon` of each other
+    pub fn approx_eq(&self, other: Vector, epsilon: f32) -> bool {
+        (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon
+    }
+}
+
+/// Moves, turns and scales points, in that order: scaled first, then rotated, then moved.
+/// Used to place nested menus inside their parents, and to convert window positions
+/// into the world.
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub translation: Vector,
+    /// In radians, anticlockwise
+    pub rotation: f32,
+    pub scale: f32,
+}
+
+impl Transform {
+    pub const fn new(translation: Vector, rotation: f32, scale: f32) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub const fn identity() -> Self {
+        Self::new(Vector::zero(), 0.0, 1.0)
+    }
+
+    pub fn apply(&self, point: Vector) -> Vector {
+        (point * self.scale).rotate(self.rotation) + self.translation
+    }
+
+    /// Applies the transform to a direction or size, which doesn't get moved
+    pub fn apply_vector(&self, vector: Vector) -> Vector {
+        (vector * self.scale).rotate(self.rotation)
+    }
+
+    /// Undoes `apply`, such as turning a position on the screen back into local coordinates
+    pub fn inverse_apply(&self, point: Vector) -> Vector {
+        (point - self.translation).rotate(-self.rotation) / self.scale
+    }
+
+    /// The transform which applies `child` first, and then self.
+    /// `child` is relative to self, like a nested menu is to its parent.
+    pub fn then(&self, child: Transform) -> Transform {
+        Transform {
+            translation: self.apply(child.translation),
+            rotation: self.rotation + child.rotation,
+            scale: self.scale * child.scale,
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
 }
 
 impl Add for Vector {
@@ -141,6 +226,13 @@ impl Mul<f32> for Vector {
     }
 }
 
+impl Mul<Vector> for f32 {
+    type Output = Vector;
+    fn mul(self, rhs: Vector) -> Vector {
+        rhs * self
+    }
+}
+
 impl MulAssign<f32> for Vector {
     fn mul_assign(&mut self, rhs: f32) {
         self.x *= rhs;
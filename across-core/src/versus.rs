@@ -0,0 +1,93 @@
+use crate::enemy::enemy::EnemyKind;
+
+/// A group of enemies which can be bought in the shop and sent to the opponent
+#[derive(Debug, Clone, Copy)]
+pub struct EnemySend {
+    pub kind: EnemyKind,
+    pub count: usize,
+    pub price: u64,
+    /// How much more money the sender gets after every round from then on
+    pub income: u64,
+}
+
+impl EnemySend {
+    /// The text on the shop button, before it's translated
+    pub fn label(&self) -> String {
+        format!(
+            "Send {} {} ({})",
+            self.count,
+            self.kind.plural_name(),
+            self.price
+        )
+    }
+}
+
+/// Everything which can be sent, cheapest first
+pub const SENDS: [EnemySend; 4] = [
+    EnemySend {
+        kind: EnemyKind::Test,
+        count: 3,
+        price: 15,
+        income: 1,
+    },
+    EnemySend {
+        kind: EnemyKind::Brave,
+        count: 3,
+        price: 30,
+        income: 3,
+    },
+    EnemySend {
+        kind: EnemyKind::Carrier,
+        count: 2,
+        price: 60,
+        income: 6,
+    },
+    EnemySend {
+        kind: EnemyKind::Brave,
+        count: 8,
+        price: 70,
+        income: 8,
+    },
+];
+
+/// One player's side of a versus game, where players send enemies to each other
+#[derive(Debug, Clone, Default)]
+pub struct Versus {
+    /// Paid after every round, from the enemies this player has sent
+    income: u64,
+    /// Enemies the opponent has sent, which join the next wave
+    incoming: Vec<EnemyKind>,
+    /// Enemies the opponent sent which are joining this wave, one with each normal enemy
+    spawning: Vec<EnemyKind>,
+}
+
+impl Versus {
+    pub fn income(&self) -> u64 {
+        self.income
+    }
+
+    /// How many enemies the opponent has sent for the next wave
+    pub fn incoming(&self) -> usize {
+        self.incoming.len()
+    }
+
+    /// Keeps the income from sending enemies
+    pub fn sent(&mut self, send: EnemySend) {
+        self.income += send.income;
+    }
+
+    /// Adds enemies from the opponent to the next wave
+    pub fn receive(&mut self, kind: EnemyKind, count: usize) {
+        self.incoming.extend(std::iter::repeat(kind).take(count));
+    }
+
+    /// Starts spawning the enemies the opponent sent
+    pub fn start_wave(&mut self) {
+        self.spawning.append(&mut self.incoming);
+    }
+
+    /// The next enemy from the opponent to spawn, if any are left this wave
+    pub fn next_spawn(&mut self) -> Option<EnemyKind> {
+        self.spawning.pop()
+    }
+}
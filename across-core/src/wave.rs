@@ -0,0 +1,452 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{enemy::enemy::EnemyKind, tr};
+
+/// How many steps to wait between the end of one wave and the start of the next
+const WAVE_BREAK: usize = 300;
+/// How many steps the rush warning is shown for
+const RUSH_WARNING: usize = 120;
+/// The first round which can have a modifier
+const MODIFIER_FROM_ROUND: usize = 3;
+/// The chance of each round from `MODIFIER_FROM_ROUND` on having a modifier
+const MODIFIER_CHANCE: f64 = 0.3;
+
+/// A point in a wave where every enemy left spawns in a rapid burst
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Rush {
+    /// How many enemies spawn normally before the rush starts
+    pub after: usize,
+    /// The number of steps between each enemy during the rush
+    pub interval: usize,
+}
+
+/// Some enemies which spawn at the same moment, in a scheduled wave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Burst {
+    /// How many steps after the burst before it (or the start of the wave) this one spawns.
+    /// A long delay leaves a gap in the wave.
+    pub delay: usize,
+    pub count: usize,
+    /// The kind of every enemy in the burst, or None to pick each from the round's mix
+    pub kind: Option<EnemyKind>,
+}
+
+/// A group of enemies which spawn one after another, or in bursts if it has a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wave {
+    pub count: usize,
+    /// The number of steps between each enemy
+    pub interval: usize,
+    pub rush: Option<Rush>,
+    /// The bursts to spawn in order, instead of one enemy every interval.
+    /// `count` is the total of their counts.
+    #[serde(default)]
+    pub schedule: Vec<Burst>,
+}
+
+impl Wave {
+    pub const fn new(count: usize, interval: usize) -> Self {
+        Self {
+            count,
+            interval,
+            rush: None,
+            schedule: Vec::new(),
+        }
+    }
+
+    /// A wave which spawns its enemies in bursts, at exact steps
+    pub fn scheduled(schedule: Vec<Burst>) -> Self {
+        let mut wave = Self::new(0, 0);
+        for burst in schedule {
+            wave.push_burst(burst);
+        }
+        wave
+    }
+
+    /// Adds a burst to the end of the wave's schedule
+    pub fn push_burst(&mut self, burst: Burst) {
+        self.count += burst.count;
+        self.schedule.push(burst);
+    }
+
+    pub fn is_scheduled(&self) -> bool {
+        !self.schedule.is_empty()
+    }
+
+    pub const fn with_rush(count: usize, interval: usize, rush: Rush) -> Self {
+        Self {
+            count,
+            interval,
+            rush: Some(rush),
+            schedule: Vec::new(),
+        }
+    }
+}
+
+/// Something which changes every enemy in a wave, announced before the wave starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Enemies move faster
+    Fast,
+    /// Enemies slowly heal
+    Regenerating,
+    /// Twice as many enemies, each with half the health
+    Swarming,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 3] = [Modifier::Fast, Modifier::Regenerating, Modifier::Swarming];
+
+    pub fn name(&self) -> String {
+        match self {
+            Modifier::Fast => tr!("Fast wave"),
+            Modifier::Regenerating => tr!("Regenerating wave"),
+            Modifier::Swarming => tr!("Double count, half health"),
+        }
+    }
+
+    /// How many times as fast as normal enemies move
+    pub fn speed(&self) -> f32 {
+        match self {
+            Modifier::Fast => 1.5,
+            _ => 1.0,
+        }
+    }
+
+    /// The health enemies get back each step, normalised [0-1]
+    pub fn regeneration(&self) -> f32 {
+        match self {
+            Modifier::Regenerating => 0.002,
+            _ => 0.0,
+        }
+    }
+
+    /// How many times as many enemies spawn as normal
+    pub fn count(&self) -> usize {
+        match self {
+            Modifier::Swarming => 2,
+            _ => 1,
+        }
+    }
+
+    /// How much health enemies have compared to normal
+    pub fn health(&self) -> f32 {
+        1.0 / self.count() as f32
+    }
+
+    /// How many times the normal reward each kill is worth.
+    /// Swarming waves have twice the kills, so they pay the same for the round overall.
+    pub fn reward(&self) -> f32 {
+        match self {
+            Modifier::Fast | Modifier::Regenerating => 1.5,
+            Modifier::Swarming => 0.5,
+        }
+    }
+
+    /// The wave with as many more enemies as this modifier asks for, spawning as much
+    /// more often (or in bursts as much bigger) so the wave takes as long
+    pub fn apply(&self, wave: Wave) -> Wave {
+        let count = self.count();
+        Wave {
+            count: wave.count * count,
+            interval: (wave.interval / count).max(1),
+            rush: wave.rush.map(|rush| Rush {
+                after: rush.after * count,
+                interval: (rush.interval / count).max(1),
+            }),
+            schedule: wave
+                .schedule
+                .into_iter()
+                .map(|burst| Burst {
+                    count: burst.count * count,
+                    ..burst
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The waves in a normal game, in order
+pub fn default_waves() -> Vec<Wave> {
+    vec![
+        Wave::new(5, 90),
+        Wave::new(8, 75),
+        Wave::with_rush(
+            12,
+            75,
+            Rush {
+                after: 6,
+                interval: 10,
+            },
+        ),
+        Wave::new(15, 60),
+        Wave::with_rush(
+            20,
+            60,
+            Rush {
+                after: 8,
+                interval: 8,
+            },
+        ),
+    ]
+}
+
+/// Which kinds of enemy spawn in each round, and how often.
+/// The weights are given for some rounds, and interpolated for the rounds in between.
+#[derive(Debug, Clone)]
+pub struct EnemyMix {
+    /// Rounds in increasing order, each with a weight for every kind in `EnemyKind::ALL`
+    keyframes: Vec<(usize, [f32; EnemyKind::ALL.len()])>,
+}
+
+impl EnemyMix {
+    /// There must be at least one keyframe, and the rounds must be in increasing order
+    pub fn new(keyframes: Vec<(usize, [f32; EnemyKind::ALL.len()])>) -> Self {
+        assert!(!keyframes.is_empty());
+        assert!(keyframes.windows(2).all(|x| x[0].0 < x[1].0));
+        Self { keyframes }
+    }
+
+    /// The weight of every kind of enemy in a round.
+    /// Rounds before the first keyframe or after the last use that keyframe's weights.
+    pub fn weights(&self, round: usize) -> [f32; EnemyKind::ALL.len()] {
+        let next = self.keyframes.iter().position(|&(x, _)| x >= round);
+        match next {
+            Some(0) => self.keyframes[0].1,
+            None => self.keyframes[self.keyframes.len() - 1].1,
+            Some(i) => {
+                let (start, from) = self.keyframes[i - 1];
+                let (end, to) = self.keyframes[i];
+                let t = (round - start) as f32 / (end - start) as f32;
+                let mut weights = from;
+                for (weight, to) in weights.iter_mut().zip(to) {
+                    *weight += (to - *weight) * t;
+                }
+                weights
+            }
+        }
+    }
+
+    /// Picks the kind of the next enemy to spawn in a round
+    pub fn pick(&self, round: usize, rng: &mut impl Rng) -> EnemyKind {
+        let weights = self.weights(round);
+        let mut choice = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        for (kind, weight) in EnemyKind::ALL.into_iter().zip(weights) {
+            if choice < weight {
+                return kind;
+            }
+            choice -= weight;
+        }
+        EnemyKind::ALL[0]
+    }
+}
+
+impl Default for EnemyMix {
+    /// Only test enemies at first, with brave enemies joining from round 3, carriers
+    /// from round 6, teleporters from round 8 and juggernauts from round 10
+    fn default() -> Self {
+        Self::new(vec![
+            (2, [1.0, 0.0, 0.0, 0.0, 0.0]),
+            (3, [0.8, 0.2, 0.0, 0.0, 0.0]),
+            (5, [0.6, 0.4, 0.0, 0.0, 0.0]),
+            (6, [0.55, 0.35, 0.1, 0.0, 0.0]),
+            (8, [0.5, 0.35, 0.15, 0.0, 0.0]),
+            (10, [0.4, 0.3, 0.2, 0.1, 0.0]),
+            (12, [0.35, 0.3, 0.15, 0.1, 0.1]),
+        ])
+    }
+}
+
+/// What the scheduler wants to happen this step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnEvent {
+    Nothing,
+    Enemy,
+    /// A rush has just started. An enemy should be spawned too.
+    Rush,
+    /// A burst from a scheduled wave, of `count` enemies at once
+    Burst {
+        count: usize,
+        kind: Option<EnemyKind>,
+    },
+    /// Every enemy in a wave has been spawned and killed, and the next wave is starting
+    WaveCleared,
+}
+
+impl SpawnEvent {
+    /// The enemies to spawn this step, one item each.
+    /// Each is the kind to spawn, or None to pick one from the round's mix.
+    pub fn spawns(self) -> impl Iterator<Item = Option<EnemyKind>> {
+        let (count, kind) = match self {
+            SpawnEvent::Enemy | SpawnEvent::Rush => (1, None),
+            SpawnEvent::Burst { count, kind } => (count, kind),
+            SpawnEvent::Nothing | SpawnEvent::WaveCleared => (0, None),
+        };
+        std::iter::repeat(kind).take(count)
+    }
+}
+
+/// Works through the waves, deciding when each enemy spawns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnScheduler {
+    waves: Vec<Wave>,
+    /// The index of the current wave
+    wave: usize,
+    /// How many enemies have been spawned in the current wave
+    spawned: usize,
+    /// The index of the next burst in the current wave's schedule
+    #[serde(default)]
+    burst: usize,
+    /// Steps until the next enemy spawns, or the next wave starts
+    timer: usize,
+    /// Steps left to show the rush warning for
+    warning: usize,
+    /// The modifier on the current wave, if any
+    modifier: Option<Modifier>,
+}
+
+impl SpawnScheduler {
+    pub fn new(waves: Vec<Wave>) -> Self {
+        let mut scheduler = Self {
+            waves,
+            wave: 0,
+            spawned: 0,
+            burst: 0,
+            timer: 0,
+            warning: 0,
+            modifier: None,
+        };
+        scheduler.timer = scheduler.break_before();
+        scheduler
+    }
+
+    /// How many steps to wait before the current wave spawns anything, which includes the
+    /// delay before the first burst of a scheduled wave
+    fn break_before(&self) -> usize {
+        let first = self.waves.get(self.wave).and_then(|x| x.schedule.first());
+        WAVE_BREAK + first.map_or(0, |burst| burst.delay)
+    }
+
+    /// The round number shown to the player, starting at 1
+    pub fn round(&self) -> usize {
+        self.wave.min(self.waves.len().saturating_sub(1)) + 1
+    }
+
+    /// Jumps to the start of a round, as if every round before it had been cleared
+    pub fn skip_to(&mut self, round: usize) {
+        self.wave = round.saturating_sub(1);
+        self.spawned = 0;
+        self.burst = 0;
+        self.timer = self.break_before();
+        self.warning = 0;
+        self.modifier = None;
+    }
+
+    /// The wave being spawned, or the next one to be spawned, before its modifier
+    pub fn current_wave(&self) -> Option<Wave> {
+        self.waves.get(self.wave).cloned()
+    }
+
+    /// The current wave as it actually spawns, with its modifier
+    fn modified_wave(&self) -> Option<Wave> {
+        let wave = self.current_wave()?;
+        Some(match self.modifier {
+            Some(modifier) => modifier.apply(wave),
+            None => wave,
+        })
+    }
+
+    /// The modifier on the wave being spawned, or the next one to be spawned
+    pub fn modifier(&self) -> Option<Modifier> {
+        self.modifier
+    }
+
+    pub fn set_modifier(&mut self, modifier: Option<Modifier>) {
+        self.modifier = modifier;
+    }
+
+    /// Randomly picks whether the current wave has a modifier, and which.
+    /// Early rounds never do.
+    pub fn roll_modifier(&mut self, rng: &mut impl Rng) -> Option<Modifier> {
+        self.modifier = if self.round() >= MODIFIER_FROM_ROUND && rng.gen_bool(MODIFIER_CHANCE) {
+            Some(Modifier::ALL[rng.gen_range(0..Modifier::ALL.len())])
+        } else {
+            None
+        };
+        self.modifier
+    }
+
+    /// Counts every enemy in the current wave as spawned, so the wave ends once the
+    /// enemies already out are gone
+    pub fn finish_wave(&mut self) {
+        if let Some(wave) = self.modified_wave() {
+            self.spawned = wave.count;
+            self.burst = wave.schedule.len();
+        }
+    }
+
+    /// Whether every wave has been spawned
+    pub fn is_finished(&self) -> bool {
+        self.wave >= self.waves.len()
+    }
+
+    fn is_rushing(&self, wave: &Wave) -> bool {
+        wave.rush.map_or(false, |rush| self.spawned >= rush.after)
+    }
+
+    /// How strongly to show the rush warning, from 0 (not at all) to 1
+    pub fn warning(&self) -> f32 {
+        self.warning as f32 / RUSH_WARNING as f32
+    }
+
+    /// Moves forward one step.
+    /// `enemies_alive` is used to wait for a wave to be cleared before starting the next.
+    pub fn update(&mut self, enemies_alive: usize) -> SpawnEvent {
+        self.warning = self.warning.saturating_sub(1);
+        let Some(wave) = self.modified_wave() else {
+            return SpawnEvent::Nothing;
+        };
+
+        if self.spawned >= wave.count {
+            if enemies_alive == 0 {
+                self.wave += 1;
+                self.spawned = 0;
+                self.burst = 0;
+                self.timer = self.break_before();
+                self.modifier = None;
+                return SpawnEvent::WaveCleared;
+            }
+            return SpawnEvent::Nothing;
+        }
+
+        if self.timer > 0 {
+            self.timer -= 1;
+            return SpawnEvent::Nothing;
+        }
+
+        if let Some(&burst) = wave.schedule.get(self.burst) {
+            self.burst += 1;
+            self.spawned += burst.count;
+            self.timer = wave.schedule.get(self.burst).map_or(0, |next| next.delay);
+            return SpawnEvent::Burst {
+                count: burst.count,
+                kind: burst.kind,
+            };
+        }
+
+        let was_rushing = self.is_rushing(&wave);
+        self.spawned += 1;
+        let rushing = self.is_rushing(&wave);
+        self.timer = match wave.rush {
+            Some(rush) if rushing => rush.interval,
+            _ => wave.interval,
+        };
+        if rushing && !was_rushing {
+            self.warning = RUSH_WARNING;
+            SpawnEvent::Rush
+        } else {
+            SpawnEvent::Enemy
+        }
+    }
+}
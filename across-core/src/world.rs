@@ -0,0 +1,261 @@
+use rand::rngs::StdRng;
+
+use crate::{
+    bullet::bullet::{Bullet, Hit},
+    enemy::enemy::Enemy,
+    path::Web,
+    polygon::Polygon,
+    spatial::SpatialHash,
+    tower::tower::{Tower, TowerId},
+    vector::Vector,
+    Alive, Dead,
+};
+
+/// Every entity in a game.
+/// The entities are plain lists, and each step they're updated by running the systems below
+/// in order, rather than by each list being taken out, updated and put back.
+pub struct World {
+    pub enemies: Vec<Enemy<Alive>>,
+    /// Killed enemies, which are kept until their death animation finishes
+    pub dying: Vec<Enemy<Dead>>,
+    /// Bullets which don't belong to any tower
+    pub bullets: Vec<Bullet<Alive>>,
+    pub towers: Vec<Box<dyn Tower>>,
+    /// Towers being repaired, which can't shoot until they're finished
+    pub repairs: Vec<Repair>,
+    /// The damage done by recent hits, which float up from where they landed
+    pub damage_numbers: Vec<DamageNumber>,
+}
+
+/// How many steps damage numbers are shown for
+pub const DAMAGE_NUMBER_STEPS: usize = 45;
+
+/// The damage done by one hit, shown for a moment where it landed
+#[derive(Debug, Clone, Copy)]
+pub struct DamageNumber {
+    pub hit: Hit,
+    /// How many steps it's been shown for
+    pub age: usize,
+}
+
+/// How many steps it takes to repair a tower
+pub const REPAIR_STEPS: usize = 180;
+
+/// A tower being repaired
+#[derive(Debug, Clone, Copy)]
+pub struct Repair {
+    pub tower: TowerId,
+    /// How many steps until it's finished
+    pub remaining: usize,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self {
+            enemies: Vec::new(),
+            dying: Vec::new(),
+            bullets: Vec::new(),
+            towers: Vec::new(),
+            repairs: Vec::new(),
+            damage_numbers: Vec::new(),
+        }
+    }
+
+    /// How many critical hits landed in the last step, for playing a sound
+    pub fn crits_landed(&self) -> usize {
+        self.damage_numbers
+            .iter()
+            .filter(|x| x.age == 0 && x.hit.critical)
+            .count()
+    }
+
+    /// The tower with this ID, unless it's been sold
+    pub fn tower(&self, id: TowerId) -> Option<&dyn Tower> {
+        self.towers
+            .iter()
+            .find(|tower| tower.id() == id)
+            .map(|tower| tower.as_ref())
+    }
+
+    /// Starts repairing a tower, which stops it shooting until it's finished.
+    /// Returns false if it's already being repaired, or doesn't exist.
+    pub fn start_repair(&mut self, id: TowerId) -> bool {
+        let Some(tower) = self.towers.iter_mut().find(|tower| tower.id() == id) else {
+            return false;
+        };
+        if self.repairs.iter().any(|repair| repair.tower == id) {
+            return false;
+        }
+        tower.set_disabled(true);
+        self.repairs.push(Repair {
+            tower: id,
+            remaining: REPAIR_STEPS,
+        });
+        true
+    }
+
+    /// How far through being repaired a tower is [0-1], if it's being repaired
+    pub fn repair_progress(&self, id: TowerId) -> Option<f32> {
+        self.repairs
+            .iter()
+            .find(|repair| repair.tower == id)
+            .map(|repair| 1.0 - repair.remaining as f32 / REPAIR_STEPS as f32)
+    }
+
+    /// Moves every repair on, healing the towers which are finished.
+    /// Repairs of towers which have been destroyed or sold are dropped.
+    pub fn repair_system(&mut self) {
+        let towers = &mut self.towers;
+        self.repairs.retain_mut(|repair| {
+            let Some(tower) = towers.iter_mut().find(|tower| tower.id() == repair.tower) else {
+                return false;
+            };
+            repair.remaining = repair.remaining.saturating_sub(1);
+            if repair.remaining > 0 {
+                return true;
+            }
+            tower.heal();
+            tower.set_disabled(false);
+            false
+        });
+    }
+
+    /// Moves every enemy along its route, unless they're frozen, and moves on the death
+    /// animations.
+    /// Returns how many enemies reached the end.
+    pub fn movement_system(&mut self, web: &mut Web, frozen: bool) -> usize {
+        self.dying.retain_mut(|enemy| enemy.update());
+        if frozen {
+            return 0;
+        }
+        for tower in self.towers.iter() {
+            tower.apply_aura(&mut self.enemies);
+        }
+        let (enemies, escaped) = Enemy::update_all(std::mem::take(&mut self.enemies), web);
+        self.enemies = enemies;
+        self.release_cargo();
+        self.separate();
+        escaped
+    }
+
+    /// Moves the enemies released by others, like a carrier's swarm, in with the rest
+    fn release_cargo(&mut self) {
+        let mut released: Vec<_> = self
+            .enemies
+            .iter_mut()
+            .flat_map(|enemy| enemy.take_cargo())
+            .collect();
+        released.extend(self.dying.iter_mut().flat_map(|enemy| enemy.take_cargo()));
+        self.enemies.extend(released);
+    }
+
+    /// Pushes overlapping enemies apart sideways, so groups on the same route spread out
+    /// rather than stacking on top of each other
+    fn separate(&mut self) {
+        const STRENGTH: f32 = 0.1;
+        let grid = SpatialHash::new(&self.enemies);
+        let pushes: Vec<Vector> = self
+            .enemies
+            .iter()
+            .enumerate()
+            .map(|(i, enemy)| {
+                grid.query(enemy.position(), enemy.radius() * 2.0)
+                    .into_iter()
+                    .filter(|&j| j != i)
+                    .map(|j| {
+                        let other = &self.enemies[j];
+                        let away = enemy.position() - other.position();
+                        let overlap = enemy.radius() + other.radius() - away.length();
+                        if overlap <= 0.0 {
+                            return Vector::zero();
+                        }
+                        // enemies in exactly the same place are split up by their order
+                        let direction = if away.sqr_length() > 0.0 {
+                            away.normalised()
+                        } else if i < j {
+                            Vector::up()
+                        } else {
+                            -Vector::up()
+                        };
+                        direction * overlap * STRENGTH
+                    })
+                    .fold(Vector::zero(), |total, push| total + push)
+            })
+            .collect();
+        for (enemy, push) in self.enemies.iter_mut().zip(pushes) {
+            enemy.nudge(push);
+        }
+    }
+
+    /// Lets enemies which attack towers hit them, and removes any towers destroyed.
+    /// Returns the towers which were destroyed.
+    pub fn siege_system(&mut self) -> Vec<Box<dyn Tower>> {
+        for enemy in self.enemies.iter_mut() {
+            enemy.siege(&mut self.towers);
+        }
+        let (standing, destroyed) = std::mem::take(&mut self.towers)
+            .into_iter()
+            .partition(|tower| tower.health() > 0.0);
+        self.towers = standing;
+        destroyed
+    }
+
+    /// Kills every enemy which can be hit inside a circle.
+    /// Returns how many enemies were killed.
+    pub fn strike(&mut self, position: Vector, radius: f32) -> usize {
+        let (killed, alive): (Vec<_>, Vec<_>) = std::mem::take(&mut self.enemies)
+            .into_iter()
+            .partition(|enemy| enemy.collides(position, radius));
+        self.enemies = alive;
+        let kills = killed.len();
+        self.dying
+            .extend(killed.into_iter().map(|enemy| enemy.kill()));
+        self.release_cargo();
+        kills
+    }
+
+    /// Moves every bullet, lets the towers shoot, and moves every enemy killed into `dying`.
+    /// Returns how many enemies were killed.
+    /// Collisions are all checked against one grid, built at the start of the system.
+    /// `rng` decides which hits are critical.
+    pub fn combat_system(
+        &mut self,
+        bounds: Vector,
+        priority_zone: Option<&Polygon>,
+        obstacles: &[Polygon],
+        rng: &mut StdRng,
+    ) -> usize {
+        let mut grid = SpatialHash::new(&self.enemies);
+        let (bullets, _) = Bullet::update_all(
+            std::mem::take(&mut self.bullets),
+            &mut self.enemies,
+            &mut grid,
+            bounds,
+            rng,
+        );
+        self.bullets = bullets;
+        for tower in self.towers.iter_mut() {
+            tower.update(
+                &mut self.enemies,
+                &mut grid,
+                bounds,
+                priority_zone,
+                obstacles,
+                rng,
+            );
+        }
+        self.damage_numbers.retain_mut(|x| {
+            x.age += 1;
+            x.age < DAMAGE_NUMBER_STEPS
+        });
+        self.damage_numbers
+            .extend(grid.hits().iter().map(|&hit| DamageNumber { hit, age: 0 }));
+        let (alive, killed) = grid.partition(std::mem::take(&mut self.enemies));
+        self.enemies = alive;
+        let kills = killed.len();
+        self.dying
+            .extend(killed.into_iter().map(|enemy| enemy.kill()));
+        self.release_cargo();
+        kills
+    }
+}
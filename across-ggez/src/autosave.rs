@@ -0,0 +1,77 @@
+use ggez::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    registry::TowerDescriptor,
+    storage::{self, Saver},
+};
+
+/// Where the autosave is kept, inside ggez's user config directory
+const AUTOSAVE_PATH: &str = "/autosave.json";
+/// Where the last checkpoint is kept, in the same format as the autosave
+const CHECKPOINT_PATH: &str = "/checkpoint.json";
+
+/// Just enough of a game to carry on from the start of a round, written after every round.
+/// Every few rounds a copy is kept as a checkpoint, to go back to after a game over.
+/// Towers come back with their upgrades, health, kills and any money in them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Autosave {
+    /// The name of the map, as the map itself isn't saved
+    pub map: String,
+    pub seed: u64,
+    /// The round which is next to be played
+    pub round: usize,
+    pub lives: u32,
+    pub money: u64,
+    pub towers: Vec<TowerDescriptor>,
+    /// Whether the game was closed normally after this was saved.
+    /// If it wasn't, the game probably crashed, so the player is offered the save back.
+    pub clean_exit: bool,
+}
+
+impl Autosave {
+    /// Loads the autosave, if there is one and it can be read
+    pub fn load(ctx: &Context) -> Option<Self> {
+        Self::read(ctx, AUTOSAVE_PATH)
+    }
+
+    /// Loads the last checkpoint reached, if there is one and it can be read
+    pub fn load_checkpoint(ctx: &Context) -> Option<Self> {
+        Self::read(ctx, CHECKPOINT_PATH)
+    }
+
+    fn read(ctx: &Context, path: &str) -> Option<Self> {
+        let contents = storage::read(ctx, path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| eprintln!("Couldn't read {}: {}", path, e))
+            .ok()
+    }
+
+    /// Saves over the autosave, which is offered back if the game doesn't close properly
+    pub fn save(&self, ctx: &Context, saver: &mut Saver) {
+        self.write(ctx, saver, AUTOSAVE_PATH, "the game");
+    }
+
+    /// Saves as the checkpoint to go back to after a game over
+    pub fn save_checkpoint(&self, ctx: &Context, saver: &mut Saver) {
+        self.write(ctx, saver, CHECKPOINT_PATH, "the checkpoint");
+    }
+
+    fn write(&self, ctx: &Context, saver: &mut Saver, path: &str, description: &'static str) {
+        match serde_json::to_string(self) {
+            Ok(json) => saver.write(ctx, path, &json, description),
+            Err(e) => eprintln!("Failed to save {}: {}", description, e),
+        }
+    }
+
+    /// Marks the saved game as finished with, so it isn't offered again
+    pub fn mark_clean(ctx: &Context, saver: &mut Saver) {
+        if let Some(save) = Self::load(ctx).filter(|save| !save.clean_exit) {
+            Self {
+                clean_exit: true,
+                ..save
+            }
+            .save(ctx, saver);
+        }
+    }
+}
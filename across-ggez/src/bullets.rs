@@ -0,0 +1,25 @@
+use ggez::Context;
+
+use crate::{
+    bullet::bullet::{Bullet, BulletLook},
+    palette::scheme,
+    renderer::{draw_circle, draw_line, draw_ring},
+    Alive,
+};
+
+/// Draws a bullet in flight
+pub fn draw_bullet(ctx: &mut Context, bullet: &Bullet<Alive>) {
+    match bullet.look() {
+        BulletLook::Ball { position, radius } => {
+            draw_circle(ctx, position, radius, scheme().bullet)
+        }
+        BulletLook::Missile { position, target } => {
+            draw_circle(ctx, position, 7.0, scheme().bullet);
+            // where it's going to land
+            draw_ring(ctx, target, 10.0, 2.0, scheme().bullet);
+        }
+        BulletLook::Streak { tail, head, width } => {
+            draw_line(ctx, tail, head, width, scheme().bullet)
+        }
+    }
+}
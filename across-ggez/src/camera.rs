@@ -0,0 +1,73 @@
+use ggez::graphics::Rect;
+
+use crate::{
+    vec2d,
+    vector::{Transform, Vector},
+};
+
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 4.0;
+
+/// Moves and scales the view of the world, so large maps can be explored
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    /// How far the centre of the view has been moved, in world coordinates
+    offset: Vector,
+    /// Above 1 is zoomed in
+    zoom: f32,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self {
+            offset: Vector::zero(),
+            zoom: 1.0,
+        }
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Applies the camera to the area of the world which would be visible without it
+    pub fn view(&self, base: Rect) -> Rect {
+        let size = vec2d![base.w, base.h] / self.zoom;
+        let centre = vec2d![base.x + base.w / 2.0, base.y + base.h / 2.0] + self.offset;
+        Rect::new(
+            centre.x - size.x / 2.0,
+            centre.y - size.y / 2.0,
+            size.x,
+            size.y,
+        )
+    }
+
+    /// Converts positions in a window of size `window` into the world, when showing `view`
+    pub fn window_to_world(view: Rect, window: Vector) -> Transform {
+        Transform::new(vec2d![view.x, view.y], 0.0, view.w / window.x)
+    }
+
+    /// Moves the view by a distance in world coordinates
+    pub fn pan(&mut self, delta: Vector) {
+        self.offset += delta;
+    }
+
+    /// Zooms in (factor above 1) or out, keeping `focus` at the same place on the screen
+    pub fn zoom_at(&mut self, base: Rect, focus: Vector, factor: f32) {
+        let factor = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM) / self.zoom;
+        let view = self.view(base);
+        let centre = vec2d![view.x + view.w / 2.0, view.y + view.h / 2.0];
+        let new_centre = focus + (centre - focus) / factor;
+        self.offset += new_centre - centre;
+        self.zoom *= factor;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use ggez::{graphics::Color, timer, Context};
+
+use crate::{
+    palette::scheme,
+    path::Web,
+    renderer::{draw_circle, draw_joined_lines, draw_rectangle, draw_ring, draw_text},
+    vec2d,
+    vector::Vector,
+    GameState,
+};
+
+const TEXT_SIZE: f32 = 20.0;
+
+/// Draws performance numbers in the bottom left corner, in window coordinates
+pub fn draw_debug(ctx: &mut Context, state: &GameState, update_time: Duration) {
+    let world = &state.world;
+    let bullets = world.bullets.len()
+        + world
+            .towers
+            .iter()
+            .map(|tower| tower.bullets().borrow().len())
+            .sum::<usize>();
+    let lines = [
+        format!("FPS: {:.0}", timer::fps(ctx)),
+        format!("Update: {:.2}ms", update_time.as_secs_f32() * 1000.0),
+        format!("Enemies: {}", world.enemies.len()),
+        format!("Bullets: {}", bullets),
+        format!("Towers: {}", world.towers.len()),
+        format!(
+            "Pathfinding: {:.2}ms",
+            state.path.pathfind_time().as_secs_f32() * 1000.0
+        ),
+        format!("Route cost: {:.0}", state.path.route_cost()),
+    ];
+    let height = lines.len() as f32 * TEXT_SIZE * 1.2 + 8.0;
+    let position = vec2d![0.0, state.view_size.y - height];
+    draw_rectangle(
+        ctx,
+        position,
+        vec2d![260.0, height],
+        Color::new(0.0, 0.0, 0.0, 0.7),
+    );
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            ctx,
+            line,
+            position + vec2d![4.0, 4.0 + TEXT_SIZE * 1.2 * i as f32],
+            Some(TEXT_SIZE),
+            None,
+            Color::GREEN,
+        );
+    }
+}
+
+/// Colours each connection by how much its weight multiplies its length, from good for
+/// nothing to bad for blocked, and writes its cost halfway along it.
+/// Points the last search looked at are filled in, and points it was still waiting to
+/// look at are ringed. Drawn in world coordinates.
+pub fn draw_pathfinding(ctx: &mut Context, web: &Web) {
+    let pathfinder = web.pathfinder();
+    for connection in 0..pathfinder.connections().len() {
+        let points = web.connection_points(connection);
+        let multiplier = pathfinder.weights()[connection].multiplier();
+        draw_joined_lines(
+            ctx,
+            points.clone(),
+            5.0,
+            scheme().bad_to_good(1.0 / multiplier),
+        );
+        let cost = pathfinder.cost(connection);
+        let text = if cost.is_infinite() {
+            "blocked".to_owned()
+        } else {
+            format!("{:.0}", cost)
+        };
+        draw_text(
+            ctx,
+            &text,
+            points[points.len() / 2],
+            Some(16.0),
+            None,
+            Color::WHITE,
+        );
+    }
+    let positions = web.positions();
+    let search = pathfinder.last_search();
+    for &point in &search.closed {
+        draw_circle(ctx, positions[point], 6.0, Color::new(1.0, 1.0, 1.0, 0.6));
+    }
+    for &point in &search.open {
+        draw_ring(ctx, positions[point], 8.0, 2.0, Color::CYAN);
+    }
+}
@@ -0,0 +1,64 @@
+use ggez::event::KeyCode;
+
+use crate::{enemy::enemy::EnemyKind, vector::Vector, GameState};
+
+/// How much money each press of Ctrl+M gives
+const MONEY_CHEAT: u64 = 100;
+/// The keys which, with Ctrl, spawn each kind of enemy at the cursor,
+/// in the same order as `EnemyKind::ALL`
+const SPAWN_KEYS: [KeyCode; EnemyKind::ALL.len()] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+];
+
+/// The developer keys, added to the help screen in developer mode
+pub const HELP: [&str; 5] = [
+    "Developer: Ctrl+M adds money",
+    "Developer: Ctrl+I toggles invincibility",
+    "Developer: Ctrl+F finishes the round",
+    "Developer: Ctrl+1-5 spawns an enemy at the cursor",
+    "Developer: Ctrl+C shows the pathfinding overlay",
+];
+
+/// Handles a key pressed with Ctrl in developer mode.
+/// `cursor` is the mouse's position in the world.
+/// Returns whether the key was used.
+pub fn handle_key(state: &mut GameState, keycode: KeyCode, cursor: Vector) -> bool {
+    match keycode {
+        KeyCode::M => {
+            state.money += MONEY_CHEAT;
+            state.toasts.push(format!("+{} money", MONEY_CHEAT));
+        }
+        KeyCode::I => {
+            state.invincible = !state.invincible;
+            let message = if state.invincible {
+                "Invincible"
+            } else {
+                "No longer invincible"
+            };
+            state.toasts.push(message.to_owned());
+        }
+        KeyCode::F => {
+            // the round is cleared as normal once the scheduler sees no enemies left
+            state.spawner.finish_wave();
+            let enemies = std::mem::take(&mut state.world.enemies);
+            state
+                .world
+                .dying
+                .extend(enemies.into_iter().map(|enemy| enemy.kill()));
+        }
+        KeyCode::C => state.show_pathfinding = !state.show_pathfinding,
+        _ => {
+            let Some(index) = SPAWN_KEYS.iter().position(|&x| x == keycode) else {
+                return false;
+            };
+            let mut enemy = EnemyKind::ALL[index].spawn(&state.path);
+            enemy.move_nearest(cursor);
+            state.world.enemies.push(enemy);
+        }
+    }
+    true
+}
@@ -0,0 +1,303 @@
+use ggez::{
+    graphics::{Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    Context,
+};
+
+use crate::{
+    animator::Animator,
+    enemy::enemy::{swarm_offsets, Enemy, EnemyLook, DEATH_FRAMES},
+    error::report,
+    motion::reduced_motion,
+    palette::scheme,
+    renderer::{draw_circle, draw_mesh, draw_ring},
+    sprite::{draw_frame, Animation, SpriteSheet},
+    vec2d,
+    vector::Vector,
+    Alive, Dead,
+};
+
+/// How big each enemy's health bar is
+const HEALTH_BAR_SIZE: (f32, f32) = (30.0, 5.0);
+
+/// Each kind of enemy's sprite sheet has its walk animation on the first row and its
+/// death animation on the second. Enemies without their sheet are drawn as circles.
+const TEST_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/test_enemy.png",
+    frame_size: (32.0, 32.0),
+    walk: Animation {
+        row: 0,
+        frames: 4,
+        frame_steps: 8,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+const BRAVE_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/brave_enemy.png",
+    frame_size: (32.0, 32.0),
+    walk: Animation {
+        row: 0,
+        frames: 6,
+        frame_steps: 6,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+
+const CARRIER_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/carrier_enemy.png",
+    frame_size: (32.0, 32.0),
+    walk: Animation {
+        row: 0,
+        frames: 4,
+        frame_steps: 12,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+const SWARM_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/swarm_enemy.png",
+    frame_size: (16.0, 16.0),
+    walk: Animation {
+        row: 0,
+        frames: 4,
+        frame_steps: 4,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+
+const TELEPORTER_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/teleporter_enemy.png",
+    frame_size: (32.0, 32.0),
+    walk: Animation {
+        row: 0,
+        frames: 4,
+        frame_steps: 10,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+
+const JUGGERNAUT_SPRITES: SpriteSheet = SpriteSheet {
+    path: "/sprites/juggernaut_enemy.png",
+    frame_size: (48.0, 48.0),
+    walk: Animation {
+        row: 0,
+        frames: 4,
+        frame_steps: 14,
+        looping: true,
+    },
+    death: Animation {
+        row: 1,
+        frames: 4,
+        frame_steps: DEATH_FRAMES / 4,
+        looping: false,
+    },
+};
+
+/// Draws a living enemy, fading in while it spawns
+pub fn draw_enemy(ctx: &mut Context, enemy: &Enemy<Alive>) {
+    let visibility = if reduced_motion() {
+        1.0
+    } else {
+        enemy.visibility()
+    };
+    draw_look(
+        ctx,
+        enemy.look(),
+        enemy.position(),
+        enemy.radius(),
+        enemy.animator(),
+        visibility,
+    );
+}
+
+/// Draws a killed enemy, fading out while it dies
+pub fn draw_dead_enemy(ctx: &mut Context, enemy: &Enemy<Dead>) {
+    // without the fade the enemy is gone as soon as it's killed
+    if reduced_motion() {
+        return;
+    }
+    draw_look(
+        ctx,
+        enemy.look(),
+        enemy.position(),
+        enemy.radius(),
+        enemy.animator(),
+        enemy.visibility(),
+    );
+}
+
+/// Draws every enemy's health bar in one go, as a single mesh.
+/// Bars are left out for enemies which are still spawning, and for enemies at full
+/// health if `hide_full` is set.
+pub fn draw_health_bars(ctx: &mut Context, enemies: &[Enemy<Alive>], hide_full: bool) {
+    let (width, height) = HEALTH_BAR_SIZE;
+    let mut builder = MeshBuilder::new();
+    let mut empty = true;
+    for enemy in enemies
+        .iter()
+        .filter(|enemy| !enemy.spawning() && !(hide_full && enemy.health() >= 1.0))
+    {
+        let corner = enemy.position() - vec2d![width / 2.0, enemy.radius() + height * 2.0];
+        let health = enemy.health().clamp(0.0, 1.0);
+        let built = builder
+            .rectangle(
+                DrawMode::fill(),
+                Rect::new(corner.x, corner.y, width, height),
+                Color::new(0.0, 0.0, 0.0, 0.6),
+            )
+            .and_then(|builder| {
+                builder.rectangle(
+                    DrawMode::fill(),
+                    Rect::new(corner.x, corner.y, width * health, height),
+                    scheme().bad_to_good(health),
+                )
+            })
+            .map(|_| ());
+        if report(built).is_none() {
+            return;
+        }
+        empty = false;
+    }
+    // a mesh with nothing in it can't be built
+    if empty {
+        return;
+    }
+    draw_mesh(ctx, Ok(&mut builder), DrawParam::default());
+}
+
+/// `colour`, faded to `visibility`
+fn faded(colour: Color, visibility: f32) -> Color {
+    Color {
+        a: visibility,
+        ..colour
+    }
+}
+
+/// Draws a ring around an enemy, for palettes which tell things apart by shape
+fn draw_outline(ctx: &mut Context, position: Vector, radius: f32, visibility: f32) {
+    draw_ring(
+        ctx,
+        position,
+        radius,
+        3.0,
+        faded(scheme().outline, visibility),
+    );
+}
+
+/// Draws an enemy which looks like `look`.
+/// `visibility` is how big and opaque to draw it [0-1], for spawning and dying.
+fn draw_look(
+    ctx: &mut Context,
+    look: EnemyLook,
+    position: Vector,
+    radius: f32,
+    animator: &Animator,
+    visibility: f32,
+) {
+    let (sheet, colour) = match look {
+        EnemyLook::Test => (&TEST_SPRITES, scheme().test_enemy),
+        EnemyLook::Brave => (&BRAVE_SPRITES, scheme().brave_enemy),
+        EnemyLook::Carrier { .. } => (&CARRIER_SPRITES, scheme().carrier_enemy),
+        EnemyLook::Swarm => (&SWARM_SPRITES, scheme().swarm_enemy),
+        EnemyLook::Teleporter { .. } => (&TELEPORTER_SPRITES, scheme().teleporter_enemy),
+        EnemyLook::Juggernaut { .. } => (&JUGGERNAUT_SPRITES, scheme().juggernaut_enemy),
+    };
+    let size = radius * 2.0;
+    let radius = radius * visibility;
+    let mut visibility = visibility;
+    if let EnemyLook::Teleporter {
+        jumped_from,
+        flickering,
+    } = look
+    {
+        // the flash where it left and the flicker before a jump are skipped, as it
+        // only needs to be shown where it is now
+        let animated = !reduced_motion();
+        if let Some((from, fade)) = jumped_from.filter(|_| animated) {
+            let colour = faded(colour, fade * visibility);
+            draw_ring(ctx, from, radius * (2.0 - fade), 2.0, colour);
+            draw_ring(ctx, position, radius * (1.0 + fade), 2.0, colour);
+        }
+        // it flickers just before it jumps
+        if animated && flickering {
+            visibility *= 0.3;
+        }
+    }
+    let sprite = Color::new(1.0, 1.0, 1.0, visibility);
+    if !draw_frame(ctx, animator, sheet, position, size, sprite) {
+        draw_circle(ctx, position, radius, faded(colour, visibility));
+    }
+    if scheme().shapes {
+        draw_outline(ctx, position, radius, visibility);
+        match look {
+            // a dot in the middle, so brave enemies can be told apart by shape
+            EnemyLook::Brave => draw_circle(
+                ctx,
+                position,
+                radius * 0.35,
+                faded(scheme().outline, visibility),
+            ),
+            // a gap in the middle, so teleporters can be told apart by shape
+            EnemyLook::Teleporter { .. } => draw_ring(
+                ctx,
+                position,
+                radius * 0.45,
+                2.0,
+                faded(scheme().outline, visibility),
+            ),
+            _ => (),
+        }
+    }
+    match look {
+        // the swarm is drawn inside it until it's released
+        EnemyLook::Carrier { carrying: true } => {
+            for offset in swarm_offsets() {
+                draw_circle(
+                    ctx,
+                    position + vec2d![offset, 0.0] * visibility,
+                    radius * 0.15,
+                    faded(scheme().swarm_enemy, visibility),
+                );
+            }
+        }
+        // a ring showing how far it can reach, while it's attacking
+        EnemyLook::Juggernaut {
+            besieging: Some(reach),
+        } => draw_ring(
+            ctx,
+            position,
+            radius + reach,
+            2.0,
+            faded(colour, visibility * 0.5),
+        ),
+        _ => (),
+    }
+}
@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+
+use thiserror::Error;
+
+use crate::{
+    map::MapParseError,
+    path::{RouteCreationError, WebCreationError},
+};
+
+/// Anything which can go wrong while the game is running
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("couldn't draw: {0}")]
+    Graphics(#[from] ggez::GameError),
+    #[error("invalid map: {0}")]
+    Map(#[from] MapParseError),
+    #[error("invalid web: {0}")]
+    Web(#[from] WebCreationError),
+    #[error("invalid route: {0}")]
+    Route(#[from] RouteCreationError),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+thread_local! {
+    /// Errors from code which can't return them, like drawing, waiting to be shown
+    static REPORTED: RefCell<Vec<Error>> = RefCell::new(Vec::new());
+}
+
+/// Keeps an error to be shown to the player, for code which can't return it.
+/// Returns the value if there wasn't an error.
+pub fn report<T, E: Into<Error>>(result: std::result::Result<T, E>) -> Option<T> {
+    match result {
+        Ok(x) => Some(x),
+        Err(e) => {
+            REPORTED.with(|x| x.borrow_mut().push(e.into()));
+            None
+        }
+    }
+}
+
+/// Every error reported since this was last called
+pub fn take_reported() -> Vec<Error> {
+    REPORTED.with(|x| std::mem::take(&mut *x.borrow_mut()))
+}
@@ -0,0 +1,107 @@
+use ggez::{graphics::Color, Context};
+
+use crate::{
+    developer,
+    enemy::enemy::ENEMY_KINDS,
+    keybindings::Action,
+    lang::translate,
+    renderer::{draw_rounded_rectangle, draw_text},
+    tr, vec2d,
+    vector::Vector,
+    GameState,
+};
+
+const MARGIN: f32 = 40.0;
+const TITLE_SIZE: f32 = 36.0;
+const LINE_SIZE: f32 = 24.0;
+
+/// The lines shown on each panel, built from the key bindings, the towers which can be
+/// placed, and the kinds of enemy
+fn panels(state: &GameState) -> [(&'static str, Vec<String>); 3] {
+    let mut controls: Vec<String> = Action::ALL
+        .iter()
+        .map(|&action| {
+            format!(
+                "{}: {:?}",
+                translate(action.name()),
+                state.key_bindings.key(action)
+            )
+        })
+        .collect();
+    controls.extend([
+        tr!("Move camera: WASD / arrows"),
+        tr!("Zoom: mouse wheel"),
+        tr!("Tower menu: right mouse / left trigger"),
+        tr!("Place several towers: hold Shift"),
+        tr!("Stop placing: Escape"),
+        tr!("Undo placing a tower: Ctrl+Z"),
+        tr!("Fullscreen: Alt+Enter"),
+        tr!("Close help: F1"),
+    ]);
+    if state.developer {
+        controls.extend(developer::HELP.iter().map(|&line| line.to_owned()));
+    }
+
+    let towers = state
+        .tower_registry
+        .kinds()
+        .iter()
+        .map(|kind| {
+            // spawn one to find out how fast it fires
+            let tower = (kind.spawn)(Vector::default());
+            tr!(
+                "{}: costs {}, fires every {} frames",
+                translate(kind.name),
+                kind.price,
+                tower.time_until_shot()
+            )
+        })
+        .collect();
+
+    let enemies = ENEMY_KINDS
+        .iter()
+        .map(|(name, description)| format!("{}: {}", translate(name), translate(description)))
+        .collect();
+
+    [
+        ("Controls", controls),
+        ("Towers", towers),
+        ("Enemies", enemies),
+    ]
+}
+
+/// Draws the help panels over the whole window, in window coordinates
+pub fn draw_help(ctx: &mut Context, state: &GameState) {
+    let panels = panels(state);
+    let width = (state.view_size.x - MARGIN * (panels.len() + 1) as f32) / panels.len() as f32;
+    let height = state.view_size.y - MARGIN * 2.0;
+    for (i, (title, lines)) in panels.iter().enumerate() {
+        let position = vec2d![MARGIN + (width + MARGIN) * i as f32, MARGIN];
+        draw_rounded_rectangle(
+            ctx,
+            position,
+            vec2d![width, height],
+            12.0,
+            Color::new(0.0, 0.0, 0.0, 0.75),
+            Some((2.0, Color::new(1.0, 1.0, 1.0, 0.5))),
+        );
+        draw_text(
+            ctx,
+            &translate(title),
+            position + vec2d![16.0, 16.0],
+            Some(TITLE_SIZE),
+            None,
+            Color::WHITE,
+        );
+        for (j, line) in lines.iter().enumerate() {
+            draw_text(
+                ctx,
+                line,
+                position + vec2d![16.0, 32.0 + TITLE_SIZE + LINE_SIZE * 1.5 * j as f32],
+                Some(LINE_SIZE),
+                None,
+                Color::WHITE,
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use ggez::{graphics::Color, Context};
+
+use crate::{
+    lang::translate,
+    renderer::{draw_rectangle, draw_ring, draw_text, text_size},
+    tr, vec2d,
+    vector::Vector,
+    GameState,
+};
+
+/// The game object underneath the mouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hovered {
+    Tower(usize),
+    Enemy(usize),
+    Node(usize),
+}
+
+impl Hovered {
+    /// Finds what is underneath a position in the world.
+    /// Towers are checked first, then enemies, then nodes, since that's the order
+    /// they're drawn on top of each other.
+    pub fn find(state: &GameState, position: Vector) -> Option<Hovered> {
+        let within =
+            |centre: Vector, radius: f32| (centre - position).sqr_length() <= radius * radius;
+        if let Some(i) = state
+            .world
+            .towers
+            .iter()
+            .position(|tower| within(tower.position(), tower.radius()))
+        {
+            return Some(Hovered::Tower(i));
+        }
+        if let Some(i) = state
+            .world
+            .enemies
+            .iter()
+            .position(|enemy| within(enemy.position(), enemy.radius()))
+        {
+            return Some(Hovered::Enemy(i));
+        }
+        state
+            .path
+            .positions()
+            .iter()
+            .position(|&node| within(node, state.path.node_radius()))
+            .map(Hovered::Node)
+    }
+
+    /// The position and radius of the highlight ring, if the object still exists
+    fn outline(&self, state: &GameState) -> Option<(Vector, f32)> {
+        match *self {
+            Hovered::Tower(i) => state
+                .world
+                .towers
+                .get(i)
+                .map(|tower| (tower.position(), tower.radius())),
+            Hovered::Enemy(i) => state
+                .world
+                .enemies
+                .get(i)
+                .map(|enemy| (enemy.position(), enemy.radius())),
+            Hovered::Node(i) => state
+                .path
+                .positions()
+                .get(i)
+                .map(|&node| (node, state.path.node_radius())),
+        }
+    }
+
+    /// A short description to show next to the mouse
+    pub fn tooltip(&self, state: &GameState) -> Option<String> {
+        match *self {
+            Hovered::Tower(i) => state.world.towers.get(i).map(|tower| {
+                tr!(
+                    "{} - {} kills, level {}",
+                    translate(tower.name()),
+                    tower.kills(),
+                    tower.level()
+                )
+            }),
+            Hovered::Enemy(i) => state.world.enemies.get(i).map(|enemy| {
+                tr!(
+                    "Enemy - {}% health",
+                    format!("{:.0}", enemy.health() * 100.0)
+                )
+            }),
+            Hovered::Node(i) => Some(tr!("Node {}", i)),
+        }
+    }
+
+    /// Draws a ring around the object. This should be drawn in world coordinates.
+    pub fn draw_highlight(&self, ctx: &mut Context, state: &GameState) {
+        if let Some((position, radius)) = self.outline(state) {
+            draw_ring(
+                ctx,
+                position,
+                radius + 6.0,
+                3.0,
+                Color::new(1.0, 1.0, 1.0, 0.8),
+            );
+        }
+    }
+
+    /// Draws the tooltip next to the mouse. This should be drawn in window coordinates.
+    pub fn draw_tooltip(&self, ctx: &mut Context, state: &GameState, mouse: Vector) {
+        if let Some(text) = self.tooltip(state) {
+            let size = text_size(ctx, &text, Some(24.0));
+            let position = mouse + vec2d![16.0, 16.0];
+            draw_rectangle(
+                ctx,
+                position - vec2d![4.0, 4.0],
+                size + vec2d![8.0, 8.0],
+                Color::new(0.0, 0.0, 0.0, 0.8),
+            );
+            draw_text(ctx, &text, position, Some(24.0), None, Color::WHITE);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use ggez::{input::keyboard::KeyCode, Context};
+
+use crate::storage::{self, Saver};
+
+/// Where the bindings are saved, inside ggez's user config directory
+const KEYBINDINGS_PATH: &str = "/keybindings.txt";
+
+/// Something the player can do by pressing a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Pause,
+    SpeedUp,
+    Sell,
+    CycleTargeting,
+    PreviewRoutes,
+    PathfindingOverlay,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Pause,
+        Action::SpeedUp,
+        Action::Sell,
+        Action::CycleTargeting,
+        Action::PreviewRoutes,
+        Action::PathfindingOverlay,
+    ];
+
+    /// The name shown to the player
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Pause => "Pause",
+            Action::SpeedUp => "Speed up",
+            Action::Sell => "Sell",
+            Action::CycleTargeting => "Cycle targeting",
+            Action::PreviewRoutes => "Preview routes",
+            Action::PathfindingOverlay => "Pathfinding overlay",
+        }
+    }
+
+    fn default_key(&self) -> KeyCode {
+        match self {
+            Action::RotateLeft => KeyCode::A,
+            Action::RotateRight => KeyCode::D,
+            Action::Pause => KeyCode::P,
+            Action::SpeedUp => KeyCode::F,
+            Action::Sell => KeyCode::X,
+            Action::CycleTargeting => KeyCode::T,
+            Action::PreviewRoutes => KeyCode::R,
+            Action::PathfindingOverlay => KeyCode::O,
+        }
+    }
+}
+
+/// Keys which can be bound to an action.
+/// Escape and backspace are left out, since they're used by the menus,
+/// and F1 and F3 since they open the help screen and debug overlay.
+const BINDABLE_KEYS: [KeyCode; 54] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+    KeyCode::F2,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Left,
+    KeyCode::Up,
+    KeyCode::Right,
+    KeyCode::Down,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Return,
+    KeyCode::Delete,
+];
+
+/// Which key triggers each action
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    /// In the same order as `Action::ALL`
+    keys: [KeyCode; Action::ALL.len()],
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.keys[Self::index(action)]
+    }
+
+    /// Finds the action a key is bound to, if any
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|&action| self.key(action) == key)
+    }
+
+    pub fn is_bindable(key: KeyCode) -> bool {
+        BINDABLE_KEYS.contains(&key)
+    }
+
+    /// Binds a key to an action.
+    /// If another action was using that key, the two swap keys so nothing is left unbound.
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        if let Some(other) = self.action(key) {
+            self.keys[Self::index(other)] = self.key(action);
+        }
+        self.keys[Self::index(action)] = key;
+    }
+
+    fn index(action: Action) -> usize {
+        Action::ALL.iter().position(|&x| x == action).unwrap()
+    }
+
+    /// Loads the saved bindings, falling back to the defaults for anything missing
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut bindings = Self::default();
+        let contents = storage::read(ctx, KEYBINDINGS_PATH).unwrap_or_default();
+        for line in contents.lines() {
+            let Some((action, key)) = line.split_once('=') else {
+                continue;
+            };
+            let action = Action::ALL
+                .iter()
+                .copied()
+                .find(|x| format!("{:?}", x) == action.trim());
+            let key = BINDABLE_KEYS
+                .iter()
+                .copied()
+                .find(|x| format!("{:?}", x) == key.trim());
+            if let (Some(action), Some(key)) = (action, key) {
+                bindings.bind(action, key);
+            }
+        }
+        bindings
+    }
+
+    /// Saves the bindings as lines of `Action=Key`
+    pub fn save(&self, ctx: &Context, saver: &mut Saver) {
+        let contents: String = Action::ALL
+            .iter()
+            .map(|&action| format!("{:?}={:?}\n", action, self.key(action)))
+            .collect();
+        saver.write(ctx, KEYBINDINGS_PATH, &contents, "your key bindings");
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL.map(|action| action.default_key()),
+        }
+    }
+}
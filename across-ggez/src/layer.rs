@@ -0,0 +1,78 @@
+use ggez::{
+    graphics::{self, Rect},
+    Context,
+};
+
+use crate::error::report;
+
+/// Where something is drawn, from the bottom up.
+/// Everything on a layer is drawn above everything on the layers before it, whatever order
+/// it was submitted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// Obstacles, and anything else on the ground
+    Background,
+    /// The web, the routes through it, and the priority zone
+    Path,
+    Enemies,
+    Towers,
+    Bullets,
+    /// Effects over everything else in the world, like the airstrike target and the cursor
+    Particles,
+    /// What the player needs to know while playing, like their lives and money
+    Hud,
+    /// Menus and tooltips
+    Ui,
+    /// Anything which covers the whole window, like the help and the toasts
+    Overlay,
+}
+
+impl Layer {
+    /// Whether things on this layer are drawn in world coordinates, rather than window
+    /// coordinates
+    pub fn in_world(&self) -> bool {
+        *self <= Layer::Particles
+    }
+}
+
+/// A draw call waiting for its layer
+type Draw<'a> = Box<dyn FnOnce(&mut Context) + 'a>;
+
+/// Collects everything drawn in a frame, so it can be drawn layer by layer
+#[derive(Default)]
+pub struct RenderQueue<'a> {
+    draws: Vec<(Layer, Draw<'a>)>,
+}
+
+impl<'a> RenderQueue<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds something to draw on a layer.
+    /// Things on the same layer are drawn in the order they were submitted.
+    pub fn submit(&mut self, layer: Layer, draw: impl FnOnce(&mut Context) + 'a) {
+        self.draws.push((layer, Box::new(draw)));
+    }
+
+    /// Draws everything, from the bottom layer up, switching between world and window
+    /// coordinates as needed. Window coordinates are left set afterwards.
+    pub fn flush(mut self, ctx: &mut Context, world_view: Rect, ui_view: Rect) {
+        // this sort is stable, so each layer keeps its order
+        self.draws.sort_by_key(|(layer, _)| *layer);
+        let mut in_world = None;
+        for (layer, draw) in self.draws {
+            if in_world != Some(layer.in_world()) {
+                in_world = Some(layer.in_world());
+                let view = if layer.in_world() {
+                    world_view
+                } else {
+                    ui_view
+                };
+                report(graphics::set_screen_coordinates(ctx, view));
+            }
+            draw(ctx);
+        }
+        report(graphics::set_screen_coordinates(ctx, ui_view));
+    }
+}
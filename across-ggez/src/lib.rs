@@ -0,0 +1,2307 @@
+pub mod autosave;
+pub mod bullets;
+pub mod camera;
+pub mod debug;
+pub mod developer;
+pub mod enemies;
+pub mod error;
+pub mod help;
+pub mod hover;
+pub mod keybindings;
+pub mod layer;
+pub mod motion;
+pub mod palette;
+pub mod panel;
+pub mod profile;
+pub mod radial;
+pub mod renderer;
+pub mod scene;
+pub mod scenery;
+pub mod sprite;
+pub mod stats;
+pub mod storage;
+pub mod summary;
+pub mod toast;
+pub mod towers;
+pub mod ui;
+
+// the game itself lives in the core crate, and is re-exported so the front end can use
+// it as if it were part of this one
+pub use across_core::{
+    ability, angle, animator, autoplay, build, bullet, cli, enemy, estimate, grid, kill_reward,
+    lang, map, net, path, pathfind, polygon, registry, rng, shop, spatial, theme, tower, tr, vec2d,
+    vector, versus, wave, world, Alive, Dead, Updated, SCREEN_HEIGHT, SCREEN_WIDTH, STARTING_MONEY,
+};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use ability::{Abilities, Ability, AIRSTRIKE_RADIUS, EMERGENCY_CASH};
+use autosave::Autosave;
+use camera::Camera;
+use cli::{Difficulty, LaunchOptions};
+use error::report;
+use estimate::{Simulation, SimulationSetup, WinEstimator};
+use ggez::audio;
+use ggez::conf::Conf;
+use ggez::event;
+use ggez::graphics::{self, get_window_color_format, Color, Rect};
+use ggez::input::gamepad::GamepadId;
+use ggez::input::keyboard::{self, KeyCode, KeyMods};
+use ggez::input::mouse;
+use ggez::{filesystem, Context, GameError, GameResult};
+
+use hover::Hovered;
+use keybindings::{Action, KeyBindings};
+use lang::{translate, Language};
+use layer::{Layer, RenderQueue};
+use map::Map;
+use net::{Message, Session};
+use palette::Palette;
+
+use path::Web;
+use polygon::Polygon;
+use profile::Profile;
+use radial::{RadialMenu, RadialOption};
+use rand::Rng;
+use registry::{TowerDescriptor, TowerRegistry};
+use renderer::{draw_rectangle, draw_star, draw_text, UI_SCALE_RANGE};
+use rng::{RunRng, Stream};
+use scene::{Input, Scene};
+use shop::{RoundPhase, Shop, ShopItem};
+use storage::Saver;
+use summary::RoundSummary;
+use toast::Toasts;
+use tower::tower::{repair_price, spawn_tower, TestTower, Tower, Upgrade};
+use towers::{draw_miniature, RangeStyle};
+use ui::{Anchor, Button, Checkbox, DragButton, Menu, Price, Slider, TextInput, UIElement};
+use vector::*;
+use versus::{Versus, SENDS};
+use wave::{EnemyMix, SpawnScheduler};
+use world::World;
+
+const GAME_MENU_INDEX: usize = 0;
+const MAIN_MENU_INDEX: usize = 1;
+const SETTINGS_MENU_INDEX: usize = 2;
+const STATS_MENU_INDEX: usize = 3;
+const SHOP_MENU_INDEX: usize = 4;
+const SPECTATE_MENU_INDEX: usize = 5;
+
+/// How many simulation steps are run each second at normal speed, whatever the frame rate
+const STEPS_PER_SECOND: f32 = 60.0;
+/// Frames longer than this, in seconds, only run this much of the simulation,
+/// so a long pause like dragging the window doesn't make the game jump ahead
+const MAX_FRAME_TIME: f32 = 0.25;
+/// Where ggez looks for the window config when the game starts
+const CONFIG_PATH: &str = "/conf.toml";
+
+/// How far the rotate keys turn the selected tower, in radians
+const ROTATE_STEP: f32 = std::f32::consts::PI / 12.0;
+
+/// How much is cut off each side of a corner when routes are smoothed,
+/// which is twice the radius of an enemy
+const CORNER_RADIUS: f32 = 30.0;
+
+/// The money given for a life, from the shop
+const CASH_IN_REWARD: u64 = 40;
+/// How long after placing a tower it can be undone for a full refund
+const UNDO_WINDOW: Duration = Duration::from_secs(5);
+/// How many rounds apart checkpoints are saved
+const CHECKPOINT_ROUNDS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    MainMenu,
+    Play,
+    Settings,
+    Stats,
+    /// The shop between rounds, drawn over the paused game
+    Shop,
+    /// Watching a simulation of the next wave, without playing
+    Spectate,
+}
+
+impl GameMode {
+    /// The index into `MainState::menus` of the menu shown in this mode
+    pub fn menu_index(&self) -> usize {
+        match self {
+            GameMode::MainMenu => MAIN_MENU_INDEX,
+            GameMode::Play => GAME_MENU_INDEX,
+            GameMode::Settings => SETTINGS_MENU_INDEX,
+            GameMode::Stats => STATS_MENU_INDEX,
+            GameMode::Shop => SHOP_MENU_INDEX,
+            GameMode::Spectate => SPECTATE_MENU_INDEX,
+        }
+    }
+}
+
+/// Options the player can change from the settings screen
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    /// Normalised [0-1]
+    volume: f32,
+    /// How many simulation steps are run per frame
+    game_speed: f32,
+    /// Whether the grey connections of the web are drawn, or just the route
+    show_web: bool,
+    accessibility: Accessibility,
+    /// Whether each tower's stats are written to a file when a game ends
+    export_stats: bool,
+    /// Whether to show the estimated chance of surviving the next round
+    show_win_chance: bool,
+    /// Whether enemies round off the corners of their routes
+    smooth_routes: bool,
+    language: Language,
+    /// Whether frames wait for the display, which only changes when the game next starts
+    vsync: bool,
+    limit_frame_rate: bool,
+    /// The most frames drawn each second, if `limit_frame_rate` is on
+    frame_limit: f32,
+    /// Whether enemies which haven't been hurt are drawn without a health bar
+    hide_full_health_bars: bool,
+    /// How the ranges of placed towers are drawn
+    range_style: RangeStyle,
+    /// How much bigger menus and text are drawn, within `UI_SCALE_RANGE`.
+    /// It's only applied when leaving the settings, so the slider doesn't move while
+    /// it's being dragged.
+    ui_scale: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            game_speed: 1.0,
+            show_web: true,
+            accessibility: Accessibility::default(),
+            export_stats: false,
+            show_win_chance: false,
+            smooth_routes: false,
+            language: Language::default(),
+            ui_scale: 1.0,
+            vsync: true,
+            limit_frame_rate: false,
+            frame_limit: 60.0,
+            hide_full_health_bars: false,
+            range_style: RangeStyle::default(),
+        }
+    }
+}
+
+/// Settings which make the game easier to play for some players.
+/// Anything which draws should follow these, through `palette::scheme` and
+/// `motion::reduced_motion`, which are kept in step with them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Accessibility {
+    /// Screen shake, particles, animated lines and tweens should be skipped,
+    /// jumping straight to their final state instead
+    reduced_motion: bool,
+    /// The colours everything is drawn in
+    palette: Palette,
+}
+
+/// The last tower the player placed, which can be undone until it shoots or `UNDO_WINDOW`
+/// has passed
+struct Placement {
+    position: Vector,
+    price: u64,
+    placed: Instant,
+}
+
+/// This stores the state of the game
+/// and can be manipulated by menus
+pub struct GameState {
+    path: Web,
+    world: World,
+    hover_position: Option<Vector>,
+    mode: GameMode,
+    /// The name scores are saved under
+    player_name: String,
+    /// The player's stats across every game, saved when a game ends
+    profile: Profile,
+    /// The map being played, which the web is built from
+    map: Map,
+    difficulty: Difficulty,
+    /// Notifications for the player, which can be added to from anywhere
+    toasts: Toasts,
+    /// The area painted by the player which towers prefer to shoot into
+    priority_zone: Option<Polygon>,
+    settings: Settings,
+    /// Fractional simulation steps carried over between frames, so non-integer game speeds work
+    pending_steps: f32,
+    /// The area of the world which is currently visible in the window
+    world_view: Rect,
+    /// The size of the window
+    view_size: Vector,
+    /// The index of the tower the player last clicked on
+    selected_tower: Option<usize>,
+    /// Whatever is underneath the mouse, updated every frame
+    hovered: Option<Hovered>,
+    key_bindings: KeyBindings,
+    /// The action waiting for the player to press a key to bind it to
+    rebinding: Option<Action>,
+    /// How many more enemies can reach the end of the route before the game is lost
+    lives: u32,
+    /// Spent on towers and in the shop, and earned by killing enemies
+    money: u64,
+    phase: RoundPhase,
+    shop: Shop,
+    /// Whether the towers are shooting faster this round, from the shop
+    overcharged: bool,
+    /// How the current round is going so far
+    round_summary: RoundSummary,
+    /// How the last round went, shown in the shop
+    last_summary: Option<RoundSummary>,
+    /// A game which didn't close properly, which the player can carry on with
+    interrupted: Option<Autosave>,
+    /// The game as it was at the last checkpoint, which the player can go back to
+    checkpoint: Option<Autosave>,
+    /// Saves files in the background
+    saver: Saver,
+    /// A co-op game over the local network, if one is being played
+    session: Option<Session>,
+    /// Where to join a game from, as typed in the main menu
+    join_address: String,
+    /// Enemies sent and received in a versus game
+    versus: Versus,
+    /// Whether the player has asked for the map to be exported, which is done next update
+    exporting_map: bool,
+    /// Whether the player has picked a new language, which is saved next update
+    language_changed: bool,
+    /// Whether the player has turned vsync on or off, which is saved next update
+    vsync_changed: bool,
+    abilities: Abilities,
+    /// The ability waiting for the player to click where to use it
+    targeting: Option<Ability>,
+    /// Decides when enemies spawn
+    spawner: SpawnScheduler,
+    /// Decides which kind of enemy spawns
+    enemy_mix: EnemyMix,
+    win_estimator: WinEstimator,
+    /// The simulation being watched in spectator mode
+    spectating: Option<Simulation>,
+    /// How many simulation steps are run each frame while spectating
+    spectate_speed: f32,
+    /// Simulation steps which are due but haven't been run yet
+    spectate_steps: f32,
+    /// Whether the help screen is open, which pauses the game
+    show_help: bool,
+    /// Whether the performance numbers are shown
+    show_debug: bool,
+    /// Whether the route each kind of enemy will take is drawn
+    show_route_previews: bool,
+    /// Whether the game was started with `--developer`, which enables cheats
+    developer: bool,
+    /// A developer cheat which stops escaping enemies taking lives
+    invincible: bool,
+    /// Whether each connection's pathfinding cost, and the points the last search looked
+    /// at, are drawn over the map
+    show_pathfinding: bool,
+    /// Everything random in a run comes from here, so a run can be replayed
+    /// by starting a new game with the same seed
+    rng: RunRng,
+    /// A seed typed in on the main menu, used for new games instead of a random one
+    chosen_seed: Option<u64>,
+    /// The seed of the last game which ended, shown on the main menu
+    previous_seed: Option<u64>,
+    camera: Camera,
+    /// Whether the middle mouse button is held down to drag the camera
+    panning: bool,
+    /// Every kind of tower which can be bought
+    tower_registry: TowerRegistry,
+    /// Held open with the right mouse button or left trigger to pick a tower
+    radial_menu: RadialMenu,
+    /// The tower picked from the radial menu, waiting to be placed
+    placing: Option<fn(Vector) -> Box<dyn Tower>>,
+    /// The last tower placed, which can be undone with Ctrl+Z
+    last_placement: Option<Placement>,
+    /// The position of the controller's left stick, with up being negative y like the screen
+    stick: Vector,
+    /// Whether the last tower was picked with a controller, so the stick moves the placement cursor
+    using_controller: bool,
+    /// Whether shift is held, which keeps a tower in hand after placing it
+    shift_held: bool,
+    /// The last error shown, so an error which happens every frame is only shown once
+    last_error: Option<String>,
+}
+
+impl GameState {
+    /// Initialises the game
+    pub fn new() -> error::Result<Self> {
+        Self::with_map(Map::default(), Difficulty::default())
+    }
+
+    /// Initialises the game on a map, or fails if the map doesn't build a valid web
+    pub fn with_map(map: Map, difficulty: Difficulty) -> error::Result<Self> {
+        let seed = rand::random();
+        let tower_registry = TowerRegistry::default();
+        let path = map.build_web()?;
+        let waves = map.waves();
+
+        // one enemy at the beginning of the route
+        // no bullets
+        // one web
+        // no towers
+        Ok(Self {
+            world: World::new(),
+            path,
+            hover_position: None,
+            mode: GameMode::MainMenu,
+            player_name: String::new(),
+            profile: Profile::default(),
+            map,
+            difficulty,
+            toasts: Toasts::default(),
+            priority_zone: None,
+            settings: Settings::default(),
+            pending_steps: 0.0,
+            world_view: Rect::new(0.0, 0.0, SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32),
+            view_size: vec2d![SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
+            selected_tower: None,
+            hovered: None,
+            key_bindings: KeyBindings::default(),
+            rebinding: None,
+            lives: difficulty.lives(),
+            money: STARTING_MONEY,
+            phase: RoundPhase::Fighting,
+            shop: Shop::default(),
+            overcharged: false,
+            round_summary: RoundSummary::default(),
+            last_summary: None,
+            interrupted: None,
+            checkpoint: None,
+            saver: Saver::default(),
+            session: None,
+            join_address: String::new(),
+            versus: Versus::default(),
+            exporting_map: false,
+            language_changed: false,
+            vsync_changed: false,
+            abilities: Abilities::default(),
+            targeting: None,
+            spawner: SpawnScheduler::new(waves),
+            enemy_mix: EnemyMix::default(),
+            win_estimator: WinEstimator::default(),
+            spectating: None,
+            spectate_speed: 1.0,
+            spectate_steps: 0.0,
+            show_help: false,
+            show_debug: false,
+            show_route_previews: false,
+            developer: false,
+            invincible: false,
+            show_pathfinding: false,
+            rng: RunRng::new(seed),
+            chosen_seed: None,
+            previous_seed: None,
+            camera: Camera::new(),
+            panning: false,
+            radial_menu: RadialMenu::new(
+                tower_registry
+                    .kinds()
+                    .iter()
+                    .map(|kind| RadialOption {
+                        name: kind.name,
+                        spawn: kind.spawn,
+                    })
+                    .collect(),
+            ),
+            tower_registry,
+            placing: None,
+            last_placement: None,
+            stick: Vector::zero(),
+            using_controller: false,
+            shift_held: false,
+            last_error: None,
+        })
+    }
+
+    /// Buys and places a tower, unless it can't be afforded or it would leave enemies with
+    /// no route at all.
+    /// Returns whether the tower was placed.
+    /// In co-op, the host is asked to place it instead.
+    pub fn place_tower(&mut self, mut tower: Box<dyn Tower>) -> bool {
+        if let Some(session) = self.coop_session().filter(|x| !x.is_host()) {
+            session.send(&Message::Place {
+                name: tower.name().to_owned(),
+                position: tower.position(),
+            });
+            return true;
+        }
+        if let Some(reason) = self.map.build.refusal(tower.as_ref(), &self.world.towers) {
+            self.toasts.push(translate(reason));
+            return false;
+        }
+        // on grid maps towers fill whole tiles, so they're moved to the middle of theirs
+        if let Some(grid) = self.path.grid() {
+            let Some(tile) = grid.tile_at(tower.position()) else {
+                self.toasts.push(tr!("Towers have to be built on the grid"));
+                return false;
+            };
+            if grid.is_solid(tile) {
+                self.toasts.push(tr!("There's already a tower there"));
+                return false;
+            }
+            let centred = TowerDescriptor {
+                position: grid.centre(tile),
+                ..TowerDescriptor::of(tower.as_ref())
+            };
+            if let Some(centred) = self.tower_registry.spawn(&centred) {
+                tower = centred;
+            }
+        }
+        let price = tower.price();
+        if price > self.money {
+            self.toasts.push(tr!("Not enough money"));
+            return false;
+        }
+        let placed_position = tower.position();
+        let placed = Message::Place {
+            name: tower.name().to_owned(),
+            position: placed_position,
+        };
+        if self.overcharged {
+            tower.set_fire_rate(2.0);
+        }
+        self.world.towers.push(tower);
+        if !self.path.recalculate_weights(&self.world.towers) {
+            self.world.towers.pop();
+            self.toasts.push(tr!("That would block every route"));
+            return false;
+        }
+        self.money -= price;
+        if let Some(session) = self.coop_session() {
+            session.send(&placed);
+        } else {
+            // placements are shared in co-op, so they can't be undone by one player
+            self.last_placement = Some(Placement {
+                position: placed_position,
+                price,
+                placed: Instant::now(),
+            });
+        }
+        true
+    }
+
+    /// Takes back the last tower placed for a full refund, as long as it was placed
+    /// within `UNDO_WINDOW` and hasn't shot yet
+    pub fn undo_placement(&mut self) {
+        if self.host_only() {
+            return;
+        }
+        let Some(placement) = self.last_placement.take() else {
+            self.toasts.push(tr!("Nothing to undo"));
+            return;
+        };
+        let Some(index) = self
+            .world
+            .towers
+            .iter()
+            .position(|x| x.position().approx_eq(placement.position, 0.01))
+            .filter(|&index| {
+                placement.placed.elapsed() <= UNDO_WINDOW
+                    && self.world.towers[index].stats().shots_fired == 0
+            })
+        else {
+            self.toasts.push(tr!("It's too late to undo that"));
+            return;
+        };
+        let tower = self.world.towers.remove(index);
+        self.selected_tower = None;
+        self.money += placement.price + tower.refund();
+        // without the tower, the weights go back to how they were before it was placed
+        if !self.path.recalculate_weights(&self.world.towers) {
+            self.toasts.push(tr!(
+                "No route left after undoing a placement, keeping the old one"
+            ));
+        }
+        self.toasts.push(tr!("Placement undone"));
+    }
+
+    /// Sells a tower for half what it cost.
+    /// In co-op, the host is asked to sell it instead.
+    pub fn sell_tower(&mut self, index: usize) {
+        let Some(position) = self.world.towers.get(index).map(|x| x.position()) else {
+            return;
+        };
+        if let Some(session) = self.coop_session() {
+            session.send(&Message::Sell { position });
+            if !session.is_host() {
+                return;
+            }
+        }
+        let tower = self.world.towers.remove(index);
+        self.money += tower.price() / 2 + tower.refund();
+        if !self.path.recalculate_weights(&self.world.towers) {
+            self.toasts.push(tr!(
+                "No route left after removing a tower, keeping the old one"
+            ));
+        }
+    }
+
+    /// Finds new routes after enemies have destroyed some towers, and gives back what
+    /// they were holding
+    pub fn towers_destroyed(&mut self, destroyed: Vec<Box<dyn Tower>>) {
+        self.selected_tower = None;
+        self.toasts.push(tr!("A tower was destroyed"));
+        self.money += destroyed.iter().map(|tower| tower.refund()).sum::<u64>();
+        if !self.path.recalculate_weights(&self.world.towers) {
+            self.toasts.push(tr!(
+                "No route left after a tower was destroyed, keeping the old one"
+            ));
+        }
+    }
+
+    /// The co-op game being played, if there is one
+    fn coop_session(&mut self) -> Option<&mut Session> {
+        self.session.as_mut().filter(|x| !x.is_versus())
+    }
+
+    /// Whether this player has joined someone else's co-op game
+    pub fn is_client(&self) -> bool {
+        self.session
+            .as_ref()
+            .is_some_and(|x| !x.is_host() && !x.is_versus())
+    }
+
+    /// Whether something has to be left to the host, as it isn't shared with the other
+    /// player yet and would put the two games out of step. Tells the player if so.
+    fn host_only(&mut self) -> bool {
+        let client = self.is_client();
+        if client {
+            self.toasts.push(tr!("Only the host can do that in co-op"));
+        }
+        client
+    }
+
+    /// Starts a co-op or versus game, which another player can join over the local network
+    pub fn host_game(&mut self, versus: bool) {
+        match Session::host(versus) {
+            Ok(session) => {
+                self.session = Some(session);
+                self.toasts
+                    .push(tr!("Waiting for a player to join on port {}", net::PORT));
+            }
+            Err(e) => {
+                eprintln!("Failed to host: {}", e);
+                self.toasts.push(tr!("Couldn't host a game"));
+            }
+        }
+    }
+
+    /// Joins the game hosted at the address typed in
+    pub fn join_game(&mut self) {
+        match Session::join(self.join_address.trim()) {
+            Ok(session) => {
+                self.session = Some(session);
+                self.toasts.push(tr!("Joined - waiting for the host"));
+            }
+            Err(e) => {
+                eprintln!("Failed to join {}: {}", self.join_address, e);
+                self.toasts.push(tr!("Couldn't join that game"));
+            }
+        }
+    }
+
+    /// Lets in a player waiting to join, and does what the other player asked for.
+    /// The host runs the game, so players who join only keep their towers, money, lives
+    /// and rounds in step with the host, and leave anything else to the host.
+    pub fn update_session(&mut self) {
+        let Some(session) = &mut self.session else {
+            return;
+        };
+        if session.accept() {
+            self.toasts.push(if session.is_versus() {
+                tr!("A player joined - starting a versus game")
+            } else {
+                tr!("A player joined - starting a co-op game")
+            });
+            self.restart(self.rng.seed());
+        }
+        let Some(session) = &mut self.session else {
+            return;
+        };
+        let host = session.is_host();
+        for message in session.receive() {
+            match message {
+                Message::Welcome { seed, map, .. } if map == self.map.name => self.restart(seed),
+                Message::Welcome { .. } => {
+                    self.toasts.push(tr!("The host is playing a different map"));
+                    self.session = None;
+                    return;
+                }
+                Message::Place { name, position } => {
+                    let Some(kind) = self.tower_registry.kinds().iter().find(|x| x.name == name)
+                    else {
+                        continue;
+                    };
+                    let tower = (kind.spawn)(position);
+                    if host {
+                        self.place_tower(tower);
+                    } else {
+                        self.world.towers.push(tower);
+                        self.path.recalculate_weights(&self.world.towers);
+                    }
+                }
+                Message::Sell { position } => {
+                    let Some(index) = self
+                        .world
+                        .towers
+                        .iter()
+                        .position(|x| x.position().approx_eq(position, 0.01))
+                    else {
+                        continue;
+                    };
+                    if host {
+                        self.sell_tower(index);
+                    } else {
+                        self.world.towers.remove(index);
+                        self.path.recalculate_weights(&self.world.towers);
+                    }
+                }
+                Message::Shared { money, lives } if !host => {
+                    self.money = money;
+                    self.lives = lives;
+                }
+                Message::Shared { .. } => (),
+                Message::StartRound { round } if !host => {
+                    if round != self.spawner.round() {
+                        self.spawner.skip_to(round);
+                    }
+                    self.begin_round();
+                }
+                Message::StartRound { .. } => (),
+                Message::Send { kind, count } => {
+                    self.versus.receive(kind, count);
+                    self.toasts.push(tr!(
+                        "Your opponent sent {} {}",
+                        count,
+                        translate(kind.plural_name())
+                    ));
+                }
+            }
+        }
+        if let Some(session) = self.session.as_mut().filter(|x| host && !x.is_versus()) {
+            session.share(self.money, self.lives);
+        }
+    }
+
+    /// Buys an upgrade for the selected tower, if it can be upgraded any further
+    pub fn upgrade_selected(&mut self, upgrade: Upgrade) {
+        if self.host_only() {
+            return;
+        }
+        let Some(tower) = self
+            .selected_tower
+            .and_then(|index| self.world.towers.get_mut(index))
+        else {
+            self.toasts.push(tr!("Select a tower to upgrade first"));
+            return;
+        };
+        if upgrade.price() > self.money {
+            self.toasts.push(tr!("Not enough money"));
+            return;
+        }
+        if !upgrade.apply(tower.as_mut()) {
+            self.toasts
+                .push(tr!("That tower can't be upgraded like that"));
+            return;
+        }
+        self.money -= upgrade.price();
+        // what the tower can see has changed, so the route might too
+        if !self.path.recalculate_weights(&self.world.towers) {
+            self.toasts.push(tr!(
+                "No route left after upgrading a tower, keeping the old one"
+            ));
+        }
+    }
+
+    /// Presses a button in the selected tower's panel, given its index
+    pub fn press_panel_button(&mut self, button: usize) {
+        if self.host_only() {
+            return;
+        }
+        let Some(tower) = self
+            .selected_tower
+            .and_then(|index| self.world.towers.get_mut(index))
+        else {
+            return;
+        };
+        if let Some(message) = tower.press(button, &mut self.money) {
+            self.toasts.push(message);
+        }
+    }
+
+    /// Pays to repair the selected tower, which can't shoot until it's finished
+    pub fn repair_selected(&mut self) {
+        if self.host_only() {
+            return;
+        }
+        let Some(tower) = self
+            .selected_tower
+            .and_then(|index| self.world.towers.get(index))
+        else {
+            self.toasts.push(tr!("Select a tower to repair first"));
+            return;
+        };
+        if tower.health() >= 1.0 {
+            self.toasts.push(tr!("That tower isn't damaged"));
+            return;
+        }
+        let (id, price) = (tower.id(), repair_price(tower.as_ref()));
+        if price > self.money {
+            self.toasts.push(tr!("Not enough money"));
+            return;
+        }
+        if !self.world.start_repair(id) {
+            self.toasts
+                .push(tr!("That tower is already being repaired"));
+            return;
+        }
+        self.money -= price;
+    }
+
+    /// Uses an ability from the HUD, if it has recharged.
+    /// Abilities which need a target wait for the player to click on the map.
+    pub fn activate(&mut self, ability: Ability) {
+        if self.host_only() {
+            return;
+        }
+        if !self.abilities.is_ready(ability) {
+            self.toasts
+                .push(tr!("{} isn't ready yet", translate(ability.name())));
+            return;
+        }
+        match ability {
+            Ability::Airstrike => {
+                // clicking the button again cancels it
+                self.targeting = match self.targeting {
+                    Some(Ability::Airstrike) => None,
+                    _ => Some(Ability::Airstrike),
+                };
+                return;
+            }
+            Ability::Freeze => (),
+            Ability::EmergencyCash => self.money += EMERGENCY_CASH,
+        }
+        self.abilities.use_ability(ability);
+    }
+
+    /// Switches to another mode, letting the old scene clean up and the new one set up
+    pub fn set_mode(&mut self, mode: GameMode) {
+        if mode == self.mode {
+            return;
+        }
+        self.mode.scene().exit(self);
+        self.mode = mode;
+        mode.scene().enter(self);
+    }
+
+    /// Goes back to the game from the menus, which is the shop if it was open
+    pub fn resume(&mut self) {
+        self.set_mode(match self.phase {
+            RoundPhase::Fighting => GameMode::Play,
+            RoundPhase::Shopping => GameMode::Shop,
+        });
+    }
+
+    /// Rates the round which has just been cleared, and keeps the rating if it's a new best
+    pub fn finish_round(&mut self, ctx: &mut Context, round: usize) {
+        let summary = RoundSummary {
+            round,
+            ..std::mem::take(&mut self.round_summary)
+        };
+        self.profile
+            .record_stars(&self.map.name, round as u32, summary.stars());
+        self.profile.save(ctx, &mut self.saver);
+        self.last_summary = Some(summary);
+        self.money += self.versus.income();
+        for tower in self.world.towers.iter_mut() {
+            tower.end_round();
+        }
+        let save = self.autosave(false);
+        if round % CHECKPOINT_ROUNDS == 0 && !self.spawner.is_finished() {
+            save.save_checkpoint(ctx, &mut self.saver);
+            self.toasts
+                .push(tr!("Checkpoint saved - round {}", save.round));
+            self.checkpoint = Some(save.clone());
+        }
+        save.save(ctx, &mut self.saver);
+    }
+
+    /// Saves the map and its waves as a single file, which other players can import
+    pub fn export_map(&mut self, ctx: &Context) {
+        let path = format!("/exports{}", self.map.install_path());
+        self.saver
+            .export(ctx, &path, &self.map.to_text(), "the map for sharing");
+    }
+
+    /// Enough of the game to carry on with from the start of the next round
+    pub fn autosave(&self, clean_exit: bool) -> Autosave {
+        Autosave {
+            map: self.map.name.clone(),
+            seed: self.rng.seed(),
+            round: self.spawner.round(),
+            lives: self.lives,
+            money: self.money,
+            towers: self
+                .world
+                .towers
+                .iter()
+                .map(|tower| TowerDescriptor::of(tower.as_ref()))
+                .collect(),
+            clean_exit,
+        }
+    }
+
+    /// Carries on with the game which didn't close properly, from the shop before the
+    /// round it was on
+    pub fn restore_autosave(&mut self) {
+        let Some(save) = self.interrupted.take() else {
+            self.toasts.push(tr!("There's no game to restore"));
+            return;
+        };
+        if save.map != self.map.name {
+            self.toasts
+                .push(tr!("The interrupted game was on a different map"));
+            return;
+        }
+        self.restore(&save);
+    }
+
+    /// Goes back to the last checkpoint, from the shop before its round.
+    /// The checkpoint is kept, so it can be gone back to again.
+    pub fn restore_checkpoint(&mut self) {
+        let Some(save) = self.checkpoint.clone() else {
+            self.toasts.push(tr!("There's no checkpoint to go back to"));
+            return;
+        };
+        if save.map != self.map.name {
+            self.toasts
+                .push(tr!("The checkpoint was on a different map"));
+            return;
+        }
+        self.restore(&save);
+    }
+
+    /// Starts a saved game again, from the shop before the round it was on
+    fn restore(&mut self, save: &Autosave) {
+        self.restart(save.seed);
+        self.lives = save.lives;
+        self.money = save.money;
+        self.spawner.skip_to(save.round);
+        for descriptor in &save.towers {
+            if let Some(tower) = self.tower_registry.spawn(descriptor) {
+                self.world.towers.push(tower);
+            }
+        }
+        self.path.recalculate_weights(&self.world.towers);
+        self.open_shop();
+        self.toasts.push(tr!("Restored round {}", save.round));
+    }
+
+    /// Stops time and opens the shop, ending any overcharge from the last round
+    pub fn open_shop(&mut self) {
+        self.phase = RoundPhase::Shopping;
+        self.set_mode(GameMode::Shop);
+        self.shop.restock();
+        self.set_overcharged(false);
+    }
+
+    /// Closes the shop and carries on with the next round.
+    /// In co-op only the host can do this, and the other player's round starts with it.
+    pub fn start_next_round(&mut self) {
+        if self.host_only() {
+            return;
+        }
+        let round = self.spawner.round();
+        if let Some(session) = self.coop_session() {
+            session.send(&Message::StartRound { round });
+        }
+        self.begin_round();
+    }
+
+    fn begin_round(&mut self) {
+        self.phase = RoundPhase::Fighting;
+        self.set_mode(GameMode::Play);
+        self.versus.start_wave();
+    }
+
+    /// Buys enemies to add to the opponent's next wave, in a versus game
+    pub fn send_enemies(&mut self, index: usize) {
+        let send = SENDS[index];
+        let Some(session) = self
+            .session
+            .as_mut()
+            .filter(|x| x.is_versus() && x.is_connected())
+        else {
+            self.toasts.push(tr!("Sending enemies needs a versus game"));
+            return;
+        };
+        if send.price > self.money {
+            self.toasts.push(tr!("Not enough money"));
+            return;
+        }
+        session.send(&Message::Send {
+            kind: send.kind,
+            count: send.count,
+        });
+        self.money -= send.price;
+        self.versus.sent(send);
+    }
+
+    /// Buys an item from the shop, if it hasn't already been bought and can be afforded
+    pub fn buy(&mut self, item: ShopItem) {
+        if self.host_only() {
+            return;
+        }
+        if self.shop.is_bought(item) {
+            self.toasts.push(tr!("Already bought"));
+            return;
+        }
+        if item.price() > self.money {
+            self.toasts.push(tr!("Not enough money"));
+            return;
+        }
+        match item {
+            ShopItem::ExtraLife => self.lives += 1,
+            ShopItem::Overcharge => self.set_overcharged(true),
+            ShopItem::CashIn => {
+                // the last life can't be sold
+                if self.lives <= 1 {
+                    self.toasts.push(tr!("You need your last life"));
+                    return;
+                }
+                self.lives -= 1;
+                self.money += CASH_IN_REWARD;
+            }
+        }
+        self.money -= item.price();
+        self.shop.buy(item);
+    }
+
+    fn set_overcharged(&mut self, overcharged: bool) {
+        self.overcharged = overcharged;
+        let fire_rate = if overcharged { 2.0 } else { 1.0 };
+        for tower in self.world.towers.iter_mut() {
+            tower.set_fire_rate(fire_rate);
+        }
+    }
+
+    /// Called when the player runs out of lives.
+    /// Exports the towers' stats if the player wants them, then starts a new game,
+    /// keeping the player's settings.
+    pub fn end_game(&mut self, ctx: &mut Context) {
+        self.profile.record_game(
+            &self.map.name,
+            self.spawner.round() as u32,
+            &self.world.towers,
+        );
+        self.profile.save(ctx, &mut self.saver);
+        Autosave::mark_clean(ctx, &mut self.saver);
+        if self.settings.export_stats {
+            stats::export_tower_stats(ctx, &mut self.saver, &self.world.towers);
+        }
+
+        self.toasts
+            .push(tr!("Game over! You reached round {}", self.spawner.round()));
+        let seed = self.chosen_seed.unwrap_or_else(rand::random);
+        let previous_seed = self.rng.seed();
+        self.restart(seed);
+        self.previous_seed = Some(previous_seed);
+        if let Some(checkpoint) = self
+            .checkpoint
+            .as_ref()
+            .filter(|save| save.map == self.map.name)
+        {
+            self.toasts.push(tr!(
+                "Press Checkpoint in the menu to go back to round {}",
+                checkpoint.round
+            ));
+        }
+    }
+
+    /// Starts a new game from the given seed, keeping the player's settings
+    pub fn restart(&mut self, seed: u64) {
+        let mut new_game = match GameState::with_map(self.map.clone(), self.difficulty) {
+            Ok(new_game) => new_game,
+            Err(e) => {
+                self.toasts.push(tr!("Couldn't restart: {}", e));
+                return;
+            }
+        };
+        new_game.settings = self.settings;
+        new_game.key_bindings = self.key_bindings.clone();
+        new_game.player_name = self.player_name.clone();
+        new_game.profile = self.profile.clone();
+        new_game.toasts = std::mem::take(&mut self.toasts);
+        new_game.show_debug = self.show_debug;
+        new_game.show_route_previews = self.show_route_previews;
+        new_game.developer = self.developer;
+        new_game.invincible = self.invincible;
+        new_game.show_pathfinding = self.show_pathfinding;
+        new_game.interrupted = self.interrupted.take();
+        new_game.checkpoint = self.checkpoint.take();
+        std::mem::swap(&mut new_game.saver, &mut self.saver);
+        new_game.session = self.session.take();
+        new_game.join_address = self.join_address.clone();
+        new_game.view_size = self.view_size;
+        new_game.chosen_seed = self.chosen_seed;
+        new_game.previous_seed = self.previous_seed;
+        new_game.rng = RunRng::new(seed);
+        new_game.update_world_view();
+        new_game.set_smooth_routes(self.settings.smooth_routes);
+        *self = new_game;
+        if let Some(session) = self.session.as_mut().filter(|x| x.is_host()) {
+            session.send(&Message::Welcome {
+                seed,
+                versus: session.is_versus(),
+                map: self.map.name.clone(),
+            });
+        }
+    }
+
+    /// Switches to the next way of drawing the ranges of placed towers
+    pub fn cycle_range_style(&mut self) {
+        let range_style = self.settings.range_style.next();
+        self.settings.range_style = range_style;
+        self.toasts
+            .push(tr!("Ranges: {}", translate(range_style.name())));
+    }
+
+    /// Turns reduced motion on or off, which every animation follows from the next frame
+    pub fn set_reduced_motion(&mut self, reduced: bool) {
+        self.settings.accessibility.reduced_motion = reduced;
+        motion::set_reduced_motion(reduced);
+    }
+
+    /// Switches to the next palette, which everything is drawn in from the next frame
+    pub fn cycle_palette(&mut self) {
+        let palette = self.settings.accessibility.palette.next();
+        self.settings.accessibility.palette = palette;
+        palette::set_palette(palette);
+        self.toasts
+            .push(tr!("Colours: {}", translate(palette.name())));
+    }
+
+    /// Saves whether vsync is on, in the config ggez reads when the game starts.
+    /// The window always starts windowed, so `--fullscreen` still works.
+    fn save_vsync(&mut self, ctx: &Context, conf: &mut Conf) {
+        conf.window_setup.vsync = self.settings.vsync;
+        conf.window_mode = window_mode();
+        let mut contents = vec![];
+        match conf.to_toml_file(&mut contents) {
+            Ok(()) => {
+                let contents = String::from_utf8_lossy(&contents);
+                self.saver
+                    .write_plain(ctx, CONFIG_PATH, &contents, "your settings");
+                self.toasts
+                    .push(tr!("Vsync will change when the game is restarted"));
+            }
+            Err(e) => {
+                eprintln!("Failed to write the window config: {}", e);
+                self.toasts
+                    .push(tr!("Couldn't save {}", tr!("your settings")));
+            }
+        }
+    }
+
+    /// Switches to the next language, which is saved next update
+    pub fn cycle_language(&mut self) {
+        self.settings.language = self.settings.language.next();
+        lang::set_language(self.settings.language);
+        self.language_changed = true;
+        self.toasts
+            .push(tr!("Language: {}", self.settings.language.name()));
+    }
+
+    /// Turns route smoothing on or off, and works out the new routes
+    pub fn set_smooth_routes(&mut self, smooth: bool) {
+        self.settings.smooth_routes = smooth;
+        self.path
+            .set_corner_radius(if smooth { CORNER_RADIUS } else { 0.0 });
+        self.path.recalculate_weights(&self.world.towers);
+    }
+
+    /// A copy of the map, towers and next wave, for estimating the chance of surviving it
+    pub fn simulation_setup(&self) -> Option<SimulationSetup> {
+        Some(SimulationSetup {
+            positions: self.path.positions(),
+            connections: self.path.pathfinder().connections().clone(),
+            curves: self.path.curves(),
+            terrain: self.path.terrain(),
+            obstacles: self.path.obstacles().clone(),
+            start: self.path.start(),
+            end: self.path.end(),
+            towers: self
+                .world
+                .towers
+                .iter()
+                .map(|tower| TowerDescriptor::of(tower.as_ref()))
+                .collect(),
+            wave: self.spawner.current_wave()?,
+            modifier: self.spawner.modifier(),
+            round: self.spawner.round(),
+            lives: self.lives,
+        })
+    }
+
+    /// Starts watching a simulation of the next wave against the towers placed so far,
+    /// which can be sped up and looked around freely
+    pub fn spectate(&mut self) {
+        let Some(setup) = self.simulation_setup() else {
+            self.toasts.push(tr!("There are no waves left to watch"));
+            return;
+        };
+        let seed = self.rng.stream(Stream::Estimates).gen();
+        match Simulation::new(&setup, &self.tower_registry, seed) {
+            Some(simulation) => {
+                self.set_mode(GameMode::Spectate);
+                self.spectating = Some(simulation);
+                self.spectate_steps = 0.0;
+            }
+            None => self.toasts.push(tr!("Enemies have no route to watch")),
+        }
+    }
+
+    /// Changes how many steps the game runs each frame, unless this player has joined a
+    /// co-op game, which runs at the host's speed
+    pub fn set_game_speed(&mut self, speed: f32) {
+        if !self.host_only() {
+            self.settings.game_speed = speed;
+        }
+    }
+
+    /// Does whatever a key binding asks for
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Pause => self.set_mode(GameMode::MainMenu),
+            Action::SpeedUp => {
+                let speed = if self.settings.game_speed > 1.0 {
+                    1.0
+                } else {
+                    2.0
+                };
+                self.set_game_speed(speed);
+            }
+            Action::Sell => {
+                if let Some(index) = self.selected_tower.take() {
+                    self.sell_tower(index);
+                }
+            }
+            Action::PreviewRoutes => self.show_route_previews = !self.show_route_previews,
+            Action::PathfindingOverlay => self.show_pathfinding = !self.show_pathfinding,
+            Action::RotateLeft | Action::RotateRight => {
+                if self.host_only() {
+                    return;
+                }
+                let angle = match action {
+                    Action::RotateLeft => -ROTATE_STEP,
+                    _ => ROTATE_STEP,
+                };
+                let rotated = self
+                    .selected_tower
+                    .and_then(|index| self.world.towers.get_mut(index))
+                    .map_or(false, |tower| tower.rotate(angle));
+                // what the tower can see has changed, so the route might too
+                if rotated && !self.path.recalculate_weights(&self.world.towers) {
+                    self.toasts.push(tr!(
+                        "No route left after rotating a tower, keeping the old one"
+                    ));
+                }
+            }
+            // no towers can be retargeted yet
+            Action::CycleTargeting => (),
+        }
+    }
+
+    /// Handles a click on the map which wasn't consumed by the UI.
+    /// Selects the tower underneath it, if there is one.
+    /// Places the tower picked from the radial menu instead, if there is one.
+    pub fn world_click(&mut self, position: Vector) {
+        if let Some(Ability::Airstrike) = self.targeting.take() {
+            self.abilities.use_ability(Ability::Airstrike);
+            let kills = self.world.strike(position, AIRSTRIKE_RADIUS);
+            self.money += kill_reward(self.spawner.modifier()) * kills as u64;
+            return;
+        }
+        if let Some(spawn) = self.placing.take() {
+            self.place_tower(spawn(position));
+            self.keep_placing(spawn);
+            return;
+        }
+        self.selected_tower = self.world.towers.iter().position(|tower| {
+            (tower.position() - position).sqr_length() <= tower.radius() * tower.radius()
+        });
+    }
+
+    /// Closes the radial menu, and starts placing whatever was picked from it
+    pub fn close_radial_menu(&mut self) {
+        if let Some(option) = self.radial_menu.close() {
+            let spawn = option.spawn;
+            self.start_placing(spawn);
+        }
+    }
+
+    /// Starts placing a tower, with the cursor in the middle of the screen
+    pub fn start_placing(&mut self, spawn: fn(Vector) -> Box<dyn Tower>) {
+        self.placing = Some(spawn);
+        let centre = vec2d![
+            self.world_view.x + self.world_view.w / 2.0,
+            self.world_view.y + self.world_view.h / 2.0
+        ];
+        self.hover_position = Some(centre);
+    }
+
+    /// Keeps the same tower in hand after placing one while shift is held, until another
+    /// can't be afforded. Otherwise, placing stops.
+    pub fn keep_placing(&mut self, spawn: fn(Vector) -> Box<dyn Tower>) {
+        if self.shift_held && spawn(Vector::zero()).price() <= self.money {
+            self.placing = Some(spawn);
+            return;
+        }
+        if self.shift_held {
+            self.toasts.push(tr!("Not enough money for another"));
+        }
+        self.placing = None;
+        self.hover_position = None;
+    }
+
+    /// Stops placing a tower without placing it
+    pub fn cancel_placing(&mut self) {
+        self.placing = None;
+        self.hover_position = None;
+    }
+
+    /// Works out the area of the world to show, from the window size and the camera
+    pub fn update_world_view(&mut self) {
+        self.world_view = self.camera.view(fit_world(self.view_size));
+    }
+
+    /// Converts a position in the window, such as the mouse, into a position in the world
+    pub fn to_world(&self, position: Vector) -> Vector {
+        Camera::window_to_world(self.world_view, self.view_size).apply(position)
+    }
+
+    /// Converts a position in the world to where it is in the window
+    pub fn to_window(&self, position: Vector) -> Vector {
+        Camera::window_to_world(self.world_view, self.view_size).inverse_apply(position)
+    }
+}
+
+/// This stores all of the data related to the game, including the canvas and menu
+pub struct MainState {
+    canvas: graphics::Canvas,
+    menus: Vec<Rc<RefCell<Menu<GameState>>>>,
+    state: GameState,
+    /// Played when a rush starts, if the sound could be loaded
+    klaxon: Option<audio::Source>,
+    /// Played when a critical hit lands, if the sound could be loaded
+    crit_sound: Option<audio::Source>,
+    /// The window config the game was started with, kept up to date with the settings
+    /// which are saved in it
+    conf: Conf,
+    /// How long the last call to `update` took, for the debug overlay
+    update_time: Duration,
+    fullscreen: bool,
+    /// When the last frame finished, for limiting the frame rate
+    frame_started: Instant,
+}
+
+impl MainState {
+    /// What the current mode does with updates, drawing and input
+    fn scene(&self) -> &'static dyn Scene {
+        self.state.mode.scene()
+    }
+
+    /// The menu which should currently be drawn and receive input
+    fn current_menu(&self) -> Rc<RefCell<Menu<GameState>>> {
+        Rc::clone(&self.menus[self.state.mode.menu_index()])
+    }
+
+    /// Shows which key is bound to each action, next to its rebind button on the settings menu
+    fn draw_key_bindings(&self, ctx: &mut Context) {
+        let menu = self.menus[SETTINGS_MENU_INDEX].borrow();
+        for (i, &action) in Action::ALL.iter().enumerate() {
+            let text = if self.state.rebinding == Some(action) {
+                tr!("Press a key...")
+            } else {
+                format!("{:?}", self.state.key_bindings.key(action))
+            };
+            let offset = vec2d![580.0, -240.0 + 70.0 * i as f32];
+            draw_text(
+                ctx,
+                &text,
+                menu.position() + offset * menu.scale(),
+                Some(32.0 * menu.scale()),
+                None,
+                Color::WHITE,
+            );
+        }
+    }
+
+    /// Lists the player's lifetime stats on the stats screen
+    fn draw_profile(&self, ctx: &mut Context) {
+        let menu = self.menus[STATS_MENU_INDEX].borrow();
+        let profile = &self.state.profile;
+        let mut lines = vec![
+            tr!("Games played: {}", profile.games_played),
+            tr!("Total kills: {}", profile.total_kills),
+            tr!("Highest round: {}", profile.highest_round),
+            tr!(
+                "Favourite tower: {}",
+                profile
+                    .favourite_tower()
+                    .map(translate)
+                    .unwrap_or_else(|| tr!("None yet"))
+            ),
+            tr!("Best rounds:"),
+        ];
+        lines.extend(profile.map_bests().iter().map(|(map, round)| {
+            format!(
+                "    {}",
+                tr!("{}: {} ({} stars)", map, round, profile.total_stars(map))
+            )
+        }));
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                ctx,
+                line,
+                menu.position() + vec2d![-300.0, -300.0 + 50.0 * i as f32] * menu.scale(),
+                Some(32.0 * menu.scale()),
+                None,
+                Color::WHITE,
+            );
+        }
+    }
+
+    /// Darkens each ability button by how much of its cooldown is left
+    fn draw_ability_cooldowns(&self, ctx: &mut Context) {
+        let menu = self.menus[GAME_MENU_INDEX].borrow();
+        for (i, &ability) in Ability::ALL.iter().enumerate() {
+            let left = self.state.abilities.cooldown_left(ability);
+            if left <= 0.0 {
+                continue;
+            }
+            let offset = vec2d![400.0, 100.0 + 120.0 * i as f32];
+            draw_rectangle(
+                ctx,
+                menu.position() + offset * menu.scale(),
+                vec2d![200.0 * left, 100.0] * menu.scale(),
+                Color::new(0.0, 0.0, 0.0, 0.6),
+            );
+        }
+    }
+
+    /// Lays out the menus and the world again for a new window size.
+    /// The canvas is made again to fill the letterboxed part of the window, so it's drawn
+    /// pixel for pixel rather than stretched.
+    fn resize(&mut self, ctx: &mut Context, window: Vector) {
+        let bars = letterbox(window);
+        // the window has been minimised
+        if bars.w < 1.0 || bars.h < 1.0 {
+            return;
+        }
+        let view_size = vec2d![bars.w.round(), bars.h.round()];
+        match graphics::Canvas::new(
+            ctx,
+            view_size.x as u16,
+            view_size.y as u16,
+            ggez::conf::NumSamples::One,
+            get_window_color_format(ctx),
+        ) {
+            Ok(canvas) => self.canvas = canvas,
+            Err(e) => eprintln!("Failed to resize the canvas: {}", e),
+        }
+        for menu in &self.menus {
+            menu.borrow_mut().set_viewport(view_size);
+        }
+        self.state.view_size = view_size;
+        self.state.update_world_view();
+    }
+
+    /// Switches between fullscreen and a window.
+    /// ggez sends a resize event afterwards, which lays everything out again.
+    fn toggle_fullscreen(&mut self, ctx: &mut Context) {
+        let fullscreen_type = if self.fullscreen {
+            ggez::conf::FullscreenType::Windowed
+        } else {
+            ggez::conf::FullscreenType::Desktop
+        };
+        match graphics::set_fullscreen(ctx, fullscreen_type) {
+            Ok(()) => self.fullscreen = !self.fullscreen,
+            Err(e) => eprintln!("Failed to change to {:?}: {}", fullscreen_type, e),
+        }
+    }
+}
+
+/// Gets the position of the mouse in the letterboxed part of the window, which the UI is
+/// laid out in. Use `GameState::to_world` to find where it is in the world.
+pub fn mouse_position(ctx: &mut Context) -> Vector {
+    let bars = letterbox(window_size(ctx));
+    let position: Vector = mouse::position(ctx).into();
+    position - vec2d![bars.x, bars.y]
+}
+
+/// Gets the size of the letterboxed part of the window, which the UI is laid out in
+pub fn view_size(ctx: &Context) -> Vector {
+    let bars = letterbox(window_size(ctx));
+    vec2d![bars.w, bars.h]
+}
+
+/// Gets the size of the whole window, including any black bars
+fn window_size(ctx: &Context) -> Vector {
+    let size = graphics::drawable_size(ctx);
+    vec2d![size.0, size.1]
+}
+
+/// The largest area in the middle of a window with the same shape as
+/// SCREEN_WIDTH x SCREEN_HEIGHT. The game is drawn here, with black bars around it
+/// if the window is a different shape.
+pub fn letterbox(window: Vector) -> Rect {
+    let scale = (window.x / SCREEN_WIDTH as f32).min(window.y / SCREEN_HEIGHT as f32);
+    let size = vec2d![SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32] * scale;
+    Rect::new(
+        (window.x - size.x) / 2.0,
+        (window.y - size.y) / 2.0,
+        size.x,
+        size.y,
+    )
+}
+
+/// Draws how a round went, with its star rating underneath
+fn draw_summary(ctx: &mut Context, summary: &RoundSummary, position: Vector) {
+    const LINE_HEIGHT: f32 = 36.0;
+    const STAR_RADIUS: f32 = 14.0;
+    let lines = summary.lines();
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            ctx,
+            line,
+            position + vec2d![0.0, LINE_HEIGHT * i as f32],
+            None,
+            None,
+            Color::WHITE,
+        );
+    }
+    for i in 0..RoundSummary::MAX_STARS {
+        let colour = if i < summary.stars() {
+            Color::from_rgb(255, 215, 0)
+        } else {
+            Color::new(0.3, 0.3, 0.3, 1.0)
+        };
+        let centre = position
+            + vec2d![
+                STAR_RADIUS + STAR_RADIUS * 2.5 * i as f32,
+                LINE_HEIGHT * lines.len() as f32 + STAR_RADIUS
+            ];
+        draw_star(ctx, centre, STAR_RADIUS, colour);
+    }
+}
+
+/// Finds the area of the world to show in a window of this size.
+/// The whole SCREEN_WIDTH x SCREEN_HEIGHT world always fits without being stretched,
+/// with any spare space split evenly on either side.
+pub fn fit_world(view_size: Vector) -> Rect {
+    let scale = (SCREEN_WIDTH as f32 / view_size.x).max(SCREEN_HEIGHT as f32 / view_size.y);
+    let size = view_size * scale;
+    Rect::new(
+        (SCREEN_WIDTH as f32 - size.x) / 2.0,
+        (SCREEN_HEIGHT as f32 - size.y) / 2.0,
+        size.x,
+        size.y,
+    )
+}
+
+macro_rules! menu_new {
+    ($location:expr, $anchor:expr, $scale:expr, $parent:expr, [$({$type:ident, $button_location:expr, $button_size:expr, $($arguments:expr,)* $(,)?})*]) => {
+        {
+            let menu = Rc::new(RefCell::new(Menu::new($location, $anchor, $scale, $parent)));
+            let buttons = vec![
+                $($type::new($button_location, $button_size, Rc::downgrade(&menu), $($arguments,)*).into(),)*
+            ];
+            menu.borrow_mut().add_elements(buttons);
+            menu
+        }
+    }
+}
+
+impl MainState {
+    /// Initialises the game
+    fn new(ctx: &mut Context, options: LaunchOptions) -> GameResult<MainState> {
+        let game_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::TopLeft,
+            1.0,
+            None,
+            [
+                {
+                    Button, vec2d![0.0, 100.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| {
+                        let rng = state.rng.stream(Stream::Spawning);
+                        let position = vec2d![
+                            rng.gen_range(0.0..SCREEN_WIDTH as f32),
+                            rng.gen_range(0.0..SCREEN_HEIGHT as f32)
+                        ];
+                        state.place_tower(spawn_tower(position));
+                    },
+                    "Spawn Tower",
+                }
+                {
+                    DragButton, vec2d![0.0, 300.0], vec2d![100.0, 100.0],
+                    |start, state| state.hover_position = Some(state.to_world(start)),
+                    |start, position, movement, state| {
+                        state.hover_position = Some(state.to_world(position))
+                    },
+                    |start, position, state| {
+                        let position = state.to_world(position);
+                        state.place_tower(spawn_tower(position));
+                        state.using_controller = false;
+                        state.keep_placing(spawn_tower);
+                    },
+                    "Drag!",
+                    Some(|ctx, centre, size| {
+                        draw_miniature(ctx, spawn_tower(Vector::zero()).as_ref(), centre, size)
+                    }),
+                    Some(Price {
+                        amount: spawn_tower(Vector::zero()).price(),
+                        on_refused: |state| state.toasts.push(tr!("Not enough money")),
+                    }),
+                }
+                {
+                    Button, vec2d![0.0, 500.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.set_mode(GameMode::MainMenu),
+                    "Pause",
+                }
+                {
+                    Button, vec2d![650.0, 100.0], vec2d![200.0, 100.0],
+                    |state: &mut GameState| state.upgrade_selected(Upgrade::Radius),
+                    "Extend range (20)",
+                }
+                {
+                    Button, vec2d![650.0, 220.0], vec2d![200.0, 100.0],
+                    |state: &mut GameState| state.upgrade_selected(Upgrade::Fov),
+                    "Widen cone (20)",
+                }
+                {
+                    Button, vec2d![650.0, 340.0], vec2d![200.0, 100.0],
+                    |state: &mut GameState| state.repair_selected(),
+                    "Repair",
+                }
+                {
+                    Button, vec2d![650.0, 460.0], vec2d![200.0, 100.0],
+                    |state: &mut GameState| state.upgrade_selected(Upgrade::Crit),
+                    "Sharpen aim (25)",
+                }
+                {
+                    DragButton, vec2d![0.0, 700.0], vec2d![100.0, 100.0],
+                    |start, state| {
+                        state.priority_zone = Some(Polygon::new(vec![state.to_world(start)]))
+                    },
+                    |start, position, movement, state| {
+                        let position = state.to_world(position);
+                        // only add points once the brush has moved far enough, to keep the polygon small
+                        if let Some(zone) = state.priority_zone.as_mut() {
+                            let last = *zone.points().last().unwrap_or(&position);
+                            if (position - last).sqr_length() >= 20.0 * 20.0 {
+                                zone.push(position);
+                            }
+                        }
+                    },
+                    |start, position, state| {
+                        let position = state.to_world(position);
+                        if let Some(zone) = state.priority_zone.as_mut() {
+                            zone.push(position);
+                        }
+                        if !state.priority_zone.as_ref().map_or(false, Polygon::is_valid) {
+                            state.priority_zone = None;
+                        }
+                    },
+                    "Zone",
+                    None,
+                    None,
+                }
+            ]
+        );
+        let main_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::Centre,
+            1.0,
+            None,
+            [
+                {
+                    Button, vec2d![-50.0, -100.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| {
+                        // a newly typed seed starts a new game
+                        if let Some(seed) = state.chosen_seed.filter(|&seed| seed != state.rng.seed()) {
+                            state.restart(seed);
+                        }
+                        state.resume();
+                    },
+                    "Play",
+                }
+                {
+                    Button, vec2d![-50.0, 150.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.set_mode(GameMode::Settings),
+                    "Settings",
+                }
+                {
+                    Button, vec2d![-50.0, -220.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.restore_autosave(),
+                    "Restore",
+                }
+                {
+                    Button, vec2d![-460.0, -100.0], vec2d![160.0, 60.0],
+                    |state: &mut GameState| state.spectate(),
+                    "Spectate",
+                }
+                {
+                    Button, vec2d![-460.0, -20.0], vec2d![160.0, 60.0],
+                    |state: &mut GameState| state.restore_checkpoint(),
+                    "Checkpoint",
+                }
+                {
+                    TextInput, vec2d![-200.0, 50.0], vec2d![400.0, 60.0],
+                    |text: &str, state: &mut GameState| {
+                        state.player_name = text.to_owned();
+                    },
+                    "Player name",
+                    20,
+                }
+                {
+                    TextInput, vec2d![-200.0, 280.0], vec2d![400.0, 60.0],
+                    |text: &str, state: &mut GameState| {
+                        state.chosen_seed = text.trim().parse().ok();
+                    },
+                    "Seed (optional)",
+                    20,
+                }
+                {
+                    Button, vec2d![-50.0, 380.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.set_mode(GameMode::Stats),
+                    "Stats",
+                }
+                {
+                    Button, vec2d![300.0, -100.0], vec2d![160.0, 60.0],
+                    |state: &mut GameState| state.host_game(false),
+                    "Host co-op",
+                }
+                {
+                    Button, vec2d![300.0, -180.0], vec2d![160.0, 60.0],
+                    |state: &mut GameState| state.host_game(true),
+                    "Host versus",
+                }
+                {
+                    TextInput, vec2d![300.0, 0.0], vec2d![300.0, 60.0],
+                    |text: &str, state: &mut GameState| {
+                        state.join_address = text.to_owned();
+                    },
+                    "Host's address",
+                    40,
+                }
+                {
+                    Button, vec2d![300.0, 80.0], vec2d![160.0, 60.0],
+                    |state: &mut GameState| state.join_game(),
+                    "Join",
+                }
+            ]
+        );
+        let stats_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::Centre,
+            1.0,
+            None,
+            [
+                {
+                    Button, vec2d![-50.0, 300.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.set_mode(GameMode::MainMenu),
+                    "Back",
+                }
+            ]
+        );
+        let shop_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::Centre,
+            1.0,
+            None,
+            [
+                {
+                    Button, vec2d![-150.0, -200.0], vec2d![300.0, 80.0],
+                    |state: &mut GameState| state.buy(ShopItem::ExtraLife),
+                    ShopItem::ExtraLife.label(),
+                }
+                {
+                    Button, vec2d![-150.0, -100.0], vec2d![300.0, 80.0],
+                    |state: &mut GameState| state.buy(ShopItem::Overcharge),
+                    ShopItem::Overcharge.label(),
+                }
+                {
+                    Button, vec2d![-150.0, 0.0], vec2d![300.0, 80.0],
+                    |state: &mut GameState| state.buy(ShopItem::CashIn),
+                    ShopItem::CashIn.label(),
+                }
+                {
+                    Button, vec2d![-100.0, 150.0], vec2d![200.0, 100.0],
+                    |state: &mut GameState| state.start_next_round(),
+                    "Next round",
+                }
+            ]
+        );
+        let spectate_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::BottomLeft,
+            1.0,
+            None,
+            [
+                {
+                    Slider, vec2d![20.0, -80.0], vec2d![300.0, 40.0],
+                    |value, state: &mut GameState| state.spectate_speed = value,
+                    "Speed",
+                    0.25,
+                    8.0,
+                    1.0,
+                }
+                {
+                    Button, vec2d![360.0, -100.0], vec2d![160.0, 80.0],
+                    |state: &mut GameState| state.spectate(),
+                    "Restart",
+                }
+                {
+                    Button, vec2d![540.0, -100.0], vec2d![160.0, 80.0],
+                    |state: &mut GameState| state.set_mode(GameMode::MainMenu),
+                    "Back",
+                }
+            ]
+        );
+        // the saved config, if there is one, is what the window was opened with
+        let conf = filesystem::read_config(ctx).unwrap_or_else(|_| Conf {
+            window_mode: window_mode(),
+            ..Conf::default()
+        });
+        let mut settings = Settings::default();
+        settings.vsync = conf.window_setup.vsync;
+        let settings_menu = menu_new!(
+            vec2d![0.0, 0.0],
+            Anchor::Centre,
+            1.0,
+            None,
+            [
+                {
+                    Slider, vec2d![-200.0, -250.0], vec2d![400.0, 40.0],
+                    |value, state: &mut GameState| state.settings.volume = value,
+                    "Volume",
+                    0.0,
+                    1.0,
+                    settings.volume,
+                }
+                {
+                    Slider, vec2d![-200.0, -130.0], vec2d![400.0, 40.0],
+                    |value, state: &mut GameState| state.set_game_speed(value),
+                    "Game speed",
+                    0.25,
+                    4.0,
+                    settings.game_speed,
+                }
+                {
+                    Checkbox, vec2d![-200.0, -50.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.settings.show_web = checked,
+                    "Show web",
+                    settings.show_web,
+                }
+                {
+                    Checkbox, vec2d![-200.0, 0.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.set_reduced_motion(checked),
+                    "Reduced motion",
+                    settings.accessibility.reduced_motion,
+                }
+                {
+                    Button, vec2d![-560.0, -250.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.cycle_palette(),
+                    "Colours",
+                }
+                {
+                    Slider, vec2d![-560.0, -130.0], vec2d![260.0, 40.0],
+                    |value, state: &mut GameState| state.settings.ui_scale = value,
+                    "UI scale",
+                    UI_SCALE_RANGE.0,
+                    UI_SCALE_RANGE.1,
+                    settings.ui_scale,
+                }
+                {
+                    Button, vec2d![300.0, -250.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::RotateLeft),
+                    Action::RotateLeft.name(),
+                }
+                {
+                    Button, vec2d![300.0, -180.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::RotateRight),
+                    Action::RotateRight.name(),
+                }
+                {
+                    Button, vec2d![300.0, -110.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::Pause),
+                    Action::Pause.name(),
+                }
+                {
+                    Button, vec2d![300.0, -40.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::SpeedUp),
+                    Action::SpeedUp.name(),
+                }
+                {
+                    Button, vec2d![300.0, 30.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::Sell),
+                    Action::Sell.name(),
+                }
+                {
+                    Button, vec2d![300.0, 100.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::CycleTargeting),
+                    Action::CycleTargeting.name(),
+                }
+                {
+                    Button, vec2d![300.0, 170.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.rebinding = Some(Action::PreviewRoutes),
+                    Action::PreviewRoutes.name(),
+                }
+                {
+                    Checkbox, vec2d![-200.0, 50.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.settings.export_stats = checked,
+                    "Export tower stats",
+                    settings.export_stats,
+                }
+                {
+                    Checkbox, vec2d![-200.0, 100.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.settings.show_win_chance = checked,
+                    "Show win chance",
+                    settings.show_win_chance,
+                }
+                {
+                    Checkbox, vec2d![300.0, 250.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.set_smooth_routes(checked),
+                    "Smooth routes",
+                    settings.smooth_routes,
+                }
+                {
+                    Button, vec2d![300.0, 320.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.exporting_map = true,
+                    "Export map",
+                }
+                {
+                    Button, vec2d![300.0, 390.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.cycle_language(),
+                    "Language",
+                }
+                {
+                    Checkbox, vec2d![-560.0, -50.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| {
+                        state.settings.vsync = checked;
+                        state.vsync_changed = true;
+                    },
+                    "Vsync",
+                    settings.vsync,
+                }
+                {
+                    Checkbox, vec2d![-560.0, 0.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.settings.limit_frame_rate = checked,
+                    "Limit frame rate",
+                    settings.limit_frame_rate,
+                }
+                {
+                    Slider, vec2d![-560.0, 110.0], vec2d![260.0, 40.0],
+                    |value, state: &mut GameState| state.settings.frame_limit = value,
+                    "Frame limit",
+                    30.0,
+                    240.0,
+                    settings.frame_limit,
+                }
+                {
+                    Checkbox, vec2d![-560.0, 170.0], vec2d![40.0, 40.0],
+                    |checked, state: &mut GameState| state.settings.hide_full_health_bars = checked,
+                    "Hide full health bars",
+                    settings.hide_full_health_bars,
+                }
+                {
+                    Button, vec2d![-560.0, 240.0], vec2d![260.0, 60.0],
+                    |state: &mut GameState| state.cycle_range_style(),
+                    "Ranges",
+                }
+                {
+                    Button, vec2d![-50.0, 170.0], vec2d![100.0, 100.0],
+                    |state: &mut GameState| state.set_mode(GameMode::MainMenu),
+                    "Back",
+                }
+            ]
+        );
+        let mut state = GameState::with_map(options.map.unwrap_or_default(), options.difficulty)
+            .map_err(|e| GameError::CustomError(e.to_string()))?;
+        state.settings.vsync = settings.vsync;
+        state.developer = options.developer;
+        if options.install {
+            let map = &state.map;
+            state
+                .saver
+                .export(ctx, &map.install_path(), &map.to_text(), "the imported map");
+        }
+        if let Some(seed) = options.seed {
+            state.chosen_seed = Some(seed);
+            state.restart(seed);
+        }
+        if options.play {
+            state.set_mode(GameMode::Play);
+        }
+        // one shop button for every kind of tower, squeezed together once there are too
+        // many to fit down the screen
+        let kinds = state.tower_registry.kinds().len().max(1);
+        let spacing = 120.0f32.min((SCREEN_HEIGHT as f32 - 140.0) / kinds as f32);
+        let shop_buttons = state
+            .tower_registry
+            .kinds()
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                Button::new_indexed(
+                    vec2d![150.0, 100.0 + spacing * i as f32],
+                    vec2d![200.0, spacing - 20.0],
+                    Rc::downgrade(&game_menu),
+                    |index, state: &mut GameState| {
+                        let spawn = state.tower_registry.get(index).map(|kind| kind.spawn);
+                        if let Some(spawn) = spawn {
+                            state.start_placing(spawn);
+                        }
+                    },
+                    i,
+                    &format!("{} ({})", kind.name, kind.price),
+                )
+                .into()
+            })
+            .collect();
+        game_menu.borrow_mut().add_elements(shop_buttons);
+        let ability_buttons = Ability::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, ability)| {
+                Button::new_indexed(
+                    vec2d![400.0, 100.0 + 120.0 * i as f32],
+                    vec2d![200.0, 100.0],
+                    Rc::downgrade(&game_menu),
+                    |index, state: &mut GameState| state.activate(Ability::ALL[index]),
+                    i,
+                    ability.name(),
+                )
+                .into()
+            })
+            .collect();
+        game_menu.borrow_mut().add_elements(ability_buttons);
+        // one shop button for everything which can be sent in versus
+        let send_buttons = SENDS
+            .iter()
+            .enumerate()
+            .map(|(i, send)| {
+                Button::new_indexed(
+                    vec2d![250.0, -200.0 + 100.0 * i as f32],
+                    vec2d![300.0, 80.0],
+                    Rc::downgrade(&shop_menu),
+                    |index, state: &mut GameState| state.send_enemies(index),
+                    i,
+                    &send.label(),
+                )
+                .into()
+            })
+            .collect();
+        shop_menu.borrow_mut().add_elements(send_buttons);
+
+        if !options.fullscreen {
+            graphics::set_drawable_size(ctx, 1920.0 / 2.0, 1080.0 / 2.0)?;
+        }
+
+        let mut s = MainState {
+            canvas: graphics::Canvas::new(
+                ctx,
+                SCREEN_WIDTH as u16,
+                SCREEN_HEIGHT as u16,
+                ggez::conf::NumSamples::One,
+                get_window_color_format(ctx),
+            )?,
+            menus: vec![
+                game_menu,
+                main_menu,
+                settings_menu,
+                stats_menu,
+                shop_menu,
+                spectate_menu,
+            ],
+            state,
+            klaxon: audio::Source::new(ctx, "/klaxon.ogg").ok(),
+            crit_sound: audio::Source::new(ctx, "/crit.ogg").ok(),
+            conf,
+            update_time: Duration::ZERO,
+            fullscreen: options.fullscreen,
+            frame_started: Instant::now(),
+        };
+        if options.fullscreen {
+            s.resize(ctx, window_size(ctx));
+        } else {
+            s.resize(
+                ctx,
+                vec2d![SCREEN_WIDTH as f32 / 2.0, SCREEN_HEIGHT as f32 / 2.0],
+            );
+        }
+        s.state.key_bindings = KeyBindings::load(ctx);
+        s.state.profile = Profile::load(ctx);
+        storage::load_language(ctx);
+        s.state.settings.language = lang::language();
+        s.state.interrupted = Autosave::load(ctx).filter(|save| !save.clean_exit);
+        s.state.checkpoint = Autosave::load_checkpoint(ctx);
+        if s.state.interrupted.is_some() {
+            s.state.toasts.push(tr!(
+                "Your last game didn't close properly - press Restore to carry on"
+            ));
+        }
+        Ok(s)
+    }
+}
+
+impl event::EventHandler<ggez::GameError> for MainState {
+    /// Moves the game one step through time
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        let started = Instant::now();
+        self.state
+            .toasts
+            .update(ggez::timer::delta(_ctx).as_secs_f32());
+        self.state.update_session();
+        self.state.shift_held = keyboard::is_mod_active(_ctx, KeyMods::SHIFT);
+        if std::mem::take(&mut self.state.exporting_map) {
+            self.state.export_map(_ctx);
+        }
+        if std::mem::take(&mut self.state.language_changed) {
+            storage::save_language(_ctx, &mut self.state.saver);
+        }
+        if std::mem::take(&mut self.state.vsync_changed) {
+            self.state.save_vsync(_ctx, &mut self.conf);
+        }
+        // the simulation runs at the same rate however fast frames are drawn
+        let steps = ggez::timer::delta(_ctx).as_secs_f32().min(MAX_FRAME_TIME) * STEPS_PER_SECOND;
+        for message in self.state.saver.messages() {
+            self.state.toasts.push(message);
+        }
+        for error in error::take_reported() {
+            let message = error.to_string();
+            if self.state.last_error.as_ref() != Some(&message) {
+                self.state.toasts.push(message.clone());
+                self.state.last_error = Some(message);
+            }
+        }
+        for element in &self.menus[GAME_MENU_INDEX].borrow().elements {
+            if let UIElement::DragButton(button) = element {
+                button.set_money(self.state.money);
+            }
+        }
+
+        self.scene().update(self, _ctx, steps);
+        self.update_time = started.elapsed();
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let ui_view = Rect::new(0.0, 0.0, self.state.view_size.x, self.state.view_size.y);
+        graphics::set_canvas(ctx, Some(&self.canvas));
+        graphics::clear(ctx, self.scene().background(&self.state));
+
+        let state = &self.state;
+        let mut queue = RenderQueue::new();
+        self.scene().draw(self, &mut queue);
+        queue.submit(Layer::Overlay, move |ctx| {
+            state.toasts.draw(ctx, state.view_size)
+        });
+        queue.flush(ctx, state.world_view, ui_view);
+
+        graphics::set_canvas(ctx, None);
+        // the canvas is drawn pixel for pixel in the middle of the window, with black bars
+        // filling any space around it
+        let window = window_size(ctx);
+        let bars = letterbox(window);
+        report(graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(0.0, 0.0, window.x, window.y),
+        ));
+        graphics::clear(ctx, Color::BLACK);
+        graphics::draw(
+            ctx,
+            &self.canvas,
+            graphics::DrawParam::new()
+                .dest([bars.x.round(), bars.y.round()])
+                .color(Color::from((255, 255, 255, 255))),
+        )?;
+
+        graphics::present(ctx)?;
+        if self.state.settings.limit_frame_rate {
+            let frame = Duration::from_secs_f32(1.0 / self.state.settings.frame_limit);
+            let elapsed = self.frame_started.elapsed();
+            if elapsed < frame {
+                std::thread::sleep(frame - elapsed);
+            }
+        }
+        self.frame_started = Instant::now();
+
+        Ok(())
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        self.resize(ctx, vec2d![width, height]);
+    }
+
+    /// Saves the game marked as closed properly, so it isn't offered back next time
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        self.state.autosave(true).save(ctx, &mut self.state.saver);
+        self.state.saver.flush();
+        false
+    }
+
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: event::MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+        let position = mouse_position(ctx);
+        if button == event::MouseButton::Left
+            && self
+                .current_menu()
+                .borrow()
+                .input_start(position, &mut self.state)
+        {
+            return;
+        }
+        self.scene()
+            .handle_input(self, ctx, Input::MouseDown(button, position));
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        if self.state.radial_menu.is_open() && !self.state.using_controller {
+            self.state.radial_menu.point_at(mouse_position(ctx));
+        }
+        if self.state.panning {
+            // drag the world along with the mouse
+            let world_per_pixel = self.state.world_view.w / self.state.view_size.x;
+            self.state.camera.pan(vec2d![-dx, -dy] * world_per_pixel);
+            self.state.update_world_view();
+        }
+        self.current_menu().borrow().input_moved(
+            mouse_position(ctx),
+            vec2d![dx, dy],
+            &mut self.state,
+        );
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        ctx: &mut Context,
+        button: event::MouseButton,
+        _x: f32,
+        _y: f32,
+    ) {
+        match button {
+            event::MouseButton::Left => {
+                self.current_menu()
+                    .borrow()
+                    .input_released(mouse_position(ctx), &mut self.state);
+            }
+            event::MouseButton::Middle => self.state.panning = false,
+            event::MouseButton::Right if !self.state.using_controller => {
+                self.state.close_radial_menu()
+            }
+            _ => (),
+        }
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: event::Button, _id: GamepadId) {
+        self.scene().handle_input(self, ctx, Input::Gamepad(btn));
+    }
+
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: event::Button, _id: GamepadId) {
+        if btn == event::Button::LeftTrigger2 && self.state.using_controller {
+            self.state.close_radial_menu();
+        }
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        _ctx: &mut Context,
+        axis: event::Axis,
+        value: f32,
+        _id: GamepadId,
+    ) {
+        match axis {
+            event::Axis::LeftStickX => self.state.stick.x = value,
+            // the stick's y axis points up, but the screen's points down
+            event::Axis::LeftStickY => self.state.stick.y = -value,
+            _ => (),
+        }
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        self.scene().handle_input(self, ctx, Input::MouseWheel(y));
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        self.current_menu()
+            .borrow()
+            .text_input(character, &mut self.state);
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        _repeat: bool,
+    ) {
+        if keycode == KeyCode::Return && keymods.contains(KeyMods::ALT) {
+            self.toggle_fullscreen(ctx);
+            return;
+        }
+        if let Some(action) = self.state.rebinding.take() {
+            // escape cancels rebinding
+            if KeyBindings::is_bindable(keycode) {
+                self.state.key_bindings.bind(action, keycode);
+                self.state.key_bindings.save(ctx, &mut self.state.saver);
+            }
+            return;
+        }
+
+        match keycode {
+            KeyCode::F3 => self.state.show_debug = !self.state.show_debug,
+            KeyCode::Back => {
+                self.current_menu().borrow().backspace(&mut self.state);
+            }
+            _ => {
+                let used = self
+                    .scene()
+                    .handle_input(self, ctx, Input::Key(keycode, keymods));
+                // this is what ggez does by default
+                if !used && keycode == KeyCode::Escape {
+                    event::quit(ctx);
+                }
+            }
+        }
+    }
+}
+
+/// How the window starts, before `--fullscreen` is applied
+fn window_mode() -> ggez::conf::WindowMode {
+    ggez::conf::WindowMode::default().resizable(true)
+}
+
+/// Opens the window and runs the game until it is closed
+pub fn run(options: LaunchOptions) -> GameResult {
+    let cb = ggez::ContextBuilder::new("ACROSS", "Cosmo Brain").window_mode(window_mode());
+    let (mut ctx, event_loop) = cb.build()?;
+    // this is done after building, as a saved config replaces the builder's window mode
+    if options.fullscreen {
+        graphics::set_fullscreen(&mut ctx, ggez::conf::FullscreenType::Desktop)?;
+    }
+
+    let state = MainState::new(&mut ctx, options)?;
+    event::run(ctx, event_loop, state)
+}
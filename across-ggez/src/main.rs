@@ -0,0 +1,28 @@
+use across_ggez::{
+    autoplay::autoplay,
+    cli::{LaunchOptions, USAGE},
+};
+
+fn main() -> ggez::GameResult {
+    let options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Some(games) = options.autoplay {
+        let map = options.map.unwrap_or_default();
+        let first_seed = options.seed.unwrap_or_else(rand::random);
+        let mut won = 0;
+        for seed in (0..games as u64).map(|i| first_seed.wrapping_add(i)) {
+            // the map has already been checked when it was loaded
+            let report = autoplay(&map, options.difficulty, seed).expect("Invalid map");
+            won += report.won as usize;
+            println!("{}", report);
+        }
+        println!("Won {} of {} games", won, games);
+        return Ok(());
+    }
+    across_ggez::run(options)
+}
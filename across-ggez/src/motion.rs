@@ -0,0 +1,16 @@
+use std::cell::Cell;
+
+thread_local! {
+    static REDUCED: Cell<bool> = Cell::new(false);
+}
+
+/// Turns reduced motion on or off, for everything drawn from the next frame
+pub fn set_reduced_motion(reduced: bool) {
+    REDUCED.with(|current| current.set(reduced));
+}
+
+/// Whether animations should be skipped, jumping straight to their final state.
+/// Anything which animates should check this, just as it draws in `scheme`'s colours.
+pub fn reduced_motion() -> bool {
+    REDUCED.with(|current| current.get())
+}
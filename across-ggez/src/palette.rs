@@ -0,0 +1,276 @@
+use std::cell::Cell;
+
+use ggez::graphics::Color;
+
+/// The colours everything in the game is drawn in.
+/// Some players find the standard reds, oranges and greens hard to tell apart, so other
+/// palettes use colours which are easier to distinguish, and tell things apart by shape too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    #[default]
+    Standard,
+    /// Colours which can be told apart with any kind of colour blindness
+    Colourblind,
+    /// Bright colours on black
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [
+        Palette::Standard,
+        Palette::Colourblind,
+        Palette::HighContrast,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::Colourblind => "Colourblind",
+            Palette::HighContrast => "High contrast",
+        }
+    }
+
+    /// The palette after this one, for cycling through them in the settings
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|x| x == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn scheme(&self) -> &'static Scheme {
+        match self {
+            Palette::Standard => &STANDARD,
+            Palette::Colourblind => &COLOURBLIND,
+            Palette::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
+
+/// What a palette draws each thing in
+#[derive(Debug, Clone, Copy)]
+pub struct Scheme {
+    pub background: Color,
+    /// The connections of the web
+    pub web: Color,
+    /// The route enemies are taking, and its nodes
+    pub route: Color,
+    /// Drawn under connections with that terrain
+    pub mud: Color,
+    pub road: Color,
+    pub water: Color,
+    pub test_enemy: Color,
+    pub brave_enemy: Color,
+    pub carrier_enemy: Color,
+    /// The small enemies carriers release
+    pub swarm_enemy: Color,
+    pub teleporter_enemy: Color,
+    pub juggernaut_enemy: Color,
+    pub bullet: Color,
+    /// The damage hits do, floating up from where they landed
+    pub damage_number: Color,
+    pub critical_hit: Color,
+    pub test_tower: Color,
+    pub sector_tower: Color,
+    pub freeze_tower: Color,
+    pub bank_tower: Color,
+    pub sniper_tower: Color,
+    /// The ring a sniper tower draws around the enemy it's locking on to
+    pub lock_on: Color,
+    pub tesla_tower: Color,
+    pub decoy_tower: Color,
+    pub missile_tower: Color,
+    pub bouncer_tower: Color,
+    pub lance_tower: Color,
+    pub wall: Color,
+    /// Map obstacles, which block towers from seeing through them
+    pub obstacle: Color,
+    /// Where towers can be built, on maps which have build zones
+    pub build_zone: Color,
+    /// Where towers can never be built
+    pub no_build_zone: Color,
+    /// What towers can see
+    pub range: Color,
+    /// The edge of what towers can see, when ranges are drawn as outlines
+    pub range_outline: Color,
+    /// The ring around the selected tower
+    pub selection: Color,
+    /// Drawn over enemies while the freeze ability holds them
+    pub frozen: Color,
+    /// Where an airstrike will land, while it is being aimed
+    pub airstrike: Color,
+    /// The previews of the cautious and brave routes
+    pub cautious_route: Color,
+    pub brave_route: Color,
+    /// The ends of bars which go from bad to good, like the win chance
+    pub bad: Color,
+    pub good: Color,
+    /// Whether enemies are outlined and towers are marked with a glyph, so they can be
+    /// told apart without their colours
+    pub shapes: bool,
+    /// The colour of the outlines and glyphs
+    pub outline: Color,
+}
+
+impl Scheme {
+    /// The colour of a bar which is `amount` [0-1] of the way from bad to good
+    pub fn bad_to_good(&self, amount: f32) -> Color {
+        let amount = amount.clamp(0.0, 1.0);
+        let mix = |bad: f32, good: f32| bad + (good - bad) * amount;
+        Color::new(
+            mix(self.bad.r, self.good.r),
+            mix(self.bad.g, self.good.g),
+            mix(self.bad.b, self.good.b),
+            mix(self.bad.a, self.good.a),
+        )
+    }
+}
+
+const STANDARD: Scheme = Scheme {
+    background: Color::new(0.0, 0.0, 0.0, 1.0),
+    web: Color::new(0.5, 0.5, 0.5, 1.0),
+    route: Color::new(1.0, 1.0, 1.0, 1.0),
+    mud: Color::new(0.45, 0.3, 0.15, 0.8),
+    road: Color::new(0.6, 0.6, 0.55, 0.6),
+    water: Color::new(0.15, 0.4, 0.9, 0.7),
+    test_enemy: Color::new(1.0, 0.0, 0.0, 1.0),
+    brave_enemy: Color::new(1.0, 0.55, 0.0, 1.0),
+    carrier_enemy: Color::new(0.6, 0.2, 0.8, 1.0),
+    swarm_enemy: Color::new(1.0, 0.4, 0.7, 1.0),
+    teleporter_enemy: Color::new(0.2, 0.9, 0.9, 1.0),
+    juggernaut_enemy: Color::new(0.55, 0.55, 0.6, 1.0),
+    bullet: Color::new(0.0, 1.0, 0.5, 1.0),
+    damage_number: Color::new(1.0, 1.0, 1.0, 0.9),
+    critical_hit: Color::new(1.0, 0.85, 0.1, 1.0),
+    test_tower: Color::new(1.0, 1.0, 1.0, 1.0),
+    sector_tower: Color::new(0.47, 0.78, 1.0, 1.0),
+    freeze_tower: Color::new(0.67, 0.9, 1.0, 1.0),
+    bank_tower: Color::new(1.0, 0.84, 0.0, 1.0),
+    sniper_tower: Color::new(0.45, 0.55, 0.35, 1.0),
+    lock_on: Color::new(1.0, 0.2, 0.2, 0.8),
+    tesla_tower: Color::new(0.55, 0.6, 1.0, 1.0),
+    decoy_tower: Color::new(1.0, 0.5, 0.7, 1.0),
+    missile_tower: Color::new(0.85, 0.35, 0.2, 1.0),
+    bouncer_tower: Color::new(0.6, 0.9, 0.4, 1.0),
+    lance_tower: Color::new(0.75, 0.75, 0.85, 1.0),
+    wall: Color::new(0.59, 0.43, 0.31, 1.0),
+    obstacle: Color::new(0.35, 0.3, 0.25, 1.0),
+    build_zone: Color::new(0.3, 1.0, 0.3, 0.15),
+    no_build_zone: Color::new(1.0, 0.2, 0.2, 0.25),
+    range: Color::new(1.0, 1.0, 1.0, 0.39),
+    range_outline: Color::new(1.0, 1.0, 1.0, 0.8),
+    selection: Color::new(1.0, 1.0, 0.0, 0.5),
+    frozen: Color::new(0.5, 0.8, 1.0, 0.5),
+    airstrike: Color::new(1.0, 0.3, 0.0, 0.3),
+    cautious_route: Color::new(1.0, 0.2, 0.2, 0.35),
+    brave_route: Color::new(1.0, 0.55, 0.0, 0.35),
+    bad: Color::new(1.0, 0.0, 0.0, 1.0),
+    good: Color::new(0.0, 1.0, 0.0, 1.0),
+    shapes: false,
+    outline: Color::new(1.0, 1.0, 1.0, 1.0),
+};
+
+/// Based on the Okabe-Ito colours
+const COLOURBLIND: Scheme = Scheme {
+    background: Color::new(0.0, 0.0, 0.0, 1.0),
+    web: Color::new(0.5, 0.5, 0.5, 1.0),
+    route: Color::new(1.0, 1.0, 1.0, 1.0),
+    mud: Color::new(0.6, 0.45, 0.3, 0.8),
+    road: Color::new(0.6, 0.6, 0.6, 0.6),
+    water: Color::new(0.0, 0.45, 0.7, 0.8),
+    test_enemy: Color::new(0.84, 0.37, 0.0, 1.0),
+    brave_enemy: Color::new(0.94, 0.89, 0.26, 1.0),
+    carrier_enemy: Color::new(0.8, 0.47, 0.65, 1.0),
+    swarm_enemy: Color::new(0.0, 0.62, 0.45, 1.0),
+    teleporter_enemy: Color::new(0.34, 0.71, 0.91, 1.0),
+    juggernaut_enemy: Color::new(0.9, 0.62, 0.0, 1.0),
+    bullet: Color::new(0.34, 0.71, 0.91, 1.0),
+    damage_number: Color::new(1.0, 1.0, 1.0, 0.9),
+    critical_hit: Color::new(0.9, 0.62, 0.0, 1.0),
+    test_tower: Color::new(1.0, 1.0, 1.0, 1.0),
+    sector_tower: Color::new(0.0, 0.45, 0.7, 1.0),
+    freeze_tower: Color::new(0.6, 0.85, 0.95, 1.0),
+    bank_tower: Color::new(0.94, 0.89, 0.26, 1.0),
+    sniper_tower: Color::new(0.5, 0.5, 0.5, 1.0),
+    lock_on: Color::new(0.0, 0.45, 0.7, 0.9),
+    tesla_tower: Color::new(0.8, 0.6, 0.7, 1.0),
+    decoy_tower: Color::new(0.8, 0.47, 0.65, 1.0),
+    missile_tower: Color::new(0.84, 0.37, 0.0, 1.0),
+    bouncer_tower: Color::new(0.34, 0.71, 0.91, 1.0),
+    lance_tower: Color::new(0.7, 0.7, 0.7, 1.0),
+    wall: Color::new(0.59, 0.43, 0.31, 1.0),
+    obstacle: Color::new(0.35, 0.3, 0.25, 1.0),
+    build_zone: Color::new(0.0, 0.45, 0.7, 0.2),
+    no_build_zone: Color::new(0.84, 0.37, 0.0, 0.3),
+    range: Color::new(1.0, 1.0, 1.0, 0.39),
+    range_outline: Color::new(1.0, 1.0, 1.0, 0.8),
+    selection: Color::new(1.0, 1.0, 1.0, 0.5),
+    frozen: Color::new(0.34, 0.71, 0.91, 0.5),
+    airstrike: Color::new(0.84, 0.37, 0.0, 0.3),
+    cautious_route: Color::new(0.84, 0.37, 0.0, 0.35),
+    brave_route: Color::new(0.94, 0.89, 0.26, 0.35),
+    bad: Color::new(0.84, 0.37, 0.0, 1.0),
+    good: Color::new(0.0, 0.45, 0.7, 1.0),
+    shapes: true,
+    outline: Color::new(1.0, 1.0, 1.0, 1.0),
+};
+
+const HIGH_CONTRAST: Scheme = Scheme {
+    background: Color::new(0.0, 0.0, 0.0, 1.0),
+    web: Color::new(0.8, 0.8, 0.8, 1.0),
+    route: Color::new(1.0, 1.0, 1.0, 1.0),
+    mud: Color::new(1.0, 0.5, 0.0, 0.9),
+    road: Color::new(1.0, 1.0, 1.0, 0.5),
+    water: Color::new(0.0, 0.5, 1.0, 0.9),
+    test_enemy: Color::new(1.0, 0.0, 1.0, 1.0),
+    brave_enemy: Color::new(1.0, 1.0, 0.0, 1.0),
+    carrier_enemy: Color::new(0.6, 0.4, 1.0, 1.0),
+    swarm_enemy: Color::new(1.0, 0.5, 0.5, 1.0),
+    teleporter_enemy: Color::new(0.5, 1.0, 0.5, 1.0),
+    juggernaut_enemy: Color::new(1.0, 0.3, 0.0, 1.0),
+    bullet: Color::new(0.0, 1.0, 1.0, 1.0),
+    damage_number: Color::new(1.0, 1.0, 1.0, 1.0),
+    critical_hit: Color::new(1.0, 1.0, 0.0, 1.0),
+    test_tower: Color::new(1.0, 1.0, 1.0, 1.0),
+    sector_tower: Color::new(0.3, 0.6, 1.0, 1.0),
+    freeze_tower: Color::new(0.6, 1.0, 1.0, 1.0),
+    bank_tower: Color::new(1.0, 0.9, 0.2, 1.0),
+    sniper_tower: Color::new(0.6, 1.0, 0.4, 1.0),
+    lock_on: Color::new(1.0, 0.0, 1.0, 1.0),
+    tesla_tower: Color::new(0.4, 0.6, 1.0, 1.0),
+    decoy_tower: Color::new(1.0, 0.5, 0.0, 1.0),
+    missile_tower: Color::new(1.0, 0.3, 0.3, 1.0),
+    bouncer_tower: Color::new(0.3, 1.0, 0.3, 1.0),
+    lance_tower: Color::new(1.0, 1.0, 1.0, 1.0),
+    wall: Color::new(0.7, 0.7, 0.7, 1.0),
+    obstacle: Color::new(0.5, 0.5, 0.5, 1.0),
+    build_zone: Color::new(0.0, 1.0, 1.0, 0.2),
+    no_build_zone: Color::new(1.0, 0.0, 1.0, 0.3),
+    range: Color::new(1.0, 1.0, 1.0, 0.25),
+    range_outline: Color::new(1.0, 1.0, 1.0, 1.0),
+    selection: Color::new(1.0, 1.0, 1.0, 0.7),
+    frozen: Color::new(0.0, 1.0, 1.0, 0.6),
+    airstrike: Color::new(1.0, 0.0, 1.0, 0.35),
+    cautious_route: Color::new(1.0, 0.0, 1.0, 0.5),
+    brave_route: Color::new(1.0, 1.0, 0.0, 0.5),
+    bad: Color::new(1.0, 0.0, 1.0, 1.0),
+    good: Color::new(0.0, 1.0, 1.0, 1.0),
+    shapes: true,
+    outline: Color::new(1.0, 1.0, 1.0, 1.0),
+};
+
+thread_local! {
+    static CURRENT: Cell<Palette> = Cell::new(Palette::Standard);
+}
+
+/// Changes the colours everything is drawn in from the next frame
+pub fn set_palette(palette: Palette) {
+    CURRENT.with(|current| current.set(palette));
+}
+
+pub fn palette() -> Palette {
+    CURRENT.with(|current| current.get())
+}
+
+/// The colours to draw with, which every `draw` should use rather than its own colours
+pub fn scheme() -> &'static Scheme {
+    palette().scheme()
+}
@@ -0,0 +1,80 @@
+use ggez::{graphics::Align, Context};
+
+use crate::{
+    renderer::{draw_rounded_rectangle, draw_text, ui_scale},
+    ui::UiTheme,
+    vec2d,
+    vector::Vector,
+    GameState,
+};
+
+/// The size of each button in a tower's panel, before the UI scale
+const BUTTON_SIZE: (f32, f32) = (180.0, 40.0);
+/// The space between the selected tower and its panel, and between the buttons
+const GAP: f32 = 8.0;
+
+/// Where each button in the selected tower's panel is, in window coordinates, as its
+/// top-left corner and size, with its label.
+/// Towers without buttons of their own have no panel.
+fn buttons(state: &GameState) -> Vec<(Vector, Vector, String)> {
+    let Some(tower) = state
+        .selected_tower
+        .and_then(|index| state.world.towers.get(index))
+    else {
+        return vec![];
+    };
+    let scale = ui_scale();
+    let size = vec2d![BUTTON_SIZE.0, BUTTON_SIZE.1] * scale;
+    let edge = state.to_window(tower.position() + vec2d![tower.radius(), 0.0]);
+    let corner = edge + vec2d![GAP, -size.y / 2.0];
+    tower
+        .panel()
+        .into_iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let position = corner + vec2d![0.0, (size.y + GAP * scale) * i as f32];
+            (position, size, label)
+        })
+        .collect()
+}
+
+/// Draws the selected tower's panel, in window coordinates
+pub fn draw_panel(ctx: &mut Context, state: &GameState) {
+    let theme = UiTheme::default();
+    for (position, size, label) in buttons(state) {
+        draw_rounded_rectangle(
+            ctx,
+            position,
+            size,
+            theme.corner_radius,
+            theme.button,
+            Some((theme.border_thickness, theme.panel_border)),
+        );
+        draw_text(
+            ctx,
+            &label,
+            position + vec2d![0.0, (size.y - 20.0 * ui_scale()) / 2.0],
+            Some(20.0),
+            Some((size, Align::Center)),
+            theme.button_text,
+        );
+    }
+}
+
+/// Presses the panel button under a position in the window, if there is one.
+/// Returns whether a button was pressed.
+pub fn click(state: &mut GameState, position: Vector) -> bool {
+    let pressed = buttons(state).into_iter().position(|(corner, size, _)| {
+        position.x >= corner.x
+            && position.y >= corner.y
+            && position.x <= corner.x + size.x
+            && position.y <= corner.y + size.y
+    });
+    match pressed {
+        Some(button) => {
+            state.press_panel_button(button);
+            true
+        }
+        None => false,
+    }
+}
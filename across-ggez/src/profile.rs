@@ -0,0 +1,136 @@
+use ggez::Context;
+
+use crate::{
+    storage::{self, Saver},
+    tower::tower::Tower,
+};
+
+/// Where the profile is saved, inside ggez's user config directory
+const PROFILE_PATH: &str = "/profile.txt";
+
+/// The player's statistics across every game they've played
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub games_played: u32,
+    pub total_kills: u64,
+    pub highest_round: u32,
+    /// Kills made by each kind of tower, by name
+    tower_kills: Vec<(String, u64)>,
+    /// The highest round reached on each map, by name
+    map_bests: Vec<(String, u32)>,
+    /// The best star rating for each round of each map, as (map name, round, stars)
+    round_stars: Vec<(String, u32, u8)>,
+}
+
+impl Profile {
+    /// The tower with the most kills, if any tower has killed anything
+    pub fn favourite_tower(&self) -> Option<&str> {
+        self.tower_kills
+            .iter()
+            .filter(|(_, kills)| *kills > 0)
+            .max_by_key(|(_, kills)| *kills)
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn map_bests(&self) -> &Vec<(String, u32)> {
+        &self.map_bests
+    }
+
+    /// Keeps a round's star rating, if it's the best one yet for that round of the map
+    pub fn record_stars(&mut self, map: &str, round: u32, stars: u8) {
+        match self
+            .round_stars
+            .iter_mut()
+            .find(|(name, x, _)| name == map && *x == round)
+        {
+            Some((_, _, best)) => *best = (*best).max(stars),
+            None => self.round_stars.push((map.to_owned(), round, stars)),
+        }
+    }
+
+    /// The best star rating for every round of a map, added together
+    pub fn total_stars(&self, map: &str) -> u32 {
+        self.round_stars
+            .iter()
+            .filter(|(name, _, _)| name == map)
+            .map(|&(_, _, stars)| stars as u32)
+            .sum()
+    }
+
+    /// Adds a finished game to the totals
+    pub fn record_game(&mut self, map: &str, round: u32, towers: &[Box<dyn Tower>]) {
+        self.games_played += 1;
+        self.highest_round = self.highest_round.max(round);
+        for tower in towers {
+            let kills = tower.kills() as u64;
+            self.total_kills += kills;
+            match self
+                .tower_kills
+                .iter_mut()
+                .find(|(name, _)| name == tower.name())
+            {
+                Some((_, total)) => *total += kills,
+                None => self.tower_kills.push((tower.name().to_owned(), kills)),
+            }
+        }
+        match self.map_bests.iter_mut().find(|(name, _)| name == map) {
+            Some((_, best)) => *best = (*best).max(round),
+            None => self.map_bests.push((map.to_owned(), round)),
+        }
+    }
+
+    /// Loads the saved profile, or an empty one if there isn't one
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut profile = Self::default();
+        let contents = storage::read(ctx, PROFILE_PATH).unwrap_or_default();
+        for line in contents.lines() {
+            let Some((key, value)) = line.rsplit_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+            match key.split_once(':') {
+                Some(("tower", name)) => profile.tower_kills.push((name.to_owned(), value)),
+                Some(("map", name)) => profile.map_bests.push((name.to_owned(), value as u32)),
+                Some(("stars", rest)) => {
+                    if let Some((name, Ok(round))) = rest
+                        .rsplit_once(':')
+                        .map(|(name, round)| (name, round.parse::<u32>()))
+                    {
+                        profile
+                            .round_stars
+                            .push((name.to_owned(), round, value as u8));
+                    }
+                }
+                _ => match key {
+                    "games_played" => profile.games_played = value as u32,
+                    "total_kills" => profile.total_kills = value,
+                    "highest_round" => profile.highest_round = value as u32,
+                    _ => (),
+                },
+            }
+        }
+        profile
+    }
+
+    /// Saves the profile as lines of `key=value`,
+    /// with `tower:Name=kills` and `map:Name=round` for the per-tower and per-map stats,
+    /// and `stars:Name:round=stars` for each round's rating
+    pub fn save(&self, ctx: &Context, saver: &mut Saver) {
+        let mut contents = format!(
+            "games_played={}\ntotal_kills={}\nhighest_round={}\n",
+            self.games_played, self.total_kills, self.highest_round
+        );
+        for (name, kills) in &self.tower_kills {
+            contents.push_str(&format!("tower:{}={}\n", name, kills));
+        }
+        for (name, round) in &self.map_bests {
+            contents.push_str(&format!("map:{}={}\n", name, round));
+        }
+        for (name, round, stars) in &self.round_stars {
+            contents.push_str(&format!("stars:{}:{}={}\n", name, round, stars));
+        }
+        saver.write(ctx, PROFILE_PATH, &contents, "your stats");
+    }
+}
@@ -0,0 +1,112 @@
+use std::f32::consts::PI;
+
+use ggez::{graphics::Color, Context};
+
+use crate::{
+    renderer::{draw_circle, draw_sector, draw_text, text_size},
+    tower::tower::Tower,
+    vector::Vector,
+};
+
+/// How far the stick or mouse has to move from the centre before an option is picked
+const DEAD_ZONE: f32 = 0.5;
+const RADIUS: f32 = 160.0;
+
+/// Something which can be picked from a radial menu
+pub struct RadialOption {
+    pub name: &'static str,
+    pub spawn: fn(Vector) -> Box<dyn Tower>,
+}
+
+/// A ring of options around a point, picked by pointing in their direction.
+/// This is held open with a button, and the option being pointed at when it is
+/// released is chosen, so it works just as well with a controller as a mouse.
+pub struct RadialMenu {
+    options: Vec<RadialOption>,
+    /// Where the menu is drawn, in window coordinates, if it is open
+    centre: Option<Vector>,
+    selected: Option<usize>,
+}
+
+impl RadialMenu {
+    pub fn new(options: Vec<RadialOption>) -> Self {
+        Self {
+            options,
+            centre: None,
+            selected: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.centre.is_some()
+    }
+
+    pub fn centre(&self) -> Option<Vector> {
+        self.centre
+    }
+
+    pub fn open(&mut self, centre: Vector) {
+        self.centre = Some(centre);
+        self.selected = None;
+    }
+
+    /// The angle each option takes up
+    fn slice(&self) -> f32 {
+        2.0 * PI / self.options.len() as f32
+    }
+
+    /// Picks the option in this direction.
+    /// `direction` is normalised so that 1 is a full tilt of the stick.
+    pub fn point(&mut self, direction: Vector) {
+        if self.options.is_empty() || direction.length() < DEAD_ZONE {
+            return;
+        }
+        // option 0 is straight up, and they go round clockwise
+        let angle = (direction.angle() + PI / 2.0 + self.slice() / 2.0).rem_euclid(2.0 * PI);
+        self.selected = Some((angle / self.slice()) as usize % self.options.len());
+    }
+
+    /// Like `point`, but with the mouse, so the dead zone is scaled to the size of the menu
+    pub fn point_at(&mut self, mouse: Vector) {
+        if let Some(centre) = self.centre {
+            self.point((mouse - centre) / (RADIUS / 2.0));
+        }
+    }
+
+    /// Closes the menu, returning the option which was picked
+    pub fn close(&mut self) -> Option<&RadialOption> {
+        self.centre = None;
+        self.selected.take().map(|i| &self.options[i])
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let Some(centre) = self.centre else {
+            return;
+        };
+        draw_circle(ctx, centre, RADIUS, Color::new(0.0, 0.0, 0.0, 0.6));
+        for (i, option) in self.options.iter().enumerate() {
+            let middle = i as f32 * self.slice() - PI / 2.0;
+            if self.selected == Some(i) {
+                draw_sector(
+                    ctx,
+                    centre,
+                    RADIUS,
+                    middle - self.slice() / 2.0,
+                    middle + self.slice() / 2.0,
+                    32,
+                    Color::new(1.0, 1.0, 1.0, 0.3),
+                );
+            }
+            let size = text_size(ctx, option.name, Some(24.0));
+            draw_text(
+                ctx,
+                option.name,
+                centre + Vector::from_polar(middle, RADIUS * 0.65) - size / 2.0,
+                Some(24.0),
+                None,
+                Color::WHITE,
+            );
+        }
+        draw_circle(ctx, centre, 8.0, Color::WHITE);
+    }
+}
@@ -0,0 +1,353 @@
+use std::{cell::Cell, f32::consts::PI};
+
+use ggez::{
+    graphics::{
+        self, Align, Color, DrawMode, DrawParam, Image, MeshBuilder, Rect, Text, TextFragment,
+        Transform,
+    },
+    Context, GameResult,
+};
+
+use crate::{error::report, vec2d, vector::Vector};
+
+/// The length of each dash, and of each gap between them, in dashed lines
+const DASH_LENGTH: f32 = 12.0;
+
+/// The smallest and largest UI scale the player can pick
+pub const UI_SCALE_RANGE: (f32, f32) = (0.75, 1.5);
+
+thread_local! {
+    /// How much bigger menus and text are drawn than they were designed, from the settings
+    static UI_SCALE: Cell<f32> = Cell::new(1.0);
+}
+
+/// Makes menus and text bigger or smaller, so they're readable on any display
+pub fn set_ui_scale(scale: f32) {
+    UI_SCALE.with(|x| x.set(scale.clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1)));
+}
+
+pub fn ui_scale() -> f32 {
+    UI_SCALE.with(Cell::get)
+}
+
+thread_local! {
+    /// Where the origin is moved to and how much everything is scaled by, while a
+    /// miniature is being drawn
+    static MINIATURE: Cell<Option<(Vector, f32)>> = Cell::new(None);
+}
+
+/// Runs `draw` with everything it draws scaled about the origin by `scale`, then moved
+/// so the origin is at `centre`
+pub fn draw_scaled(centre: Vector, scale: f32, draw: impl FnOnce()) {
+    let previous = MINIATURE.with(|x| x.replace(Some((centre, scale))));
+    draw();
+    MINIATURE.with(|x| x.set(previous));
+}
+
+/// Moves and scales a drawable's parameters for the miniature being drawn, if there is one
+fn place(param: DrawParam) -> DrawParam {
+    let Some((centre, factor)) = MINIATURE.with(Cell::get) else {
+        return param;
+    };
+    match param.trans {
+        Transform::Values {
+            dest,
+            rotation,
+            scale,
+            offset,
+        } => DrawParam {
+            trans: Transform::Values {
+                dest: [centre.x + dest.x * factor, centre.y + dest.y * factor].into(),
+                rotation,
+                scale: [scale.x * factor, scale.y * factor].into(),
+                offset,
+            },
+            ..param
+        },
+        Transform::Matrix(_) => param,
+    }
+}
+
+/// Builds a mesh and draws it. Errors are reported rather than stopping the game, so
+/// at worst the mesh is missing.
+pub fn draw_mesh(
+    ctx: &mut Context,
+    builder: GameResult<&mut MeshBuilder>,
+    param: impl Into<DrawParam>,
+) {
+    if let Some(mesh) = report(builder.and_then(|builder| builder.build(ctx))) {
+        report(graphics::draw(ctx, &mesh, place(param.into())));
+    }
+}
+
+/// Draw an image stretched over a rectangle, given its top-left corner and its width and height.
+pub fn draw_image(ctx: &mut Context, image: &Image, position: Vector, size: Vector) {
+    let position: [f32; 2] = position.into();
+    let scale = [
+        size.x / image.width() as f32,
+        size.y / image.height() as f32,
+    ];
+    report(graphics::draw(
+        ctx,
+        image,
+        place(DrawParam::new().dest(position).scale(scale)),
+    ));
+}
+
+/// Draw a rectangle, given its top-left corner and its width and height.
+pub fn draw_rectangle(ctx: &mut Context, position: Vector, size: Vector, colour: Color) {
+    let position: [f32; 2] = position.into();
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().rectangle(
+            DrawMode::fill(),
+            Rect::new(0.0, 0.0, size.x, size.y),
+            Color::WHITE,
+        ),
+        (position, colour),
+    );
+}
+
+/// Draws a bar centred on `centre`, filled `amount` [0-1] of the way from the left
+pub fn draw_progress_bar(
+    ctx: &mut Context,
+    centre: Vector,
+    size: Vector,
+    amount: f32,
+    colour: Color,
+) {
+    let corner = centre - size * 0.5;
+    draw_rectangle(ctx, corner, size, Color::new(0.0, 0.0, 0.0, 0.6));
+    draw_rectangle(
+        ctx,
+        corner,
+        vec2d![size.x * amount.clamp(0.0, 1.0), size.y],
+        colour,
+    );
+}
+
+/// Draw a rectangle with rounded corners, given its top-left corner and its width and height.
+/// If `outline` is given, a border of that thickness and colour is drawn on top.
+pub fn draw_rounded_rectangle(
+    ctx: &mut Context,
+    position: Vector,
+    size: Vector,
+    corner_radius: f32,
+    colour: Color,
+    outline: Option<(f32, Color)>,
+) {
+    let bounds = Rect::new(position.x, position.y, size.x, size.y);
+    let mut builder = MeshBuilder::new();
+    let mut built = builder
+        .rounded_rectangle(DrawMode::fill(), bounds, corner_radius, colour)
+        .map(|_| ());
+    if let Some((thickness, outline_colour)) = outline {
+        built = built.and_then(|_| {
+            builder
+                .rounded_rectangle(
+                    DrawMode::stroke(thickness),
+                    bounds,
+                    corner_radius,
+                    outline_colour,
+                )
+                .map(|_| ())
+        });
+    }
+    draw_mesh(ctx, built.map(|_| &mut builder), DrawParam::default());
+}
+
+/// Draw a circle, given its centre position and its radius.
+pub fn draw_circle(ctx: &mut Context, position: Vector, radius: f32, colour: Color) {
+    let position: [f32; 2] = position.into();
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().circle(DrawMode::fill(), [0.0, 0.0], radius, 0.2, Color::WHITE),
+        (position, colour),
+    );
+}
+
+/// Draw a filled polygon, given its corners in order.
+/// There must be at least 3 points.
+pub fn draw_polygon(ctx: &mut Context, points: &[Vector], colour: Color) {
+    let points: Vec<[f32; 2]> = points.iter().map(|&p| p.into()).collect();
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().polygon(DrawMode::fill(), points.as_slice(), Color::WHITE),
+        DrawParam::from(([0.0, 0.0], colour)),
+    );
+}
+
+/// Draw a five pointed star, pointing up, given its centre and the radius of its points.
+pub fn draw_star(ctx: &mut Context, centre: Vector, radius: f32, colour: Color) {
+    let points: Vec<_> = (0..10)
+        .map(|x| {
+            let radius = if x % 2 == 0 { radius } else { radius / 2.5 };
+            centre + Vector::from_polar(x as f32 * PI / 5.0 - PI / 2.0, radius)
+        })
+        .collect();
+    draw_polygon(ctx, &points, colour);
+}
+
+/// Draw the outline of a circle, given its centre position and its radius.
+pub fn draw_ring(ctx: &mut Context, position: Vector, radius: f32, thickness: f32, colour: Color) {
+    let position: [f32; 2] = position.into();
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().circle(
+            DrawMode::stroke(thickness),
+            [0.0, 0.0],
+            radius,
+            0.2,
+            Color::WHITE,
+        ),
+        (position, colour),
+    );
+}
+
+pub fn draw_sector(
+    ctx: &mut Context,
+    position: Vector,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    triangle_count: usize,
+    colour: Color,
+) {
+    // TODO: I have to make my own arc???
+    let step_size = (end_angle - start_angle) / triangle_count as f32;
+    let triangles: Vec<_> = (0..triangle_count)
+        .map(|i| i as f32 * step_size)
+        .map(|theta| theta + start_angle)
+        .map(|theta| {
+            [
+                vec2d![0.0, 0.0],
+                Vector::from_polar(theta, radius),
+                Vector::from_polar(theta + step_size, radius),
+            ]
+        })
+        .flatten()
+        .collect();
+    let position: [f32; 2] = position.into();
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().triangles(triangles.as_slice(), Color::WHITE),
+        (position, colour),
+    );
+}
+
+/// Draw text, given its top-left corner's position, the font size and the bounds.
+/// The default size is 32px, and every size is multiplied by the UI scale.
+/// The default bounds are infinity (no bounds).
+pub fn draw_text(
+    ctx: &mut Context,
+    text: &str,
+    position: Vector,
+    size: Option<f32>,
+    bounds: Option<(Vector, graphics::Align)>,
+    colour: Color,
+) {
+    let size = match size {
+        Some(x) => x,
+        None => 32.0,
+    } * ui_scale();
+    let mut text = Text::new(TextFragment::new(text).scale(size));
+    match bounds {
+        Some((b, align)) => {
+            let bounds: [f32; 2] = b.into();
+            text.set_bounds(bounds, align);
+        }
+        None => (),
+    }
+    let position: [f32; 2] = position.into();
+    report(graphics::draw(
+        ctx,
+        &text,
+        place(DrawParam::from((position, colour))),
+    ));
+}
+
+/// Get the width and height of some text once drawn, at the given font size.
+/// The default size is 32px, and every size is multiplied by the UI scale.
+pub fn text_size(ctx: &mut Context, text: &str, size: Option<f32>) -> Vector {
+    let size = match size {
+        Some(x) => x,
+        None => 32.0,
+    } * ui_scale();
+    let text = Text::new(TextFragment::new(text).scale(size));
+    let dimensions = text.dimensions(ctx);
+    vec2d![dimensions.w, dimensions.h]
+}
+
+/// Draw a dashed line through some points, in order.
+/// The dashes carry on around corners, rather than starting again at each point.
+pub fn draw_dashed_lines(ctx: &mut Context, points: &[Vector], thickness: f32, colour: Color) {
+    let mut builder = MeshBuilder::new();
+    let mut empty = true;
+    // how far along the whole line the dashes have reached
+    let mut travelled = 0.0;
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let length = a.distance(b);
+        if length == 0.0 {
+            continue;
+        }
+        let direction = (b - a) * (1.0 / length);
+        let mut along = 0.0;
+        while along < length {
+            let step = (DASH_LENGTH - travelled % DASH_LENGTH).min(length - along);
+            let in_dash = (travelled / DASH_LENGTH) as usize % 2 == 0;
+            if in_dash && step > 0.01 {
+                let start: [f32; 2] = (a + direction * along).into();
+                let end: [f32; 2] = (a + direction * (along + step)).into();
+                if report(builder.line(&[start, end], thickness, colour)).is_none() {
+                    return;
+                }
+                empty = false;
+            }
+            along += step;
+            travelled += step;
+        }
+    }
+    // a mesh with nothing in it can't be built
+    if !empty {
+        draw_mesh(ctx, Ok(&mut builder), DrawParam::default());
+    }
+}
+
+/// The points along an arc, from `start_angle` to `end_angle`, for drawing its outline
+pub fn arc_points(
+    centre: Vector,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    segments: usize,
+) -> Vec<Vector> {
+    let step_size = (end_angle - start_angle) / segments as f32;
+    (0..=segments)
+        .map(|i| centre + Vector::from_polar(start_angle + step_size * i as f32, radius))
+        .collect()
+}
+
+pub fn draw_line(ctx: &mut Context, a: Vector, b: Vector, thickness: f32, colour: Color) {
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().line(&[[a.x, a.y], [b.x, b.y]], thickness, colour),
+        DrawParam::from(([0.0, 0.0], Color::WHITE)),
+    );
+}
+
+pub fn draw_joined_lines(ctx: &mut Context, points: Vec<Vector>, thickness: f32, colour: Color) {
+    draw_mesh(
+        ctx,
+        MeshBuilder::new().line(
+            points
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>()
+                .as_slice(),
+            thickness,
+            colour,
+        ),
+        DrawParam::from(([0.0, 0.0], Color::WHITE)),
+    );
+}
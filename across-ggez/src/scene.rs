@@ -0,0 +1,749 @@
+use ggez::{
+    audio::SoundSource,
+    event::{Button, MouseButton},
+    graphics::Color,
+    input::keyboard::{self, KeyCode, KeyMods},
+    Context,
+};
+use rand::Rng;
+
+use crate::{
+    ability::{Ability, AIRSTRIKE_RADIUS},
+    bullets::draw_bullet,
+    debug, developer, draw_summary,
+    enemies::{draw_dead_enemy, draw_enemy, draw_health_bars},
+    fit_world, help,
+    hover::Hovered,
+    kill_reward,
+    layer::{Layer, RenderQueue},
+    motion::reduced_motion,
+    mouse_position,
+    palette::scheme,
+    panel,
+    renderer::{self, draw_circle, draw_progress_bar, draw_rectangle, draw_text, ui_scale},
+    rng::Stream,
+    scenery::{
+        background, draw_build_rules, draw_route, draw_route_previews, draw_texture, draw_web,
+        draw_zone,
+    },
+    towers::{draw_tower, RangeStyle},
+    tr, vec2d,
+    vector::Vector,
+    wave::{Modifier, SpawnEvent},
+    world::DAMAGE_NUMBER_STEPS,
+    GameMode, GameState, MainState, GAME_MENU_INDEX, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+/// Something the player did which the menus didn't use, passed on to the current scene
+#[derive(Debug, Clone, Copy)]
+pub enum Input {
+    /// A mouse button was pressed at a position in the window
+    MouseDown(MouseButton, Vector),
+    /// The mouse wheel was scrolled up (positive) or down (negative)
+    MouseWheel(f32),
+    Key(KeyCode, KeyMods),
+    Gamepad(Button),
+}
+
+/// What the game does in one of its modes.
+/// Each `GameMode` has a scene, which `MainState` hands its events to, so adding a mode
+/// means adding a scene rather than another branch to every `match`.
+pub trait Scene {
+    /// Called when the game switches to this scene
+    fn enter(&self, _state: &mut GameState) {}
+
+    /// Called when the game switches away from this scene
+    fn exit(&self, _state: &mut GameState) {}
+
+    /// Moves the scene on by `steps` simulation steps, which can be fractional
+    fn update(&self, _main: &mut MainState, _ctx: &mut Context, _steps: f32) {}
+
+    /// The colour the window is cleared to before anything is drawn
+    fn background(&self, _state: &GameState) -> Color {
+        scheme().background
+    }
+
+    /// Submits everything the scene shows to the render queue
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>);
+
+    /// Handles input which the menus didn't use. Returns whether the scene used it.
+    fn handle_input(&self, _main: &mut MainState, _ctx: &mut Context, _input: Input) -> bool {
+        false
+    }
+}
+
+impl GameMode {
+    pub fn scene(&self) -> &'static dyn Scene {
+        match self {
+            GameMode::MainMenu => &MainMenuScene,
+            GameMode::Play => &PlayScene,
+            GameMode::Settings => &SettingsScene,
+            GameMode::Stats => &StatsScene,
+            GameMode::Shop => &ShopScene,
+            GameMode::Spectate => &SpectateScene,
+        }
+    }
+}
+
+/// Pans the camera with WASD or the arrow keys
+fn pan_with_keys(main: &mut MainState, ctx: &mut Context, steps: f32) {
+    // keys which are bound to an action don't also pan
+    const PAN_SPEED: f32 = 10.0;
+    let mut pan = Vector::zero();
+    for (keys, direction) in [
+        ([KeyCode::W, KeyCode::Up], vec2d![0.0, -1.0]),
+        ([KeyCode::A, KeyCode::Left], vec2d![-1.0, 0.0]),
+        ([KeyCode::S, KeyCode::Down], vec2d![0.0, 1.0]),
+        ([KeyCode::D, KeyCode::Right], vec2d![1.0, 0.0]),
+    ] {
+        if keys.iter().any(|&key| {
+            keyboard::is_key_pressed(ctx, key) && main.state.key_bindings.action(key).is_none()
+        }) {
+            pan += direction;
+        }
+    }
+    if pan.sqr_length() > 0.0 {
+        main.state
+            .camera
+            .pan(pan * PAN_SPEED * steps / main.state.camera.zoom());
+        main.state.update_world_view();
+    }
+}
+
+/// Zooms the camera towards the mouse with the mouse wheel
+fn zoom_with_wheel(main: &mut MainState, ctx: &mut Context, y: f32) {
+    if y != 0.0 {
+        let focus = main.state.to_world(mouse_position(ctx));
+        main.state
+            .camera
+            .zoom_at(fit_world(main.state.view_size), focus, 1.1f32.powf(y));
+        main.state.update_world_view();
+    }
+}
+
+pub struct MainMenuScene;
+
+impl Scene for MainMenuScene {
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        let state = &main.state;
+        let menu = &main.menus[state.mode.menu_index()];
+        queue.submit(Layer::Ui, move |ctx| menu.borrow().draw(ctx));
+        if let Some(seed) = state.previous_seed {
+            queue.submit(Layer::Hud, move |ctx| {
+                draw_text(
+                    ctx,
+                    &tr!("Last game's seed: {}", seed),
+                    vec2d![16.0, 16.0],
+                    None,
+                    None,
+                    Color::WHITE,
+                )
+            });
+        }
+    }
+}
+
+pub struct SettingsScene;
+
+impl Scene for SettingsScene {
+    /// The UI scale is only applied when leaving the settings, so the menu doesn't change
+    /// size under the slider while it's being dragged
+    fn exit(&self, state: &mut GameState) {
+        renderer::set_ui_scale(state.settings.ui_scale);
+    }
+
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        let menu = &main.menus[main.state.mode.menu_index()];
+        queue.submit(Layer::Ui, move |ctx| {
+            menu.borrow().draw(ctx);
+            main.draw_key_bindings(ctx);
+        });
+    }
+}
+
+pub struct StatsScene;
+
+impl Scene for StatsScene {
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        let menu = &main.menus[main.state.mode.menu_index()];
+        queue.submit(Layer::Ui, move |ctx| {
+            menu.borrow().draw(ctx);
+            main.draw_profile(ctx);
+        });
+    }
+}
+
+/// Watching a simulation of the next wave, which can be sped up and looked around freely
+pub struct SpectateScene;
+
+impl Scene for SpectateScene {
+    fn exit(&self, state: &mut GameState) {
+        state.spectating = None;
+        state.panning = false;
+    }
+
+    fn update(&self, main: &mut MainState, ctx: &mut Context, steps: f32) {
+        if let Some(simulation) = main.state.spectating.as_mut() {
+            main.state.spectate_steps += main.state.spectate_speed * steps;
+            while main.state.spectate_steps >= 1.0 {
+                main.state.spectate_steps -= 1.0;
+                simulation.step();
+            }
+        }
+        pan_with_keys(main, ctx, steps);
+    }
+
+    fn background(&self, state: &GameState) -> Color {
+        background(&state.map.theme)
+    }
+
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        let state = &main.state;
+        let menu = &main.menus[state.mode.menu_index()];
+        let theme = &state.map.theme;
+        if let Some(simulation) = &state.spectating {
+            queue.submit(Layer::Background, move |ctx| {
+                draw_texture(ctx, theme, state.world_view);
+                for obstacle in simulation.web().obstacles() {
+                    draw_zone(ctx, obstacle, scheme().obstacle);
+                }
+            });
+            queue.submit(Layer::Path, move |ctx| {
+                draw_web(ctx, simulation.web(), theme)
+            });
+            let world = simulation.world();
+            queue.submit(Layer::Enemies, move |ctx| {
+                for enemy in world.dying.iter() {
+                    draw_dead_enemy(ctx, enemy);
+                }
+                for enemy in world.enemies.iter() {
+                    draw_enemy(ctx, enemy);
+                }
+                draw_health_bars(ctx, &world.enemies, state.settings.hide_full_health_bars);
+            });
+            queue.submit(Layer::Towers, move |ctx| {
+                for tower in &world.towers {
+                    draw_tower(ctx, tower.as_ref(), state.settings.range_style);
+                }
+            });
+            queue.submit(Layer::Bullets, move |ctx| {
+                for bullet in world.bullets.iter() {
+                    draw_bullet(ctx, bullet);
+                }
+            });
+            queue.submit(Layer::Hud, move |ctx| {
+                let outcome = match simulation.result() {
+                    Some(true) => tr!("(survived)"),
+                    Some(false) => tr!("(lost)"),
+                    None => String::new(),
+                };
+                draw_text(
+                    ctx,
+                    &tr!(
+                        "Spectating round {} - step {} - {} lives left {}",
+                        simulation.round(),
+                        simulation.steps(),
+                        simulation.lives(),
+                        outcome
+                    ),
+                    vec2d![16.0, 16.0],
+                    None,
+                    None,
+                    Color::WHITE,
+                );
+                if let Some(modifier) = simulation.modifier() {
+                    draw_modifier(ctx, modifier, vec2d![16.0, 44.0]);
+                }
+            });
+        }
+        queue.submit(Layer::Ui, move |ctx| menu.borrow().draw(ctx));
+    }
+
+    fn handle_input(&self, main: &mut MainState, ctx: &mut Context, input: Input) -> bool {
+        match input {
+            Input::MouseDown(MouseButton::Middle, _) => main.state.panning = true,
+            Input::MouseWheel(y) => zoom_with_wheel(main, ctx, y),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Playing the game, with time running
+pub struct PlayScene;
+
+impl Scene for PlayScene {
+    /// Anything the player was in the middle of is dropped, so it isn't still going when
+    /// they come back
+    fn exit(&self, state: &mut GameState) {
+        state.cancel_placing();
+        state.hovered = None;
+        state.panning = false;
+    }
+
+    fn update(&self, main: &mut MainState, ctx: &mut Context, steps: f32) {
+        pan_with_keys(main, ctx, steps);
+
+        if main.state.radial_menu.is_open() && main.state.using_controller {
+            main.state.radial_menu.point(main.state.stick);
+        } else if main.state.placing.is_some() {
+            const CURSOR_SPEED: f32 = 12.0;
+            let cursor = match (main.state.using_controller, main.state.hover_position) {
+                (true, Some(cursor)) => cursor + main.state.stick * CURSOR_SPEED,
+                _ => main.state.to_world(mouse_position(ctx)),
+            };
+            main.state.hover_position = Some(cursor);
+        }
+
+        main.state.hovered = Hovered::find(&main.state, main.state.to_world(mouse_position(ctx)));
+
+        if !main.state.show_help {
+            main.state.pending_steps += main.state.settings.game_speed * steps;
+            if main.state.settings.show_win_chance && main.state.win_estimator.update() {
+                if let Some(setup) = main.state.simulation_setup() {
+                    let seed = main.state.rng.stream(Stream::Estimates).gen();
+                    let registry = main.state.tower_registry.clone();
+                    main.state.win_estimator.start(seed, setup, registry);
+                }
+            }
+        }
+        // finishing the game or the round leaves this scene, which stops time
+        while main.state.mode == GameMode::Play
+            && !main.state.show_help
+            && main.state.pending_steps >= 1.0
+        {
+            main.state.pending_steps -= 1.0;
+            // spawn enemies
+            let round = main.state.spawner.round();
+            let event = main.state.spawner.update(main.state.world.enemies.len());
+            let modifier = main.state.spawner.modifier();
+            for kind in event.spawns() {
+                let kind = kind.unwrap_or_else(|| {
+                    main.state
+                        .enemy_mix
+                        .pick(round, main.state.rng.stream(Stream::Spawning))
+                });
+                let enemy = kind.spawn(&main.state.path).with_modifier(modifier);
+                main.state.world.enemies.push(enemy);
+                if let Some(kind) = main.state.versus.next_spawn() {
+                    let enemy = kind.spawn(&main.state.path).with_modifier(modifier);
+                    main.state.world.enemies.push(enemy);
+                }
+            }
+            main.state.round_summary.steps += 1;
+            if event == SpawnEvent::WaveCleared {
+                main.state.toasts.push(tr!("Round {} complete!", round));
+                main.state.finish_round(ctx, round);
+                if !main.state.spawner.is_finished() {
+                    // the next round's modifier is announced before the shop opens
+                    let modifier = main
+                        .state
+                        .spawner
+                        .roll_modifier(main.state.rng.stream(Stream::Mutations));
+                    if let Some(modifier) = modifier {
+                        main.state
+                            .toasts
+                            .push(tr!("Round {}: {}", round + 1, modifier.name()));
+                    }
+                    main.state.open_shop();
+                }
+            }
+            if event == SpawnEvent::Rush {
+                if let Some(klaxon) = main.klaxon.as_mut() {
+                    klaxon.set_volume(main.state.settings.volume);
+                    if let Err(e) = klaxon.play(ctx) {
+                        eprintln!("Failed to play the rush klaxon: {}", e);
+                    }
+                }
+            }
+            main.state.abilities.update();
+            let escaped = main
+                .state
+                .world
+                .movement_system(&mut main.state.path, main.state.abilities.is_frozen());
+            if !main.state.invincible {
+                main.state.lives = main.state.lives.saturating_sub(escaped as u32);
+                main.state.round_summary.lives_lost += escaped as u32;
+            }
+            // in co-op, the host decides when the game is over
+            if main.state.lives == 0 && !main.state.is_client() {
+                main.state.end_game(ctx);
+                break;
+            }
+            let destroyed = main.state.world.siege_system();
+            if !destroyed.is_empty() {
+                main.state.towers_destroyed(destroyed);
+            }
+            main.state.world.repair_system();
+            let kills = main.state.world.combat_system(
+                vec2d![SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
+                main.state.priority_zone.as_ref(),
+                main.state.path.obstacles(),
+                main.state.rng.stream(Stream::Crits),
+            );
+            if main.state.world.crits_landed() > 0 {
+                if let Some(crit_sound) = main.crit_sound.as_mut() {
+                    crit_sound.set_volume(main.state.settings.volume);
+                    if let Err(e) = crit_sound.play(ctx) {
+                        eprintln!("Failed to play the critical hit sound: {}", e);
+                    }
+                }
+            }
+            let earned = kill_reward(main.state.spawner.modifier()) * kills as u64;
+            main.state.money += earned;
+            main.state.round_summary.kills += kills;
+            main.state.round_summary.money_earned += earned;
+        }
+    }
+
+    fn background(&self, state: &GameState) -> Color {
+        background(&state.map.theme)
+    }
+
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        let state = &main.state;
+        let theme = &state.map.theme;
+        queue.submit(Layer::Background, move |ctx| {
+            draw_texture(ctx, theme, state.world_view);
+            for obstacle in state.path.obstacles() {
+                draw_zone(ctx, obstacle, scheme().obstacle);
+            }
+        });
+        queue.submit(Layer::Path, move |ctx| {
+            if state.settings.show_web {
+                draw_web(ctx, &state.path, theme);
+            } else {
+                draw_route(ctx, &state.path, theme);
+            }
+            if state.show_route_previews {
+                draw_route_previews(ctx, &state.path);
+            }
+            if let Some(zone) = &state.priority_zone {
+                draw_zone(ctx, zone, Color::new(1.0, 0.8, 0.0, 0.25));
+            }
+            if state.show_pathfinding {
+                debug::draw_pathfinding(ctx, &state.path);
+            }
+            // where towers can be built is only shown while one is being placed
+            if state.hover_position.is_some() {
+                draw_build_rules(ctx, &state.map.build);
+            }
+        });
+        queue.submit(Layer::Enemies, move |ctx| {
+            for enemy in state.world.dying.iter() {
+                draw_dead_enemy(ctx, enemy);
+            }
+            for enemy in state.world.enemies.iter() {
+                draw_enemy(ctx, enemy);
+            }
+            draw_health_bars(
+                ctx,
+                &state.world.enemies,
+                state.settings.hide_full_health_bars,
+            );
+            if state.abilities.is_frozen() {
+                for enemy in state.world.enemies.iter() {
+                    draw_circle(ctx, enemy.position(), enemy.radius() * 1.3, scheme().frozen);
+                }
+            }
+        });
+        queue.submit(Layer::Towers, move |ctx| {
+            for (i, tower) in state.world.towers.iter().enumerate() {
+                // the selected tower's range is always shown, so it can be aimed
+                let range_style = if state.selected_tower == Some(i) {
+                    RangeStyle::Fill
+                } else {
+                    state.settings.range_style
+                };
+                draw_tower(ctx, tower.as_ref(), range_style);
+                if let Some(progress) = state.world.repair_progress(tower.id()) {
+                    draw_progress_bar(
+                        ctx,
+                        tower.position() - vec2d![0.0, tower.radius() + 24.0],
+                        vec2d![30.0, 5.0],
+                        progress,
+                        scheme().good,
+                    );
+                }
+            }
+            if let Some(tower) = state
+                .selected_tower
+                .and_then(|index| state.world.towers.get(index))
+            {
+                draw_circle(
+                    ctx,
+                    tower.position(),
+                    tower.radius() * 1.5,
+                    scheme().selection,
+                );
+            }
+        });
+        queue.submit(Layer::Bullets, move |ctx| {
+            for bullet in state.world.bullets.iter() {
+                draw_bullet(ctx, bullet);
+            }
+        });
+        queue.submit(Layer::Particles, move |ctx| {
+            if let Some(position) = state.hover_position {
+                draw_circle(ctx, position, 10.0, Color::WHITE);
+            }
+            if state.targeting == Some(Ability::Airstrike) {
+                let position = state.to_world(mouse_position(ctx));
+                draw_circle(ctx, position, AIRSTRIKE_RADIUS, scheme().airstrike);
+            }
+            if let Some(hovered) = state.hovered {
+                hovered.draw_highlight(ctx, state);
+            }
+            for number in state.world.damage_numbers.iter() {
+                // with reduced motion numbers stay still, at full strength, until they go
+                let fade = if reduced_motion() {
+                    0.0
+                } else {
+                    number.age as f32 / DAMAGE_NUMBER_STEPS as f32
+                };
+                let colour = if number.hit.critical {
+                    scheme().critical_hit
+                } else {
+                    scheme().damage_number
+                };
+                draw_text(
+                    ctx,
+                    &format!("{:.0}", number.hit.damage * 100.0),
+                    number.hit.position - vec2d![8.0, 20.0 + 20.0 * fade],
+                    Some(if number.hit.critical { 24.0 } else { 16.0 }),
+                    None,
+                    Color {
+                        a: colour.a * (1.0 - fade),
+                        ..colour
+                    },
+                );
+            }
+        });
+
+        queue.submit(Layer::Hud, move |ctx| {
+            let warning = state.spawner.warning();
+            if warning > 0.0 {
+                // the tint pulses, unless the player has asked for less motion
+                let pulse = if reduced_motion() {
+                    1.0
+                } else {
+                    (warning * 6.0 * std::f32::consts::PI).sin().abs()
+                };
+                draw_rectangle(
+                    ctx,
+                    Vector::zero(),
+                    state.view_size,
+                    Color::new(1.0, 0.0, 0.0, 0.25 * warning * pulse),
+                );
+                draw_text(
+                    ctx,
+                    &tr!("Rush incoming!"),
+                    vec2d![state.view_size.x / 2.0 - 120.0, 16.0],
+                    None,
+                    None,
+                    Color::WHITE,
+                );
+            }
+            // the HUD's lines are spaced out to fit text at the UI scale
+            let hud_scale = ui_scale();
+            let hud_line = |line: f32| {
+                vec2d![
+                    state.view_size.x - 200.0 * hud_scale,
+                    16.0 + 40.0 * hud_scale * line
+                ]
+            };
+            draw_text(
+                ctx,
+                &tr!("Lives: {}", state.lives),
+                hud_line(0.0),
+                None,
+                None,
+                Color::WHITE,
+            );
+            draw_text(
+                ctx,
+                &tr!("Round: {}", state.spawner.round()),
+                hud_line(1.0),
+                None,
+                None,
+                Color::WHITE,
+            );
+            draw_text(
+                ctx,
+                &tr!("Money: {}", state.money),
+                hud_line(2.0),
+                None,
+                None,
+                Color::WHITE,
+            );
+            if let Some(chance) = state
+                .win_estimator
+                .estimate()
+                .filter(|_| state.settings.show_win_chance)
+            {
+                let position = hud_line(3.0);
+                let size = vec2d![180.0, 24.0] * hud_scale;
+                draw_rectangle(ctx, position, size, Color::new(0.2, 0.2, 0.2, 0.8));
+                draw_rectangle(
+                    ctx,
+                    position,
+                    vec2d![size.x * chance, size.y],
+                    scheme().bad_to_good(chance),
+                );
+                draw_text(
+                    ctx,
+                    &tr!("Win chance: {}%", format!("{:.0}", chance * 100.0)),
+                    position + vec2d![4.0, 2.0] * hud_scale,
+                    Some(20.0),
+                    None,
+                    Color::WHITE,
+                );
+            }
+            if let Some(modifier) = state.spawner.modifier() {
+                draw_modifier(ctx, modifier, hud_line(4.0));
+            }
+        });
+
+        queue.submit(Layer::Ui, move |ctx| {
+            main.menus[GAME_MENU_INDEX].borrow().draw(ctx);
+            main.draw_ability_cooldowns(ctx);
+            if let Some(hovered) = state.hovered {
+                let mouse = mouse_position(ctx);
+                hovered.draw_tooltip(ctx, state, mouse);
+            }
+            panel::draw_panel(ctx, state);
+            state.radial_menu.draw(ctx);
+        });
+        let update_time = main.update_time;
+        queue.submit(Layer::Overlay, move |ctx| {
+            if state.show_debug {
+                debug::draw_debug(ctx, state, update_time);
+            }
+            if state.show_help {
+                help::draw_help(ctx, state);
+            }
+        });
+    }
+
+    fn handle_input(&self, main: &mut MainState, ctx: &mut Context, input: Input) -> bool {
+        let state = &mut main.state;
+        match input {
+            Input::MouseDown(MouseButton::Left, position) => {
+                if !panel::click(state, position) {
+                    let position = state.to_world(position);
+                    state.world_click(position);
+                }
+            }
+            Input::MouseDown(MouseButton::Middle, _) => state.panning = true,
+            Input::MouseDown(MouseButton::Right, position) => {
+                state.using_controller = false;
+                state.radial_menu.open(position);
+            }
+            Input::MouseWheel(y) => zoom_with_wheel(main, ctx, y),
+            Input::Key(KeyCode::Escape, _) if state.placing.is_some() => state.cancel_placing(),
+            Input::Key(KeyCode::F1, _) => state.show_help = !state.show_help,
+            Input::Key(KeyCode::Z, keymods) if keymods.contains(KeyMods::CTRL) => {
+                state.undo_placement()
+            }
+            Input::Key(keycode, keymods) if state.developer && keymods.contains(KeyMods::CTRL) => {
+                let cursor = state.to_world(mouse_position(ctx));
+                return developer::handle_key(state, keycode, cursor);
+            }
+            Input::Key(keycode, _) => {
+                if main.current_menu().borrow().is_focused() {
+                    return false;
+                }
+                let Some(action) = main.state.key_bindings.action(keycode) else {
+                    return false;
+                };
+                main.state.perform(action);
+            }
+            Input::Gamepad(Button::LeftTrigger2) => {
+                state.using_controller = true;
+                let centre = state.view_size / 2.0;
+                state.radial_menu.open(centre);
+            }
+            Input::Gamepad(Button::South) if state.placing.is_some() => {
+                if let Some(position) = state.hover_position {
+                    state.world_click(position);
+                }
+            }
+            Input::Gamepad(Button::East) => state.cancel_placing(),
+            Input::Gamepad(Button::Start) => state.set_mode(GameMode::MainMenu),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// The shop between rounds, drawn over the paused game
+pub struct ShopScene;
+
+impl Scene for ShopScene {
+    fn background(&self, state: &GameState) -> Color {
+        background(&state.map.theme)
+    }
+
+    fn draw<'a>(&self, main: &'a MainState, queue: &mut RenderQueue<'a>) {
+        PlayScene.draw(main, queue);
+        let state = &main.state;
+        let menu = &main.menus[state.mode.menu_index()];
+        queue.submit(Layer::Ui, move |ctx| {
+            draw_rectangle(
+                ctx,
+                Vector::zero(),
+                state.view_size,
+                Color::new(0.0, 0.0, 0.0, 0.6),
+            );
+            menu.borrow().draw(ctx);
+            draw_text(
+                ctx,
+                &tr!(
+                    "Shop - round {} is next. Money: {}",
+                    state.spawner.round(),
+                    state.money
+                ),
+                vec2d![16.0, 16.0],
+                None,
+                None,
+                Color::WHITE,
+            );
+            if let Some(modifier) = state.spawner.modifier() {
+                draw_modifier(ctx, modifier, vec2d![16.0, 44.0]);
+            }
+            if let Some(summary) = &state.last_summary {
+                draw_summary(ctx, summary, vec2d![16.0, 72.0]);
+            }
+            if state.session.as_ref().is_some_and(|x| x.is_versus()) {
+                draw_text(
+                    ctx,
+                    &tr!(
+                        "Versus - {} enemies incoming. Income: {} a round",
+                        state.versus.incoming(),
+                        state.versus.income()
+                    ),
+                    vec2d![16.0, state.view_size.y - 56.0],
+                    None,
+                    None,
+                    Color::WHITE,
+                );
+            }
+        });
+    }
+}
+
+/// Names the modifier on a wave, with how it changes the reward for each kill
+fn draw_modifier(ctx: &mut Context, modifier: Modifier, position: Vector) {
+    draw_text(
+        ctx,
+        &tr!(
+            "{} - kills pay {}x",
+            modifier.name(),
+            format!("{:.1}", modifier.reward())
+        ),
+        position,
+        Some(20.0),
+        None,
+        scheme().bad,
+    );
+}
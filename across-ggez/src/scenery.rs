@@ -0,0 +1,197 @@
+use ggez::{
+    graphics::{Color, Rect},
+    Context,
+};
+
+use crate::{
+    build::BuildRules,
+    grid::GridMap,
+    palette::{palette, scheme, Palette},
+    path::{Terrain, Web},
+    pathfind::RouteProfile,
+    polygon::Polygon,
+    renderer::{
+        draw_circle, draw_image, draw_joined_lines, draw_line, draw_polygon, draw_rectangle,
+    },
+    sprite::texture,
+    theme::{NodeStyle, Rgb, Theme},
+    vec2d,
+    vector::Vector,
+};
+
+/// Whether a map's theme is used, rather than the palette's colours
+fn theme_applies() -> bool {
+    palette() == Palette::Standard
+}
+
+/// A theme's colour, if it sets one and it applies
+fn theme_colour(colour: Option<Rgb>) -> Option<Color> {
+    colour
+        .filter(|_| theme_applies())
+        .map(|(r, g, b)| Color::from_rgb(r, g, b))
+}
+
+pub fn background(theme: &Theme) -> Color {
+    theme_colour(theme.background).unwrap_or(scheme().background)
+}
+
+/// The colour of the route and the points
+pub fn path_colour(theme: &Theme) -> Color {
+    theme_colour(theme.path).unwrap_or(scheme().route)
+}
+
+/// Draws a theme's background texture, if there is one, stretched over `area`
+pub fn draw_texture(ctx: &mut Context, theme: &Theme, area: Rect) {
+    let Some(path) = theme.texture.as_deref().filter(|_| theme_applies()) else {
+        return;
+    };
+    if let Some(image) = texture(ctx, path) {
+        draw_image(ctx, &image, vec2d![area.x, area.y], vec2d![area.w, area.h]);
+    }
+}
+
+/// Draws a point of the web in the theme's style
+pub fn draw_node(ctx: &mut Context, theme: &Theme, position: Vector, radius: f32) {
+    let colour = path_colour(theme);
+    match theme.nodes {
+        NodeStyle::Circle => draw_circle(ctx, position, radius, colour),
+        NodeStyle::Square => draw_rectangle(
+            ctx,
+            position - vec2d![radius, radius],
+            vec2d![radius, radius] * 2.0,
+            colour,
+        ),
+        NodeStyle::Diamond => draw_polygon(
+            ctx,
+            &[
+                position + vec2d![0.0, -radius],
+                position + vec2d![radius, 0.0],
+                position + vec2d![0.0, radius],
+                position + vec2d![-radius, 0.0],
+            ],
+            colour,
+        ),
+        NodeStyle::Hidden => (),
+    }
+}
+
+/// The colour terrain is drawn under a connection in, if it isn't plain
+fn terrain_colour(terrain: Terrain) -> Option<Color> {
+    match terrain {
+        Terrain::Plain => None,
+        Terrain::Mud => Some(scheme().mud),
+        Terrain::Road => Some(scheme().road),
+        Terrain::Water => Some(scheme().water),
+    }
+}
+
+/// The colour a profile's route is previewed in
+fn profile_colour(profile: RouteProfile) -> Color {
+    match profile {
+        RouteProfile::Cautious => scheme().cautious_route,
+        RouteProfile::Brave => scheme().brave_route,
+    }
+}
+
+/// Draws a web with every connection, its grid if it has one, and the route
+pub fn draw_web(ctx: &mut Context, web: &Web, theme: &Theme) {
+    if let Some(grid) = web.grid() {
+        draw_grid(ctx, grid);
+    }
+    for connection in 0..web.pathfinder().connections().len() {
+        if let Some(colour) = terrain_colour(web.terrain_of(connection)) {
+            draw_joined_lines(ctx, web.connection_points(connection), 10.0, colour);
+        }
+        draw_joined_lines(ctx, web.connection_points(connection), 2.0, scheme().web);
+        if web.is_one_way(connection) {
+            draw_arrow(ctx, web, connection);
+        }
+    }
+    draw_route(ctx, web, theme);
+}
+
+/// Draws an arrow halfway along a connection, pointing the way it goes
+fn draw_arrow(ctx: &mut Context, web: &Web, connection: usize) {
+    let points = web.connection_points(connection);
+    let (a, b) = (
+        points[(points.len() - 1) / 2],
+        points[(points.len() + 1) / 2],
+    );
+    let middle = (a + b) * 0.5;
+    let direction = (b - a).normalised();
+    draw_polygon(
+        ctx,
+        &[
+            middle + direction * 10.0,
+            middle - direction * 6.0 + direction.clockwise_90deg() * 7.0,
+            middle - direction * 6.0 + direction.anticlockwise_90deg() * 7.0,
+        ],
+        scheme().web,
+    );
+}
+
+/// Draws only a web's route and points, without the other connections
+pub fn draw_route(ctx: &mut Context, web: &Web, theme: &Theme) {
+    let points = web.route().points();
+    points
+        .iter()
+        .skip(1)
+        .enumerate()
+        .for_each(|(i, &x)| draw_line(ctx, points[i], x, 3.5, path_colour(theme)));
+    web.positions()
+        .into_iter()
+        .for_each(|x| draw_node(ctx, theme, x, web.node_radius()));
+}
+
+/// Draws the route each profile will take faintly, in the profile's colour.
+/// Routes shared by several profiles are drawn thicker for each one, so all of them show.
+pub fn draw_route_previews(ctx: &mut Context, web: &Web) {
+    for (i, profile) in RouteProfile::ALL.iter().enumerate().rev() {
+        draw_joined_lines(
+            ctx,
+            web.route_for(*profile).points().clone(),
+            8.0 + 6.0 * i as f32,
+            profile_colour(*profile),
+        );
+    }
+}
+
+/// Draws the lines between a grid's tiles, and shades the solid ones
+fn draw_grid(ctx: &mut Context, grid: &GridMap) {
+    let layout = grid.layout();
+    let size = vec2d![layout.width as f32, layout.height as f32] * layout.tile_size;
+    for x in 0..=layout.width {
+        let x = layout.origin.x + x as f32 * layout.tile_size;
+        let top = vec2d![x, layout.origin.y];
+        draw_line(ctx, top, top + vec2d![0.0, size.y], 1.0, scheme().web);
+    }
+    for y in 0..=layout.height {
+        let y = layout.origin.y + y as f32 * layout.tile_size;
+        let left = vec2d![layout.origin.x, y];
+        draw_line(ctx, left, left + vec2d![size.x, 0.0], 1.0, scheme().web);
+    }
+    let tile = vec2d![layout.tile_size, layout.tile_size];
+    for y in 0..layout.height {
+        for x in (0..layout.width).filter(|&x| grid.is_solid((x, y))) {
+            let corner = layout.origin + vec2d![x as f32, y as f32] * layout.tile_size;
+            draw_rectangle(ctx, corner, tile, scheme().no_build_zone);
+        }
+    }
+}
+
+/// Fills in an area, as long as it's a proper polygon
+pub fn draw_zone(ctx: &mut Context, zone: &Polygon, colour: Color) {
+    if zone.is_valid() {
+        draw_polygon(ctx, zone.points(), colour);
+    }
+}
+
+/// Shades where towers can and can't be built, in world coordinates
+pub fn draw_build_rules(ctx: &mut Context, rules: &BuildRules) {
+    for zone in &rules.zones {
+        draw_zone(ctx, zone, scheme().build_zone);
+    }
+    for zone in &rules.forbidden {
+        draw_zone(ctx, zone, scheme().no_build_zone);
+    }
+}
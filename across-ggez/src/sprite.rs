@@ -0,0 +1,109 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use ggez::{
+    graphics::{self, Color, DrawParam, Image, Rect},
+    Context,
+};
+
+use crate::{animator::Animator, vector::Vector};
+
+thread_local! {
+    /// Every image which has been loaded, by path, so each is only loaded once.
+    /// Images which couldn't be loaded are kept as None, so they aren't tried every frame.
+    static TEXTURES: RefCell<HashMap<String, Option<Image>>> = RefCell::new(HashMap::new());
+}
+
+/// Loads an image from the resources folder, or gets it from the cache if it's been
+/// loaded before
+pub fn texture(ctx: &mut Context, path: &str) -> Option<Image> {
+    TEXTURES.with(|textures| {
+        textures
+            .borrow_mut()
+            .entry(path.to_owned())
+            .or_insert_with(|| match Image::new(ctx, path) {
+                Ok(image) => Some(image),
+                Err(e) => {
+                    eprintln!("Failed to load {}: {}", path, e);
+                    None
+                }
+            })
+            .clone()
+    })
+}
+
+/// A row of frames in a sprite sheet, played one after another
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    /// The row of the sprite sheet the frames are on
+    pub row: usize,
+    pub frames: usize,
+    /// How many simulation steps each frame is shown for
+    pub frame_steps: usize,
+    /// Whether to start again after the last frame, rather than staying on it
+    pub looping: bool,
+}
+
+impl Animation {
+    /// The frame to show after the animation has played for this many steps
+    pub fn frame(&self, steps: usize) -> usize {
+        let frame = steps / self.frame_steps.max(1);
+        if self.looping {
+            frame % self.frames.max(1)
+        } else {
+            frame.min(self.frames.saturating_sub(1))
+        }
+    }
+}
+
+/// An image split into a grid of equally sized frames, with an animation on each row
+#[derive(Debug, Clone, Copy)]
+pub struct SpriteSheet {
+    /// Where the image is in the resources folder, like "/sprites/test_enemy.png"
+    pub path: &'static str,
+    /// The width and height of each frame, in pixels
+    pub frame_size: (f32, f32),
+    pub walk: Animation,
+    pub death: Animation,
+}
+
+/// Draws an animator's current frame from a sprite sheet, centred on `position` and
+/// scaled to `size` wide. Returns false if the sheet couldn't be loaded, so something
+/// else should be drawn instead.
+pub fn draw_frame(
+    ctx: &mut Context,
+    animator: &Animator,
+    sheet: &SpriteSheet,
+    position: Vector,
+    size: f32,
+    colour: Color,
+) -> bool {
+    let Some(image) = texture(ctx, sheet.path) else {
+        return false;
+    };
+    let animation = if animator.dying() {
+        sheet.death
+    } else {
+        sheet.walk
+    };
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let source = Rect::new(
+        animation.frame(animator.steps()) as f32 * sheet.frame_size.0 / width,
+        animation.row as f32 * sheet.frame_size.1 / height,
+        sheet.frame_size.0 / width,
+        sheet.frame_size.1 / height,
+    );
+    let scale = size / sheet.frame_size.0;
+    let flip = if animator.facing_left() { -1.0 } else { 1.0 };
+    let position: [f32; 2] = position.into();
+    graphics::draw(
+        ctx,
+        &image,
+        DrawParam::new()
+            .src(source)
+            .dest(position)
+            .offset([0.5, 0.5])
+            .scale([scale * flip, scale])
+            .color(colour),
+    )
+    .is_ok()
+}
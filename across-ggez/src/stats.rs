@@ -0,0 +1,62 @@
+use ggez::Context;
+use serde::Serialize;
+
+use crate::{storage::Saver, tower::tower::Tower};
+
+const CSV_PATH: &str = "/tower_stats.csv";
+const JSON_PATH: &str = "/tower_stats.json";
+
+/// One tower in the JSON export
+#[derive(Serialize)]
+struct TowerRecord {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    x: f32,
+    y: f32,
+    shots_fired: usize,
+    damage: f32,
+    kills: usize,
+    money_value: u64,
+}
+
+/// Writes what every tower did this game to a CSV file and a JSON file,
+/// in ggez's user config directory, for players who want to look through their runs.
+/// The files are saved in the background.
+pub fn export_tower_stats(ctx: &Context, saver: &mut Saver, towers: &[Box<dyn Tower>]) {
+    let mut csv = String::from("type,x,y,shots_fired,damage,kills,money_value\n");
+    let mut records = Vec::with_capacity(towers.len());
+    for tower in towers {
+        let stats = tower.stats();
+        let position = tower.position();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            tower.name(),
+            position.x,
+            position.y,
+            stats.shots_fired,
+            stats.damage,
+            stats.kills,
+            tower.price()
+        ));
+        records.push(TowerRecord {
+            kind: tower.name(),
+            x: position.x,
+            y: position.y,
+            shots_fired: stats.shots_fired,
+            damage: stats.damage,
+            kills: stats.kills,
+            money_value: tower.price(),
+        });
+    }
+    // numbers JSON can't hold, like an infinite damage total, are written as null
+    let json = match serde_json::to_string_pretty(&records) {
+        Ok(json) => json + "\n",
+        Err(e) => {
+            eprintln!("Failed to write the tower stats as JSON: {}", e);
+            return;
+        }
+    };
+
+    saver.export(ctx, CSV_PATH, &csv, "the tower stats spreadsheet");
+    saver.export(ctx, JSON_PATH, &json, "the tower stats JSON");
+}
@@ -0,0 +1,241 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver, Sender},
+    thread,
+};
+
+use ggez::{filesystem, Context};
+
+use crate::{
+    lang::{language, set_language, translate, Language},
+    tr,
+};
+
+/// The first line of every file saved with a checksum. Files without it were saved before
+/// checksums were added.
+const FORMAT_LINE: &str = "format=1\n";
+/// Starts the last line of every file saved with a checksum
+const CHECKSUM_KEY: &str = "checksum=";
+
+/// Where a file really is, from a path inside ggez's user config directory like "/profile.txt"
+fn real_path(ctx: &Context, path: &str) -> PathBuf {
+    filesystem::user_config_dir(ctx).join(path.trim_start_matches('/'))
+}
+
+/// `path` with `suffix` added to its whole file name, so "stats.csv" and "stats.json"
+/// don't share a "stats.bak"
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// FNV-1a, which is plenty to notice a file which was cut short or scrambled
+fn checksum(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Adds a checksum to the end of some text, as the last line, so `read` can tell if the
+/// file it's saved in has been damaged. The format line at the start tells `read` that
+/// the checksum must be there.
+fn with_checksum(contents: &str) -> String {
+    let mut body = contents.to_owned();
+    if !body.is_empty() && !body.ends_with('\n') {
+        body.push('\n');
+    }
+    let sum = checksum(&body);
+    format!("{}{}{}{:016x}\n", FORMAT_LINE, body, CHECKSUM_KEY, sum)
+}
+
+/// Replaces a file in one step, so a crash part way through can never leave it half
+/// written. The contents go to a temporary file first, which is then renamed over the old
+/// file. The old file is kept as a `.bak`, in case the new one turns out to be damaged.
+fn replace(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let temporary = with_suffix(path, ".tmp");
+    let mut file = fs::File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    if path.exists() {
+        fs::copy(path, with_suffix(path, ".bak"))?;
+    }
+    fs::rename(&temporary, path)
+}
+
+/// Loads a file saved with `Saver::write`, without its checksum.
+/// If the file is missing or damaged, the backup of the last good version is loaded.
+/// Files saved before checksums were added are trusted as they are.
+pub fn read(ctx: &Context, path: &str) -> Option<String> {
+    let path = real_path(ctx, path);
+    [path.clone(), with_suffix(&path, ".bak")]
+        .iter()
+        .find_map(|path| verify(&fs::read_to_string(path).ok()?))
+}
+
+/// Checks the checksum at the end of a file, and returns the rest of the file if it matches.
+/// A file which starts with the format line but has lost its checksum was cut short, so
+/// it's rejected, while a file without the format line predates checksums.
+fn verify(text: &str) -> Option<String> {
+    let (text, formatted) = match text.strip_prefix(FORMAT_LINE) {
+        Some(text) => (text, true),
+        // a save cut off part way through its format line is damaged too
+        None if FORMAT_LINE.starts_with(text) => return None,
+        None => (text, false),
+    };
+    let start = text
+        .rfind(CHECKSUM_KEY)
+        .filter(|&i| i == 0 || text[..i].ends_with('\n'));
+    let Some(start) = start else {
+        return (!formatted).then(|| text.to_owned());
+    };
+    let (body, sum) = text.split_at(start);
+    let sum = u64::from_str_radix(sum[CHECKSUM_KEY.len()..].trim(), 16).ok()?;
+    (sum == checksum(body)).then(|| body.to_owned())
+}
+
+/// A file waiting to be saved on the background thread
+struct SaveJob {
+    path: PathBuf,
+    /// Exactly what goes in the file, including any checksum
+    contents: String,
+    /// What's being saved, for the message when it's done, such as "your stats"
+    description: &'static str,
+    /// Whether to tell the player when it's saved, rather than only if it fails
+    announce: bool,
+}
+
+/// Saves files on a background thread, so a slow disk never holds up a frame.
+/// Files are saved in the order they're queued, and the thread is only started once
+/// something needs saving.
+#[derive(Default)]
+pub struct Saver {
+    jobs: Option<Sender<SaveJob>>,
+    /// For each finished job which should be shown to the player, whether it succeeded
+    /// and what was saved
+    results: Option<Receiver<Option<(bool, &'static str)>>>,
+    /// How many jobs have been queued but haven't finished yet
+    pending: usize,
+}
+
+impl Saver {
+    fn queue(&mut self, job: SaveJob) {
+        if self.jobs.is_none() {
+            let (job_sender, jobs) = channel::<SaveJob>();
+            let (results, result_receiver) = channel();
+            thread::spawn(move || {
+                for job in jobs {
+                    let result = match replace(&job.path, &job.contents) {
+                        Ok(()) if job.announce => Some((true, job.description)),
+                        Ok(()) => None,
+                        Err(e) => {
+                            eprintln!("Failed to save {}: {}", job.description, e);
+                            Some((false, job.description))
+                        }
+                    };
+                    if results.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+            self.jobs = Some(job_sender);
+            self.results = Some(result_receiver);
+        }
+        if self.jobs.as_ref().unwrap().send(job).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    /// Saves a file with a checksum, which `read` can load back
+    pub fn write(&mut self, ctx: &Context, path: &str, contents: &str, description: &'static str) {
+        self.queue(SaveJob {
+            path: real_path(ctx, path),
+            contents: with_checksum(contents),
+            description,
+            announce: false,
+        });
+    }
+
+    /// Saves a file exactly as given, without a checksum, for files another program reads
+    /// back such as ggez's window config
+    pub fn write_plain(
+        &mut self,
+        ctx: &Context,
+        path: &str,
+        contents: &str,
+        description: &'static str,
+    ) {
+        self.queue(SaveJob {
+            path: real_path(ctx, path),
+            contents: contents.to_owned(),
+            description,
+            announce: false,
+        });
+    }
+
+    /// Saves a file exactly as given, for other programs to read, and tells the player
+    /// when it's done
+    pub fn export(&mut self, ctx: &Context, path: &str, contents: &str, description: &'static str) {
+        self.queue(SaveJob {
+            path: real_path(ctx, path),
+            contents: contents.to_owned(),
+            description,
+            announce: true,
+        });
+    }
+
+    /// The messages from every job which has finished since this was last called.
+    /// They're written here rather than on the background thread, which doesn't know
+    /// the player's language.
+    pub fn messages(&mut self) -> Vec<String> {
+        let Some(results) = &self.results else {
+            return vec![];
+        };
+        let finished: Vec<_> = results.try_iter().collect();
+        self.pending -= finished.len();
+        finished
+            .into_iter()
+            .flatten()
+            .map(|(saved, description)| {
+                if saved {
+                    tr!("Saved {}", translate(description))
+                } else {
+                    tr!("Couldn't save {}", translate(description))
+                }
+            })
+            .collect()
+    }
+
+    /// Waits for every queued job to finish, such as before the game closes
+    pub fn flush(&mut self) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        while self.pending > 0 && results.recv().is_ok() {
+            self.pending -= 1;
+        }
+    }
+}
+
+/// Where the chosen language is saved, inside ggez's user config directory
+const LANGUAGE_PATH: &str = "/language.txt";
+
+/// Switches to the language the player chose last time, if they chose one
+pub fn load_language(ctx: &Context) {
+    if let Some(language) = read(ctx, LANGUAGE_PATH)
+        .as_deref()
+        .and_then(Language::from_code)
+    {
+        set_language(language);
+    }
+}
+
+/// Saves the current language, so the game starts in it next time
+pub fn save_language(ctx: &Context, saver: &mut Saver) {
+    saver.write(ctx, LANGUAGE_PATH, language().code(), "your language");
+}
@@ -0,0 +1,41 @@
+use crate::tr;
+
+/// How a round went, shown in the shop before the next round
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundSummary {
+    pub round: usize,
+    pub kills: usize,
+    pub lives_lost: u32,
+    pub money_earned: u64,
+    /// How many simulation steps the round took
+    pub steps: usize,
+}
+
+impl RoundSummary {
+    /// The most stars a round can be rated
+    pub const MAX_STARS: u8 = 3;
+
+    /// 3 stars for losing no lives, 2 for losing a couple, and 1 for losing more
+    pub fn stars(&self) -> u8 {
+        match self.lives_lost {
+            0 => 3,
+            1..=2 => 2,
+            _ => 1,
+        }
+    }
+
+    /// A line of text for each stat
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            tr!("Round {} summary", self.round),
+            tr!("Enemies killed: {}", self.kills),
+            tr!("Lives lost: {}", self.lives_lost),
+            tr!("Money earned: {}", self.money_earned),
+            // there are 60 steps a second at normal speed
+            tr!(
+                "Time taken: {}s",
+                format!("{:.1}", self.steps as f32 / 60.0)
+            ),
+        ]
+    }
+}
@@ -0,0 +1,70 @@
+use ggez::{graphics::Color, Context};
+
+use crate::{
+    renderer::{draw_rounded_rectangle, draw_text, text_size},
+    vec2d,
+    vector::Vector,
+};
+
+/// How long each notification is shown for, in seconds
+const LIFETIME: f32 = 3.0;
+/// How long notifications take to fade out at the end of their lifetime, in seconds
+const FADE_TIME: f32 = 0.5;
+const TEXT_SIZE: f32 = 24.0;
+const MARGIN: f32 = 16.0;
+
+#[derive(Debug, Clone)]
+struct Toast {
+    text: String,
+    /// Seconds since the notification was shown
+    age: f32,
+}
+
+/// Short messages shown in the bottom right corner, which fade out after a few seconds
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: impl Into<String>) {
+        self.toasts.push(Toast {
+            text: text.into(),
+            age: 0.0,
+        });
+    }
+
+    /// Ages every notification by `delta` seconds, and removes any which have expired
+    pub fn update(&mut self, delta: f32) {
+        self.toasts.iter_mut().for_each(|toast| toast.age += delta);
+        self.toasts.retain(|toast| toast.age < LIFETIME);
+    }
+
+    /// Draws the notifications stacked upwards from the corner, newest at the bottom.
+    /// This should be drawn in window coordinates.
+    pub fn draw(&self, ctx: &mut Context, view_size: Vector) {
+        let mut bottom = view_size.y - MARGIN;
+        for toast in self.toasts.iter().rev() {
+            let alpha = ((LIFETIME - toast.age) / FADE_TIME).min(1.0);
+            let size = text_size(ctx, &toast.text, Some(TEXT_SIZE)) + vec2d![16.0, 16.0];
+            let position = vec2d![view_size.x - MARGIN - size.x, bottom - size.y];
+            draw_rounded_rectangle(
+                ctx,
+                position,
+                size,
+                8.0,
+                Color::new(0.0, 0.0, 0.0, 0.8 * alpha),
+                None,
+            );
+            draw_text(
+                ctx,
+                &toast.text,
+                position + vec2d![8.0, 8.0],
+                Some(TEXT_SIZE),
+                None,
+                Color::new(1.0, 1.0, 1.0, alpha),
+            );
+            bottom = position.y - MARGIN / 2.0;
+        }
+    }
+}
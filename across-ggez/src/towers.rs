@@ -0,0 +1,313 @@
+use std::f32::consts::PI;
+
+use ggez::{graphics::Color, Context};
+
+use crate::{
+    bullets::draw_bullet,
+    palette::scheme,
+    renderer::{
+        arc_points, draw_circle, draw_dashed_lines, draw_line, draw_polygon, draw_progress_bar,
+        draw_rectangle, draw_ring, draw_scaled, draw_sector, draw_star,
+    },
+    tower::tower::{Tower, TowerLook},
+    vec2d,
+    vector::Vector,
+};
+
+/// How the ranges of placed towers are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangeStyle {
+    /// Filled in, which is clearest with only a few towers
+    #[default]
+    Fill,
+    /// Just a dashed line around the edge, so many ranges can overlap and stay readable
+    Outline,
+    Hidden,
+}
+
+impl RangeStyle {
+    pub const ALL: [RangeStyle; 3] = [RangeStyle::Fill, RangeStyle::Outline, RangeStyle::Hidden];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RangeStyle::Fill => "Filled",
+            RangeStyle::Outline => "Outlined",
+            RangeStyle::Hidden => "Hidden",
+        }
+    }
+
+    /// The style after this one, for cycling through them in the settings
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|x| x == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// How thick the dashed outlines of ranges are
+const RANGE_OUTLINE_THICKNESS: f32 = 2.0;
+
+/// Draws what a tower can see.
+/// Ranges which see nothing aren't drawn, and neither are ranges which see the whole map,
+/// as covering it would hide everything else.
+pub fn draw_range(ctx: &mut Context, tower: &dyn Tower, style: RangeStyle) {
+    let (position, range) = (tower.position(), tower.range());
+    let radius = range.radius();
+    if radius <= 0.0 || !radius.is_finite() {
+        return;
+    }
+    match (range.direction(), range.fov()) {
+        (Some(direction), Some(fov)) => {
+            let start_angle = direction - fov / 2.0;
+            let end_angle = direction + fov / 2.0;
+            match style {
+                RangeStyle::Fill => draw_sector(
+                    ctx,
+                    position,
+                    radius,
+                    start_angle,
+                    end_angle,
+                    200,
+                    scheme().range,
+                ),
+                RangeStyle::Outline => {
+                    let mut points = vec![position];
+                    points.extend(arc_points(position, radius, start_angle, end_angle, 32));
+                    points.push(position);
+                    draw_dashed_lines(
+                        ctx,
+                        &points,
+                        RANGE_OUTLINE_THICKNESS,
+                        scheme().range_outline,
+                    );
+                }
+                RangeStyle::Hidden => (),
+            }
+        }
+        _ => match style {
+            RangeStyle::Fill => draw_circle(ctx, position, radius, scheme().range),
+            RangeStyle::Outline => draw_dashed_lines(
+                ctx,
+                &arc_points(position, radius, 0.0, 2.0 * PI, 64),
+                RANGE_OUTLINE_THICKNESS,
+                scheme().range_outline,
+            ),
+            RangeStyle::Hidden => (),
+        },
+    }
+}
+
+/// Draws a small star above a tower for each veterancy level it has reached
+fn draw_rank(ctx: &mut Context, position: Vector, radius: f32, level: usize) {
+    const STAR_RADIUS: f32 = 6.0;
+    for i in 0..level {
+        let centre = position
+            + vec2d![
+                (i as f32 - (level - 1) as f32 / 2.0) * STAR_RADIUS * 2.5,
+                -radius - STAR_RADIUS * 2.0
+            ];
+        draw_star(ctx, centre, STAR_RADIUS, Color::from_rgb(255, 215, 0));
+    }
+}
+
+/// Draws a health bar under a tower, once it's been damaged
+fn draw_health(ctx: &mut Context, tower: &dyn Tower) {
+    let health = tower.health().clamp(0.0, 1.0);
+    if health >= 1.0 {
+        return;
+    }
+    draw_progress_bar(
+        ctx,
+        tower.position() + vec2d![0.0, tower.radius() + 6.0],
+        vec2d![24.0, 4.0],
+        health,
+        scheme().bad_to_good(health),
+    );
+}
+
+/// The colour each kind of tower is drawn in
+fn colour(look: &TowerLook) -> Color {
+    match look {
+        TowerLook::Wall => scheme().wall,
+        TowerLook::Test => scheme().test_tower,
+        TowerLook::Sector => scheme().sector_tower,
+        TowerLook::Freeze { .. } => scheme().freeze_tower,
+        TowerLook::Bank => scheme().bank_tower,
+        TowerLook::Sniper { .. } => scheme().sniper_tower,
+        TowerLook::Tesla { .. } => scheme().tesla_tower,
+        TowerLook::Decoy => scheme().decoy_tower,
+        TowerLook::Missile => scheme().missile_tower,
+        TowerLook::Bouncer => scheme().bouncer_tower,
+        TowerLook::Lance => scheme().lance_tower,
+    }
+}
+
+/// Draws the mark inside a tower which tells its kind apart by shape, for palettes
+/// which don't rely on colour
+fn draw_shape(ctx: &mut Context, tower: &dyn Tower, look: &TowerLook) {
+    let (position, radius) = (tower.position(), tower.radius());
+    let colour = scheme().background;
+    match look {
+        TowerLook::Wall => {
+            // a cross
+            let corner = vec2d![radius, radius] * 0.6;
+            let other = vec2d![corner.x, -corner.y];
+            draw_line(ctx, position - corner, position + corner, 4.0, colour);
+            draw_line(ctx, position - other, position + other, 4.0, colour);
+        }
+        // a hole in the middle
+        TowerLook::Test => draw_circle(ctx, position, radius * 0.4, colour),
+        TowerLook::Sector => {
+            // a triangle pointing the way the tower looks
+            let angle = tower.range().direction().unwrap_or_default();
+            let forward = vec2d![angle.cos(), angle.sin()] * radius * 0.7;
+            let side = forward.clockwise_90deg() * 0.6;
+            draw_polygon(
+                ctx,
+                &[
+                    position + forward,
+                    position - forward * 0.5 + side,
+                    position - forward * 0.5 - side,
+                ],
+                colour,
+            );
+        }
+        TowerLook::Freeze { .. } => {
+            // a snowflake
+            for i in 0..3 {
+                let angle = i as f32 * PI / 3.0;
+                let arm = vec2d![angle.cos(), angle.sin()] * radius * 0.7;
+                draw_line(ctx, position - arm, position + arm, 2.0, colour);
+            }
+        }
+        TowerLook::Bank => {
+            // a square in the middle, like a coin
+            let size = vec2d![radius, radius] * 0.5;
+            draw_rectangle(ctx, position - size, size * 2.0, colour);
+        }
+        TowerLook::Sniper { .. } => {
+            // crosshairs
+            for arm in [vec2d![1.0, 0.0], vec2d![0.0, 1.0]] {
+                let arm = arm * radius * 0.7;
+                draw_line(ctx, position - arm, position + arm, 2.0, colour);
+            }
+        }
+        // a coil
+        TowerLook::Tesla { .. } => draw_ring(ctx, position, radius * 0.5, 2.0, colour),
+        TowerLook::Decoy => {
+            // a target
+            draw_ring(ctx, position, radius * 0.6, 2.0, colour);
+            draw_circle(ctx, position, radius * 0.2, colour);
+        }
+        TowerLook::Missile => {
+            // a ring of holes, like a launcher
+            for i in 0..4 {
+                let angle = PI / 4.0 + PI / 2.0 * i as f32;
+                draw_circle(
+                    ctx,
+                    position + vec2d![angle.cos(), angle.sin()] * radius * 0.55,
+                    radius * 0.2,
+                    colour,
+                );
+            }
+        }
+        TowerLook::Bouncer => {
+            // a diamond, like a ball bouncing off a corner
+            let across = vec2d![radius * 0.5, 0.0];
+            let down = vec2d![0.0, radius * 0.5];
+            draw_polygon(
+                ctx,
+                &[
+                    position - down,
+                    position + across,
+                    position + down,
+                    position - across,
+                ],
+                colour,
+            );
+        }
+        TowerLook::Lance => {
+            // a bar across the middle, like a lance
+            let along = vec2d![radius * 0.7, 0.0];
+            draw_line(ctx, position - along, position + along, 3.0, colour);
+        }
+    }
+}
+
+/// Draws a tower, with its range and its bullets.
+/// `range_style` is how to draw what the tower can see.
+pub fn draw_tower(ctx: &mut Context, tower: &dyn Tower, range_style: RangeStyle) {
+    let look = tower.look();
+    let (position, radius) = (tower.position(), tower.radius());
+    // walls and banks can't see anything, and a freeze tower's field is switched off
+    // while it's repaired
+    if !matches!(
+        look,
+        TowerLook::Wall | TowerLook::Bank | TowerLook::Freeze { active: false }
+    ) {
+        draw_range(ctx, tower, range_style);
+    }
+    tower
+        .bullets()
+        .borrow()
+        .iter()
+        .for_each(|x| draw_bullet(ctx, x));
+    if let TowerLook::Tesla { strikes, flash, .. } = &look {
+        if *flash > 0.0 {
+            let colour = Color {
+                a: *flash,
+                ..scheme().tesla_tower
+            };
+            for &strike in strikes {
+                // a zigzag, kinked sideways halfway along
+                let middle = (position + strike) / 2.0
+                    + (strike - position).clockwise_90deg().normalised() * 6.0;
+                draw_line(ctx, position, middle, 2.0, colour);
+                draw_line(ctx, middle, strike, 2.0, colour);
+            }
+        }
+    }
+    match look {
+        TowerLook::Wall => {
+            let size = vec2d![radius, radius];
+            draw_rectangle(ctx, position - size, size * 2.0, scheme().wall);
+        }
+        _ => draw_circle(ctx, position, radius, colour(&look)),
+    }
+    if scheme().shapes {
+        draw_shape(ctx, tower, &look);
+    }
+    match look {
+        TowerLook::Sniper {
+            locking_on: Some((target, locked)),
+        } => {
+            // the ring closes in on the target as the lock gets closer
+            draw_ring(
+                ctx,
+                target,
+                12.0 + 24.0 * (1.0 - locked),
+                2.0,
+                scheme().lock_on,
+            );
+            draw_line(ctx, position, target, 1.0, scheme().lock_on);
+        }
+        // below where the health bar goes
+        TowerLook::Tesla { charge, .. } => draw_progress_bar(
+            ctx,
+            position + vec2d![0.0, radius + 12.0],
+            vec2d![24.0, 4.0],
+            charge,
+            scheme().tesla_tower,
+        ),
+        _ => (),
+    }
+    draw_rank(ctx, position, radius, tower.level());
+    draw_health(ctx, tower);
+}
+
+/// Draws a tower and its range shrunk to fit in a square `size` wide, such as on a
+/// shop button. The tower should be at the origin.
+pub fn draw_miniature(ctx: &mut Context, tower: &dyn Tower, centre: Vector, size: f32) {
+    let extent = tower.range().radius().max(tower.radius());
+    let scale = size / (2.0 * extent);
+    draw_scaled(centre, scale, || draw_tower(ctx, tower, RangeStyle::Fill));
+}
@@ -0,0 +1,1352 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+    time::{Duration, Instant},
+};
+
+use ggez::{
+    graphics::{self, Color},
+    Context,
+};
+
+use crate::{
+    lang::translate,
+    renderer::{draw_rectangle, draw_rounded_rectangle, draw_text, text_size, ui_scale},
+    vec2d,
+    vector::{Transform, Vector},
+    MainState, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+/// The colours and sizes used to draw every UI element.
+/// Menus own a theme, and their elements draw with their parent's theme.
+#[derive(Debug, Clone)]
+pub struct UiTheme {
+    pub button: Color,
+    pub button_hovered: Color,
+    pub button_pressed: Color,
+    pub button_text: Color,
+    pub panel: Color,
+    pub panel_border: Color,
+    pub label_text: Color,
+    pub input_background: Color,
+    pub input_focused: Color,
+    pub input_text: Color,
+    pub placeholder_text: Color,
+    pub accent: Color,
+    pub track: Color,
+    /// Drawn over elements which can't be used
+    pub disabled: Color,
+    /// Prices the player can't afford, and the flash when they try to buy something anyway
+    pub refused: Color,
+    pub font_size: f32,
+    /// The space between the edge of a panel or input box and its contents
+    pub padding: f32,
+    pub corner_radius: f32,
+    pub border_thickness: f32,
+}
+
+impl UiTheme {
+    /// The background colour of a button in the given state
+    pub fn button_colour(&self, hovered: bool, pressed: bool) -> Color {
+        if pressed {
+            self.button_pressed
+        } else if hovered {
+            self.button_hovered
+        } else {
+            self.button
+        }
+    }
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            button: Color::from_rgb(200, 200, 200),
+            button_hovered: Color::from_rgb(230, 230, 230),
+            button_pressed: Color::from_rgb(150, 150, 150),
+            button_text: Color::BLACK,
+            panel: Color::new(0.1, 0.1, 0.15, 0.8),
+            panel_border: Color::from_rgb(90, 90, 110),
+            label_text: Color::WHITE,
+            input_background: Color::new(0.8, 0.8, 0.8, 1.0),
+            input_focused: Color::WHITE,
+            input_text: Color::BLACK,
+            placeholder_text: Color::new(0.4, 0.4, 0.4, 1.0),
+            accent: Color::from_rgb(60, 120, 220),
+            track: Color::new(0.5, 0.5, 0.5, 1.0),
+            disabled: Color::new(0.2, 0.2, 0.2, 0.7),
+            refused: Color::from_rgb(230, 40, 40),
+            font_size: 32.0,
+            padding: 8.0,
+            corner_radius: 8.0,
+            border_thickness: 2.0,
+        }
+    }
+}
+
+/// How long a button flashes for when the player tries to buy something they can't afford
+const REFUSED_FLASH: Duration = Duration::from_millis(400);
+
+/// Gets the menu a UI element belongs to.
+/// Elements are owned by their menu, so it must still exist.
+fn upgrade<T>(parent: &Weak<RefCell<Menu<T>>>) -> Rc<RefCell<Menu<T>>> {
+    parent.upgrade().expect("A UI element outlived its menu")
+}
+
+/// Which point of the window a top-level menu is positioned relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Centre,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far across and down the window this anchor is, normalised [0-1]
+    pub fn fraction(&self) -> Vector {
+        match self {
+            Anchor::TopLeft => vec2d!(0.0, 0.0),
+            Anchor::Top => vec2d!(0.5, 0.0),
+            Anchor::TopRight => vec2d!(1.0, 0.0),
+            Anchor::Left => vec2d!(0.0, 0.5),
+            Anchor::Centre => vec2d!(0.5, 0.5),
+            Anchor::Right => vec2d!(1.0, 0.5),
+            Anchor::BottomLeft => vec2d!(0.0, 1.0),
+            Anchor::Bottom => vec2d!(0.5, 1.0),
+            Anchor::BottomRight => vec2d!(1.0, 1.0),
+        }
+    }
+}
+
+pub enum UIElement<T> {
+    Button(Button<T>),
+    DragButton(DragButton<T>),
+    TextInput(TextInput<T>),
+    Slider(Slider<T>),
+    Checkbox(Checkbox<T>),
+    Menu(Rc<RefCell<Menu<T>>>),
+}
+
+impl<T> UIElement<T> {
+    pub fn position(&self) -> Vector {
+        match self {
+            UIElement::Button(x) => vec2d!(x.x(), x.y()),
+            UIElement::DragButton(x) => vec2d!(x.x(), x.y()),
+            UIElement::TextInput(x) => vec2d!(x.x(), x.y()),
+            UIElement::Slider(x) => vec2d!(x.x(), x.y()),
+            UIElement::Checkbox(x) => vec2d!(x.x(), x.y()),
+            UIElement::Menu(x) => x.borrow().position(),
+        }
+    }
+
+    pub fn size(&self) -> Vector {
+        match self {
+            UIElement::Button(x) => vec2d!(x.width(), x.height()),
+            UIElement::DragButton(x) => vec2d!(x.width(), x.height()),
+            UIElement::TextInput(x) => vec2d!(x.width(), x.height()),
+            UIElement::Slider(x) => vec2d!(x.width(), x.height()),
+            UIElement::Checkbox(x) => vec2d!(x.width(), x.height()),
+            UIElement::Menu(x) => x.borrow().size(),
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        match self {
+            UIElement::Button(x) => x.draw(ctx),
+            UIElement::DragButton(x) => x.draw(ctx),
+            UIElement::TextInput(x) => x.draw(ctx),
+            UIElement::Slider(x) => x.draw(ctx),
+            UIElement::Checkbox(x) => x.draw(ctx),
+            UIElement::Menu(x) => x.borrow().draw(ctx),
+        }
+    }
+
+    /// Every input method returns whether the input was consumed,
+    /// in which case nothing underneath should also respond to it.
+    pub fn input_at(&self, position: Vector, state: &mut T) -> bool {
+        match self {
+            UIElement::Button(x) => x.input_at(position, state),
+            UIElement::DragButton(x) => x.input_start(position, state),
+            UIElement::TextInput(x) => x.input_at(position, state),
+            UIElement::Slider(x) => x.input_start(position, state),
+            UIElement::Checkbox(x) => x.input_at(position, state),
+            UIElement::Menu(x) => x.borrow().input_at(position, state),
+        }
+    }
+
+    /// The mouse has been pressed
+    pub fn input_start(&self, position: Vector, state: &mut T) -> bool {
+        match self {
+            UIElement::DragButton(x) => x.input_start(position, state),
+            UIElement::Slider(x) => x.input_start(position, state),
+            UIElement::Menu(x) => x.borrow().input_start(position, state),
+            _ => self.input_at(position, state),
+        }
+    }
+
+    /// The mouse has moved, whether or not it is pressed.
+    /// This is consumed if something is being dragged.
+    pub fn input_moved(&self, position: Vector, movement: Vector, state: &mut T) -> bool {
+        match self {
+            UIElement::Button(x) => x.input_moved(position),
+            UIElement::DragButton(x) => x.input_moved(position, movement, state),
+            UIElement::Slider(x) => x.input_moved(position, movement, state),
+            UIElement::Menu(x) => x.borrow().input_moved(position, movement, state),
+            _ => false,
+        }
+    }
+
+    /// The mouse has been released.
+    /// This is consumed if it ends a drag.
+    pub fn input_released(&self, position: Vector, state: &mut T) -> bool {
+        match self {
+            UIElement::Button(x) => x.input_released(),
+            UIElement::DragButton(x) => x.input_released(position, state),
+            UIElement::Slider(x) => x.input_released(position, state),
+            UIElement::Menu(x) => x.borrow().input_released(position, state),
+            _ => false,
+        }
+    }
+
+    pub fn text_input(&self, character: char, state: &mut T) -> bool {
+        match self {
+            UIElement::TextInput(x) => x.text_input(character, state),
+            UIElement::Menu(x) => x.borrow().text_input(character, state),
+            _ => false,
+        }
+    }
+
+    pub fn backspace(&self, state: &mut T) -> bool {
+        match self {
+            UIElement::TextInput(x) => x.backspace(state),
+            UIElement::Menu(x) => x.borrow().backspace(state),
+            _ => false,
+        }
+    }
+
+    /// Whether this, or anything inside it, is taking keyboard input
+    pub fn is_focused(&self) -> bool {
+        match self {
+            UIElement::TextInput(x) => x.is_focused(),
+            UIElement::Menu(x) => x.borrow().is_focused(),
+            _ => false,
+        }
+    }
+
+    /// Takes keyboard focus away from this, and anything inside it
+    pub fn blur(&self) {
+        match self {
+            UIElement::TextInput(x) => x.blur(),
+            UIElement::Menu(x) => x.borrow().blur(),
+            _ => (),
+        }
+    }
+
+    /// Elements with a higher z index are drawn on top, and receive input first.
+    /// Only nested menus can be raised, everything else is at 0.
+    pub fn z_index(&self) -> i32 {
+        match self {
+            UIElement::Menu(x) => x.borrow().z_index(),
+            _ => 0,
+        }
+    }
+}
+
+/// What a button does when it is clicked
+enum ButtonCallback<T> {
+    Plain(fn(&mut T)),
+    /// For buttons generated from a list, which need to know which item they're for
+    Indexed(fn(usize, &mut T), usize),
+}
+
+pub struct Button<T> {
+    parent: Weak<RefCell<Menu<T>>>,
+    position: Vector,
+    size: Vector,
+    callback: ButtonCallback<T>,
+    text: String,
+    hovered: Cell<bool>,
+    pressed: Cell<bool>,
+}
+
+impl<T> Button<T> {
+    pub fn x(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .x
+    }
+    pub fn y(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .y
+    }
+    pub fn width(&self) -> f32 {
+        self.size.x * upgrade(&self.parent).borrow().scale()
+    }
+    pub fn height(&self) -> f32 {
+        self.size.y * upgrade(&self.parent).borrow().scale()
+    }
+
+    pub fn new(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        callback: fn(&mut T),
+        text: &str,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            position,
+            size,
+            parent,
+            callback: ButtonCallback::Plain(callback),
+            text: text.to_owned(),
+            hovered: Cell::new(false),
+            pressed: Cell::new(false),
+        }
+    }
+
+    /// A button whose callback is also given `index`,
+    /// so one function can be shared by a row of buttons generated from a list
+    pub fn new_indexed(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        callback: fn(usize, &mut T),
+        index: usize,
+        text: &str,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            callback: ButtonCallback::Indexed(callback, index),
+            ..Self::new(position, size, parent, |_| (), text)
+        }
+    }
+
+    pub fn is_hovered(&self, mouse: Vector) -> bool {
+        self.x() <= mouse.x
+            && self.y() <= mouse.y
+            && self.x() + self.width() >= mouse.x
+            && self.y() + self.height() >= mouse.y
+    }
+
+    pub fn click(&self, state: &mut T) {
+        match self.callback {
+            ButtonCallback::Plain(callback) => callback(state),
+            ButtonCallback::Indexed(callback, index) => callback(index, state),
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = upgrade(&self.parent).borrow().theme();
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(self.x(), self.y()),
+            vec2d!(self.width(), self.height()),
+            theme.corner_radius,
+            theme.button_colour(self.hovered.get(), self.pressed.get()),
+            None,
+        );
+
+        draw_text(
+            ctx,
+            &translate(&self.text),
+            vec2d!(
+                self.x(),
+                self.y() + (self.height() - theme.font_size * ui_scale()) / 2.0
+            ),
+            Some(theme.font_size),
+            Some((vec2d!(self.width(), self.height()), graphics::Align::Center)),
+            theme.button_text,
+        );
+    }
+
+    pub fn input_at(&self, position: Vector, state: &mut T) -> bool {
+        if self.is_hovered(position) {
+            self.pressed.set(true);
+            self.click(state);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn input_moved(&self, position: Vector) -> bool {
+        self.hovered.set(self.is_hovered(position));
+        false
+    }
+
+    pub fn input_released(&self) -> bool {
+        self.pressed.set(false);
+        false
+    }
+}
+
+impl<T> Into<UIElement<T>> for Button<T> {
+    fn into(self) -> UIElement<T> {
+        UIElement::Button(self)
+    }
+}
+
+/// A single line of editable text.
+/// Clicking on it gives it focus, and clicking anywhere else takes focus away.
+/// While focused, typed characters are appended and backspace removes the last one.
+pub struct TextInput<T> {
+    parent: Weak<RefCell<Menu<T>>>,
+    position: Vector,
+    size: Vector,
+    /// Called with the new text whenever it changes
+    callback: fn(&str, &mut T),
+    text: RefCell<String>,
+    placeholder: String,
+    max_length: usize,
+    focused: Cell<bool>,
+}
+
+impl<T> TextInput<T> {
+    pub fn x(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .x
+    }
+    pub fn y(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .y
+    }
+    pub fn width(&self) -> f32 {
+        self.size.x * upgrade(&self.parent).borrow().scale()
+    }
+    pub fn height(&self) -> f32 {
+        self.size.y * upgrade(&self.parent).borrow().scale()
+    }
+
+    pub fn new(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        callback: fn(&str, &mut T),
+        placeholder: &str,
+        max_length: usize,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            position,
+            size,
+            parent,
+            callback,
+            text: RefCell::new(String::new()),
+            placeholder: placeholder.to_owned(),
+            max_length,
+            focused: Cell::new(false),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.text.borrow().clone()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused.get()
+    }
+
+    pub fn is_hovered(&self, mouse: Vector) -> bool {
+        self.x() <= mouse.x
+            && self.y() <= mouse.y
+            && self.x() + self.width() >= mouse.x
+            && self.y() + self.height() >= mouse.y
+    }
+
+    pub fn input_at(&self, position: Vector, _state: &mut T) -> bool {
+        self.focused.set(self.is_hovered(position));
+        self.focused.get()
+    }
+
+    pub fn blur(&self) {
+        self.focused.set(false);
+    }
+
+    pub fn text_input(&self, character: char, state: &mut T) -> bool {
+        if !self.focused.get() {
+            return false;
+        }
+        // backspace and friends arrive here on some platforms, they're handled separately
+        if !character.is_control() && self.text.borrow().chars().count() < self.max_length {
+            self.text.borrow_mut().push(character);
+            let callback = self.callback;
+            callback(&self.text.borrow(), state);
+        }
+        true
+    }
+
+    pub fn backspace(&self, state: &mut T) -> bool {
+        if !self.focused.get() {
+            return false;
+        }
+        if self.text.borrow_mut().pop().is_some() {
+            let callback = self.callback;
+            callback(&self.text.borrow(), state);
+        }
+        true
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = upgrade(&self.parent).borrow().theme();
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(self.x(), self.y()),
+            vec2d!(self.width(), self.height()),
+            theme.corner_radius,
+            if self.focused.get() {
+                theme.input_focused
+            } else {
+                theme.input_background
+            },
+            Some((theme.border_thickness, theme.panel_border)),
+        );
+
+        let text = self.text.borrow();
+        let text_position = vec2d!(
+            self.x() + theme.padding,
+            self.y() + (self.height() - theme.font_size * ui_scale()) / 2.0
+        );
+        if text.is_empty() && !self.focused.get() {
+            draw_text(
+                ctx,
+                &translate(&self.placeholder),
+                text_position,
+                Some(theme.font_size),
+                None,
+                theme.placeholder_text,
+            );
+        } else {
+            draw_text(
+                ctx,
+                &text,
+                text_position,
+                Some(theme.font_size),
+                None,
+                theme.input_text,
+            );
+        }
+
+        if self.focused.get() {
+            let cursor_x = text_position.x + text_size(ctx, &text, Some(theme.font_size)).x;
+            draw_rectangle(
+                ctx,
+                vec2d!(cursor_x, text_position.y),
+                vec2d!(2.0, theme.font_size * ui_scale()),
+                theme.input_text,
+            );
+        }
+    }
+}
+
+impl<T> Into<UIElement<T>> for TextInput<T> {
+    fn into(self) -> UIElement<T> {
+        UIElement::TextInput(self)
+    }
+}
+
+/// What dragging from a button costs
+pub struct Price<T> {
+    pub amount: u64,
+    /// Called when the player tries to drag from the button without enough money
+    pub on_refused: fn(&mut T),
+}
+
+/// A button which can be dragged from, such as to place a tower.
+/// Each callback is given where the drag started.
+/// Buttons with a price are greyed out, and can't be dragged from, while the player can't
+/// afford them.
+pub struct DragButton<T> {
+    parent: Weak<RefCell<Menu<T>>>,
+    position: Vector,
+    size: Vector,
+    /// Called with (start, state) when the drag begins
+    on_start: fn(Vector, &mut T),
+    /// Called with (start, position, movement, state) as the mouse moves
+    on_moved: fn(Vector, Vector, Vector, &mut T),
+    /// Called with (start, position, state) when the mouse is released
+    on_released: fn(Vector, Vector, &mut T),
+    text: String,
+    /// Draws a picture of what's dragged, given its centre and size, instead of the text
+    icon: Option<fn(&mut Context, Vector, f32)>,
+    /// What dragging from this costs, shown under the icon
+    price: Option<Price<T>>,
+    /// Whether the player has enough money to pay the price
+    affordable: Cell<bool>,
+    /// When the player last tried to drag from this without enough money
+    refused: Cell<Option<Instant>>,
+    drag_start: Cell<Option<Vector>>,
+    hovered: Cell<bool>,
+}
+
+impl<T> DragButton<T> {
+    pub fn x(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .x
+    }
+    pub fn y(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .y
+    }
+    pub fn width(&self) -> f32 {
+        self.size.x * upgrade(&self.parent).borrow().scale()
+    }
+    pub fn height(&self) -> f32 {
+        self.size.y * upgrade(&self.parent).borrow().scale()
+    }
+
+    pub fn new(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        on_start: fn(Vector, &mut T),
+        on_moved: fn(Vector, Vector, Vector, &mut T),
+        on_released: fn(Vector, Vector, &mut T),
+        text: &str,
+        icon: Option<fn(&mut Context, Vector, f32)>,
+        price: Option<Price<T>>,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            position,
+            size,
+            parent,
+            on_start,
+            on_moved,
+            on_released,
+            text: text.to_owned(),
+            icon,
+            price,
+            affordable: Cell::new(true),
+            refused: Cell::new(None),
+            drag_start: Cell::new(None),
+            hovered: Cell::new(false),
+        }
+    }
+
+    pub fn is_hovered(&self, mouse: Vector) -> bool {
+        self.x() <= mouse.x
+            && self.y() <= mouse.y
+            && self.x() + self.width() >= mouse.x
+            && self.y() + self.height() >= mouse.y
+    }
+
+    /// Tells the button how much money the player has, so it knows if they can afford it
+    pub fn set_money(&self, money: u64) {
+        self.affordable.set(
+            self.price
+                .as_ref()
+                .map_or(true, |price| price.amount <= money),
+        );
+    }
+
+    /// Clicks on a button the player can't afford are still consumed, but nothing is dragged
+    pub fn input_start(&self, position: Vector, state: &mut T) -> bool {
+        if !self.is_hovered(position) {
+            false
+        } else if let Some(price) = self.price.as_ref().filter(|_| !self.affordable.get()) {
+            self.refused.set(Some(Instant::now()));
+            let on_refused = price.on_refused;
+            on_refused(state);
+            true
+        } else {
+            self.drag_start.set(Some(position));
+            let on_start = self.on_start;
+            on_start(position, state);
+            true
+        }
+    }
+
+    pub fn input_moved(&self, position: Vector, movement: Vector, state: &mut T) -> bool {
+        self.hovered.set(self.is_hovered(position));
+        if let Some(start) = self.drag_start.get() {
+            let on_moved = self.on_moved;
+            on_moved(start, position, movement, state);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn input_released(&self, position: Vector, state: &mut T) -> bool {
+        if let Some(start) = self.drag_start.take() {
+            let on_released = self.on_released;
+            on_released(start, position, state);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = upgrade(&self.parent).borrow().theme();
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(self.x(), self.y()),
+            vec2d!(self.width(), self.height()),
+            theme.corner_radius,
+            theme.button_colour(self.hovered.get(), self.drag_start.get().is_some()),
+            Some((theme.border_thickness, theme.accent)),
+        );
+
+        match self.icon {
+            Some(icon) => {
+                // the icon fills the top of the button, leaving room for the price below it
+                let size = self.width().min(self.height()) * 0.6;
+                let centre = vec2d!(
+                    self.x() + self.width() / 2.0,
+                    self.y() + theme.padding + size / 2.0
+                );
+                icon(ctx, centre, size);
+            }
+            None => draw_text(
+                ctx,
+                &translate(&self.text),
+                vec2d!(
+                    self.x(),
+                    self.y() + (self.height() - theme.font_size * ui_scale()) / 2.0
+                ),
+                Some(theme.font_size),
+                Some((vec2d!(self.width(), self.height()), graphics::Align::Center)),
+                theme.button_text,
+            ),
+        }
+        if !self.affordable.get() {
+            draw_rounded_rectangle(
+                ctx,
+                vec2d!(self.x(), self.y()),
+                vec2d!(self.width(), self.height()),
+                theme.corner_radius,
+                theme.disabled,
+                None,
+            );
+        }
+        if let Some(price) = &self.price {
+            let font_size = theme.font_size * 0.6;
+            let colour = if self.affordable.get() {
+                theme.button_text
+            } else {
+                theme.refused
+            };
+            draw_text(
+                ctx,
+                &price.amount.to_string(),
+                vec2d!(
+                    self.x(),
+                    self.y() + self.height() - theme.padding - font_size * ui_scale()
+                ),
+                Some(font_size),
+                Some((vec2d!(self.width(), self.height()), graphics::Align::Center)),
+                colour,
+            );
+        }
+        // the flash fades out, starting from when the player was refused
+        let flash = self.refused.get().map_or(0.0, |refused| {
+            1.0 - refused.elapsed().as_secs_f32() / REFUSED_FLASH.as_secs_f32()
+        });
+        if flash > 0.0 {
+            let mut outline = theme.refused;
+            outline.a *= flash;
+            let mut colour = outline;
+            colour.a *= 0.6;
+            draw_rounded_rectangle(
+                ctx,
+                vec2d!(self.x(), self.y()),
+                vec2d!(self.width(), self.height()),
+                theme.corner_radius,
+                colour,
+                Some((theme.border_thickness * 2.0, outline)),
+            );
+        }
+    }
+}
+
+impl<T> Into<UIElement<T>> for DragButton<T> {
+    fn into(self) -> UIElement<T> {
+        UIElement::DragButton(self)
+    }
+}
+
+/// A horizontal track with a thumb which can be dragged along it.
+/// The position of the thumb is mapped onto a range of values.
+pub struct Slider<T> {
+    parent: Weak<RefCell<Menu<T>>>,
+    position: Vector,
+    size: Vector,
+    /// Called with the new value whenever the thumb moves
+    callback: fn(f32, &mut T),
+    text: String,
+    min: f32,
+    max: f32,
+    value: Cell<f32>,
+    dragging: Cell<bool>,
+}
+
+impl<T> Slider<T> {
+    pub fn x(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .x
+    }
+    pub fn y(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .y
+    }
+    pub fn width(&self) -> f32 {
+        self.size.x * upgrade(&self.parent).borrow().scale()
+    }
+    pub fn height(&self) -> f32 {
+        self.size.y * upgrade(&self.parent).borrow().scale()
+    }
+
+    pub fn new(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        callback: fn(f32, &mut T),
+        text: &str,
+        min: f32,
+        max: f32,
+        value: f32,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            position,
+            size,
+            parent,
+            callback,
+            text: text.to_owned(),
+            min,
+            max,
+            value: Cell::new(value.clamp(min, max)),
+            dragging: Cell::new(false),
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value.get()
+    }
+
+    /// How far along the track the thumb is, normalised [0-1]
+    fn fraction(&self) -> f32 {
+        (self.value.get() - self.min) / (self.max - self.min)
+    }
+
+    pub fn is_hovered(&self, mouse: Vector) -> bool {
+        self.x() <= mouse.x
+            && self.y() <= mouse.y
+            && self.x() + self.width() >= mouse.x
+            && self.y() + self.height() >= mouse.y
+    }
+
+    /// Moves the thumb underneath the mouse
+    fn set_from_mouse(&self, mouse: Vector, state: &mut T) {
+        let fraction = ((mouse.x - self.x()) / self.width()).clamp(0.0, 1.0);
+        self.value.set(self.min + fraction * (self.max - self.min));
+        let callback = self.callback;
+        callback(self.value.get(), state);
+    }
+
+    pub fn input_start(&self, position: Vector, state: &mut T) -> bool {
+        if self.is_hovered(position) {
+            self.dragging.set(true);
+            self.set_from_mouse(position, state);
+        }
+        self.dragging.get()
+    }
+
+    pub fn input_moved(&self, position: Vector, _movement: Vector, state: &mut T) -> bool {
+        if self.dragging.get() {
+            self.set_from_mouse(position, state);
+        }
+        self.dragging.get()
+    }
+
+    pub fn input_released(&self, _position: Vector, _state: &mut T) -> bool {
+        self.dragging.replace(false)
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = upgrade(&self.parent).borrow().theme();
+        let track_height = self.height() / 4.0;
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(self.x(), self.y() + (self.height() - track_height) / 2.0),
+            vec2d!(self.width(), track_height),
+            track_height / 2.0,
+            theme.track,
+            None,
+        );
+        let thumb_width = self.height() / 2.0;
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(
+                self.x() + self.fraction() * self.width() - thumb_width / 2.0,
+                self.y()
+            ),
+            vec2d!(thumb_width, self.height()),
+            theme.corner_radius.min(thumb_width / 2.0),
+            theme.button_colour(false, self.dragging.get()),
+            Some((theme.border_thickness, theme.accent)),
+        );
+
+        draw_text(
+            ctx,
+            &format!("{}: {:.2}", translate(&self.text), self.value.get()),
+            vec2d!(
+                self.x(),
+                self.y() - theme.font_size * ui_scale() - theme.padding
+            ),
+            Some(theme.font_size),
+            None,
+            theme.label_text,
+        );
+    }
+}
+
+impl<T> Into<UIElement<T>> for Slider<T> {
+    fn into(self) -> UIElement<T> {
+        UIElement::Slider(self)
+    }
+}
+
+/// A box which is ticked or unticked when clicked
+pub struct Checkbox<T> {
+    parent: Weak<RefCell<Menu<T>>>,
+    position: Vector,
+    size: Vector,
+    /// Called with the new value whenever it is toggled
+    callback: fn(bool, &mut T),
+    text: String,
+    checked: Cell<bool>,
+}
+
+impl<T> Checkbox<T> {
+    pub fn x(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .x
+    }
+    pub fn y(&self) -> f32 {
+        upgrade(&self.parent)
+            .borrow()
+            .transform()
+            .apply(self.position)
+            .y
+    }
+    pub fn width(&self) -> f32 {
+        self.size.x * upgrade(&self.parent).borrow().scale()
+    }
+    pub fn height(&self) -> f32 {
+        self.size.y * upgrade(&self.parent).borrow().scale()
+    }
+
+    pub fn new(
+        position: Vector,
+        size: Vector,
+        parent: Weak<RefCell<Menu<T>>>,
+        callback: fn(bool, &mut T),
+        text: &str,
+        checked: bool,
+    ) -> Self
+    where
+        T: Sized,
+    {
+        Self {
+            position,
+            size,
+            parent,
+            callback,
+            text: text.to_owned(),
+            checked: Cell::new(checked),
+        }
+    }
+
+    pub fn checked(&self) -> bool {
+        self.checked.get()
+    }
+
+    pub fn is_hovered(&self, mouse: Vector) -> bool {
+        self.x() <= mouse.x
+            && self.y() <= mouse.y
+            && self.x() + self.width() >= mouse.x
+            && self.y() + self.height() >= mouse.y
+    }
+
+    pub fn input_at(&self, position: Vector, state: &mut T) -> bool {
+        if self.is_hovered(position) {
+            self.checked.set(!self.checked.get());
+            let callback = self.callback;
+            callback(self.checked.get(), state);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = upgrade(&self.parent).borrow().theme();
+        draw_rounded_rectangle(
+            ctx,
+            vec2d!(self.x(), self.y()),
+            vec2d!(self.width(), self.height()),
+            theme.corner_radius,
+            theme.input_focused,
+            Some((theme.border_thickness, theme.panel_border)),
+        );
+        if self.checked.get() {
+            let inset = self.width().min(self.height()) / 5.0;
+            draw_rectangle(
+                ctx,
+                vec2d!(self.x() + inset, self.y() + inset),
+                vec2d!(self.width() - 2.0 * inset, self.height() - 2.0 * inset),
+                theme.accent,
+            );
+        }
+
+        draw_text(
+            ctx,
+            &translate(&self.text),
+            vec2d!(
+                self.x() + self.width() + 2.0 * theme.padding,
+                self.y() + (self.height() - theme.font_size * ui_scale()) / 2.0
+            ),
+            Some(theme.font_size),
+            None,
+            theme.label_text,
+        );
+    }
+}
+
+impl<T> Into<UIElement<T>> for Checkbox<T> {
+    fn into(self) -> UIElement<T> {
+        UIElement::Checkbox(self)
+    }
+}
+
+pub struct Menu<T> {
+    /// The offset from the anchor, or from the parent menu's position if this is nested
+    position: Vector,
+    /// Only used by top-level menus
+    anchor: Anchor,
+    scale: f32,
+    /// The size of the window, which top-level menus are laid out in
+    viewport: Vector,
+    pub elements: Vec<UIElement<T>>,
+    parent: Option<Weak<RefCell<Menu<T>>>>,
+    /// If this is None, the parent's theme is used
+    theme: Option<Rc<UiTheme>>,
+    /// Where this is stacked amongst its siblings, if it is nested
+    z_index: i32,
+}
+
+impl<T> Menu<T> {
+    pub fn new(
+        position: Vector,
+        anchor: Anchor,
+        scale: f32,
+        parent: Option<Weak<RefCell<Menu<T>>>>,
+    ) -> Self {
+        Self {
+            position,
+            anchor,
+            scale,
+            viewport: vec2d!(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32),
+            elements: Vec::new(),
+            parent,
+            theme: None,
+            z_index: 0,
+        }
+    }
+
+    /// Creates a menu inside another one, positioned and scaled relative to it.
+    /// This can be done at any point, such as when opening a dialog.
+    pub fn new_child(
+        parent: &Rc<RefCell<Menu<T>>>,
+        position: Vector,
+        scale: f32,
+    ) -> Rc<RefCell<Menu<T>>> {
+        let child = Rc::new(RefCell::new(Menu::new(
+            position,
+            Anchor::TopLeft,
+            scale,
+            Some(Rc::downgrade(parent)),
+        )));
+        parent
+            .borrow_mut()
+            .add_elements(vec![UIElement::Menu(Rc::clone(&child))]);
+        child
+    }
+
+    /// Removes a menu which was added with `new_child`
+    pub fn remove_child(&mut self, child: &Rc<RefCell<Menu<T>>>) {
+        self.elements.retain(|element| match element {
+            UIElement::Menu(x) => !Rc::ptr_eq(x, child),
+            _ => true,
+        });
+    }
+
+    /// The menu this is nested inside, if there is one and it still exists
+    pub fn parent(&self) -> Option<Rc<RefCell<Menu<T>>>> {
+        self.parent.as_ref().and_then(Weak::upgrade)
+    }
+
+    /// There *must* be a better way
+    /// TODO: find a better way
+    pub fn add_elements(&mut self, elements: Vec<UIElement<T>>) {
+        self.elements.reserve(elements.len());
+        for element in elements {
+            self.elements.push(element);
+        }
+        self.sort_elements();
+    }
+
+    /// Keeps the elements in drawing order, lowest z index first.
+    /// Elements with the same z index stay in the order they were added.
+    fn sort_elements(&mut self) {
+        self.elements.sort_by_key(|element| element.z_index());
+    }
+
+    pub fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    /// Moves a nested menu above or below its siblings
+    pub fn set_z_index(menu: &Rc<RefCell<Menu<T>>>, z_index: i32) {
+        menu.borrow_mut().z_index = z_index;
+        let parent = menu.borrow().parent();
+        if let Some(parent) = parent {
+            parent.borrow_mut().sort_elements();
+        }
+    }
+
+    /// Converts positions in this menu into positions in the window
+    pub fn transform(&self) -> Transform {
+        let local = Transform::new(self.position, 0.0, self.scale);
+        match self.parent() {
+            Some(parent) => parent.borrow().transform().then(local),
+            None => {
+                let anchor = self.anchor.fraction();
+                Transform::new(
+                    vec2d!(self.viewport.x * anchor.x, self.viewport.y * anchor.y),
+                    0.0,
+                    self.viewport_scale(),
+                )
+                .then(local)
+            }
+        }
+    }
+
+    pub fn position(&self) -> Vector {
+        self.transform().translation
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.transform().scale
+    }
+
+    /// Top-level menus are designed at SCREEN_WIDTH x SCREEN_HEIGHT,
+    /// and are shrunk or grown to fit in the window without stretching,
+    /// then by the UI scale the player picked.
+    fn viewport_scale(&self) -> f32 {
+        (self.viewport.x / SCREEN_WIDTH as f32).min(self.viewport.y / SCREEN_HEIGHT as f32)
+            * ui_scale()
+    }
+
+    /// Called when the window is resized, so top-level menus can lay themselves out again.
+    /// Nested menus follow their parent, so don't need to be told.
+    pub fn set_viewport(&mut self, viewport: Vector) {
+        self.viewport = viewport;
+    }
+
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+
+    /// Returns (top_left, bottom_right)
+    pub fn bounds(&self) -> (Vector, Vector) {
+        let initial: Vector = self.elements[0].position();
+        let initial: (Vector, Vector) = (initial, initial);
+        self.elements.iter().fold(initial, |bounds, element| {
+            let position = element.position();
+            let size = element.size();
+            (bounds.0.min(position), bounds.1.max(position + size))
+        })
+    }
+
+    pub fn size(&self) -> Vector {
+        let bounds = self.bounds();
+        bounds.1 - bounds.0
+    }
+
+    /// The theme this menu and its elements are drawn with
+    pub fn theme(&self) -> Rc<UiTheme> {
+        match (&self.theme, self.parent()) {
+            (Some(theme), _) => Rc::clone(theme),
+            (None, Some(parent)) => parent.borrow().theme(),
+            (None, None) => Rc::new(UiTheme::default()),
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Rc<UiTheme>) {
+        self.theme = Some(theme);
+    }
+
+    pub fn draw(&self, ctx: &mut Context) {
+        let theme = self.theme();
+        let bounds = self.bounds();
+        let padding = vec2d!(theme.padding, theme.padding);
+        draw_rounded_rectangle(
+            ctx,
+            bounds.0 - padding,
+            bounds.1 - bounds.0 + padding * 2.0,
+            theme.corner_radius,
+            theme.panel,
+            Some((theme.border_thickness, theme.panel_border)),
+        );
+        self.elements.iter().for_each(|x| x.draw(ctx));
+    }
+
+    pub fn contains(&self, position: Vector) -> bool {
+        let bounds = self.bounds();
+        bounds.0.x <= position.x
+            && bounds.0.y <= position.y
+            && bounds.1.x >= position.x
+            && bounds.1.y >= position.y
+    }
+
+    /// Hit testing goes from the top down, so only the topmost element under the mouse responds.
+    /// Clicking on the menu's background is still consumed, so it doesn't reach the map.
+    pub fn input_at(&self, position: Vector, state: &mut T) -> bool {
+        self.blur();
+        if !self.contains(position) {
+            return false;
+        }
+        for element in self.elements.iter().rev() {
+            if element.input_at(position, state) {
+                break;
+            }
+        }
+        true
+    }
+
+    pub fn input_start(&self, position: Vector, state: &mut T) -> bool {
+        self.blur();
+        if !self.contains(position) {
+            return false;
+        }
+        for element in self.elements.iter().rev() {
+            if element.input_start(position, state) {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Movement and releases aren't bounds checked, since a drag can leave the menu.
+    /// Every element is told about them, so hover and drag states are always cleared.
+    pub fn input_moved(&self, position: Vector, movement: Vector, state: &mut T) -> bool {
+        self.elements.iter().rev().fold(false, |consumed, element| {
+            element.input_moved(position, movement, state) || consumed
+        })
+    }
+
+    pub fn input_released(&self, position: Vector, state: &mut T) -> bool {
+        self.elements.iter().rev().fold(false, |consumed, element| {
+            element.input_released(position, state) || consumed
+        })
+    }
+
+    pub fn text_input(&self, character: char, state: &mut T) -> bool {
+        self.elements
+            .iter()
+            .rev()
+            .any(|element| element.text_input(character, state))
+    }
+
+    pub fn backspace(&self, state: &mut T) -> bool {
+        self.elements
+            .iter()
+            .rev()
+            .any(|element| element.backspace(state))
+    }
+
+    pub fn blur(&self) {
+        self.elements.iter().for_each(|element| element.blur());
+    }
+
+    /// Whether any element is taking keyboard input, in which case key presses
+    /// shouldn't also trigger actions
+    pub fn is_focused(&self) -> bool {
+        self.elements.iter().any(|element| element.is_focused())
+    }
+
+    pub fn set_position(&mut self, position: Vector) {
+        self.position = position;
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+}
+
+impl<T> Into<UIElement<T>> for Rc<RefCell<Menu<T>>> {
+    fn into(self) -> UIElement<T> {
+        UIElement::Menu(self)
+    }
+}
+
+impl<T> Default for Menu<T> {
+    fn default() -> Self {
+        Self {
+            position: vec2d!(0.0, 0.0),
+            anchor: Anchor::TopLeft,
+            scale: 1.0,
+            viewport: vec2d!(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32),
+            elements: vec![],
+            parent: None,
+            theme: None,
+            z_index: 0,
+        }
+    }
+}